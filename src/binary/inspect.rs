@@ -0,0 +1,416 @@
+//! Byte-level views for the hex dump tool: `xxd` style rows, text
+//! encodings, and numeric readings of the bytes at an offset.
+
+/// Byte order used for grouped hex and multi-byte numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endian {
+    #[default]
+    Big,
+    Little,
+}
+
+impl Endian {
+    pub const ALL: [Endian; 2] = [Endian::Big, Endian::Little];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Endian::Big => "big",
+            Endian::Little => "little",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Endian::Big => "Big-endian",
+            Endian::Little => "Little-endian",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|endian| endian.id() == id)
+    }
+}
+
+/// How pasted text becomes bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+impl TextEncoding {
+    pub const ALL: [TextEncoding; 4] = [
+        TextEncoding::Utf8,
+        TextEncoding::Utf16Le,
+        TextEncoding::Utf16Be,
+        TextEncoding::Latin1,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "utf8",
+            TextEncoding::Utf16Le => "utf16le",
+            TextEncoding::Utf16Be => "utf16be",
+            TextEncoding::Latin1 => "latin1",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf16Le => "UTF-16 LE",
+            TextEncoding::Utf16Be => "UTF-16 BE",
+            TextEncoding::Latin1 => "Latin-1",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|encoding| encoding.id() == id)
+    }
+
+    pub fn encode(self, text: &str) -> Result<Vec<u8>, String> {
+        Ok(match self {
+            TextEncoding::Utf8 => text.as_bytes().to_vec(),
+            TextEncoding::Utf16Le => text.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            TextEncoding::Utf16Be => text.encode_utf16().flat_map(u16::to_be_bytes).collect(),
+            TextEncoding::Latin1 => {
+                let mut bytes = Vec::with_capacity(text.len());
+                for (position, c) in text.chars().enumerate() {
+                    let byte = u8::try_from(u32::from(c)).map_err(|_| {
+                        format!("'{}' at position {} is not in Latin-1", c, position + 1)
+                    })?;
+                    bytes.push(byte);
+                }
+                bytes
+            }
+        })
+    }
+}
+
+/// Shape of a hex dump row
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    pub bytes_per_row: usize,
+    /// Bytes printed together without a space, like `xxd -g`
+    pub group: usize,
+    /// Little-endian reverses the bytes within each group, like `xxd -e`
+    pub endian: Endian,
+}
+
+impl Layout {
+    /// Offsets within a row of `len` bytes, split into groups in display order
+    pub fn groups(&self, len: usize) -> Vec<Vec<usize>> {
+        (0..len)
+            .step_by(self.group.max(1))
+            .map(|start| {
+                let group: Vec<usize> = (start..len.min(start + self.group.max(1))).collect();
+                match self.endian {
+                    Endian::Big => group,
+                    Endian::Little => group.into_iter().rev().collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Width of the hex column of a full row, in characters
+    pub fn hex_width(&self) -> usize {
+        let groups = self.bytes_per_row.div_ceil(self.group.max(1));
+        self.bytes_per_row * 2 + groups.saturating_sub(1)
+    }
+}
+
+/// `xxd` style listing of `bytes`, numbering rows from `start`
+pub fn xxd(bytes: &[u8], start: usize, layout: Layout) -> String {
+    let mut out = String::new();
+    for (row, chunk) in bytes.chunks(layout.bytes_per_row.max(1)).enumerate() {
+        let hex = layout
+            .groups(chunk.len())
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|&i| format!("{:02x}", chunk[i]))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        out.push_str(&format!(
+            "{:08x}: {:width$}  ",
+            start + row * layout.bytes_per_row,
+            hex,
+            width = layout.hex_width()
+        ));
+        out.extend(chunk.iter().map(|&byte| super::printable(byte)));
+        out.push('\n');
+    }
+    out
+}
+
+/// Unsigned integer from the first `width` bytes, if there are that many
+fn read_uint(bytes: &[u8], width: usize, endian: Endian) -> Option<u64> {
+    let bytes = bytes.get(..width)?;
+    let fold = |value: u64, &byte: &u8| value << 8 | u64::from(byte);
+    Some(match endian {
+        Endian::Big => bytes.iter().fold(0, fold),
+        Endian::Little => bytes.iter().rev().fold(0, fold),
+    })
+}
+
+/// Sign-extends the low `width` bytes of `value`
+fn sign_extend(value: u64, width: usize) -> i64 {
+    let shift = 64 - width * 8;
+    ((value << shift) as i64) >> shift
+}
+
+/// Plain notation for everyday magnitudes, exponent notation otherwise
+fn format_float<T: std::fmt::Display + std::fmt::LowerExp + Into<f64> + Copy>(value: T) -> String {
+    let magnitude = value.into().abs();
+    if magnitude == 0.0 || !magnitude.is_finite() || (1e-6..1e16).contains(&magnitude) {
+        value.to_string()
+    } else {
+        format!("{:e}", value)
+    }
+}
+
+/// LEB128 varint at the start of `bytes`: the unsigned and signed readings
+/// and the number of bytes used, or `None` if it is unterminated or too long
+pub fn read_leb128(bytes: &[u8]) -> Option<(u64, i64, usize)> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    for (i, &byte) in bytes.iter().enumerate() {
+        // The tenth byte only has room for bit 63
+        if shift >= 64 || (shift == 63 && byte & 0x7e != 0) {
+            return None;
+        }
+        value |= u64::from(byte & 0x7f) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            let signed = if shift < 64 && byte & 0x40 != 0 {
+                (value | (!0u64 << shift)) as i64
+            } else {
+                value as i64
+            };
+            return Some((value, signed, i + 1));
+        }
+    }
+    None
+}
+
+/// Readings of the bytes at the start of `bytes` as each numeric type, with
+/// `None` where there are too few bytes
+pub fn interpret(bytes: &[u8], endian: Endian) -> Vec<(&'static str, Option<String>)> {
+    let mut readings = Vec::new();
+    for (width, unsigned, signed) in [
+        (1, "u8", "i8"),
+        (2, "u16", "i16"),
+        (4, "u32", "i32"),
+        (8, "u64", "i64"),
+    ] {
+        let value = read_uint(bytes, width, endian);
+        readings.push((
+            unsigned,
+            value.map(|v| format!("{} (0x{:0digits$x})", v, v, digits = width * 2)),
+        ));
+        readings.push((signed, value.map(|v| sign_extend(v, width).to_string())));
+    }
+    readings.push((
+        "f32",
+        read_uint(bytes, 4, endian).map(|v| format_float(f32::from_bits(v as u32))),
+    ));
+    readings.push((
+        "f64",
+        read_uint(bytes, 8, endian).map(|v| format_float(f64::from_bits(v))),
+    ));
+    let varint = read_leb128(bytes);
+    let used = |n: usize| format!("{} byte{}", n, if n == 1 { "" } else { "s" });
+    readings.push((
+        "LEB128",
+        varint.map(|(value, _, n)| format!("{} ({})", value, used(n))),
+    ));
+    readings.push((
+        "SLEB128",
+        varint.map(|(_, value, n)| format!("{} ({})", value, used(n))),
+    ));
+    readings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(bytes: &[u8], endian: Endian, name: &str) -> Option<String> {
+        interpret(bytes, endian)
+            .into_iter()
+            .find(|(label, _)| *label == name)
+            .and_then(|(_, value)| value)
+    }
+
+    #[test]
+    fn leb128() {
+        assert_eq!(read_leb128(&[0x00]), Some((0, 0, 1)));
+        assert_eq!(read_leb128(&[0x02, 0xff]), Some((2, 2, 1)));
+        assert_eq!(read_leb128(&[0xe5, 0x8e, 0x26]), Some((624485, 624485, 3)));
+        // Signed readings extend bit 6 of the last byte
+        assert_eq!(read_leb128(&[0x7f]), Some((127, -1, 1)));
+        assert_eq!(
+            read_leb128(&[0xc0, 0xbb, 0x78]),
+            Some((1973696, -123456, 3))
+        );
+        assert_eq!(read_leb128(&[0x80, 0x7f]), Some((16256, -128, 2)));
+    }
+
+    #[test]
+    fn leb128_limits() {
+        let mut max = vec![0xff; 9];
+        max.push(0x01);
+        assert_eq!(read_leb128(&max), Some((u64::MAX, -1, 10)));
+        let mut min = vec![0x80; 9];
+        min.push(0x01);
+        assert_eq!(read_leb128(&min), Some((1 << 63, i64::MIN, 10)));
+        // Bits past 64 do not fit
+        max[9] = 0x02;
+        assert_eq!(read_leb128(&max), None);
+        max[9] = 0x7f;
+        assert_eq!(read_leb128(&max), None);
+        let mut long = vec![0x80; 10];
+        long.push(0x00);
+        assert_eq!(read_leb128(&long), None);
+        // Unterminated
+        assert_eq!(read_leb128(&[]), None);
+        assert_eq!(read_leb128(&[0x80, 0xff]), None);
+    }
+
+    #[test]
+    fn integers() {
+        let bytes = [0xff, 0xfe, 0x01, 0x80, 0x00, 0x00, 0x00, 0x80];
+        let big = |name| reading(&bytes, Endian::Big, name);
+        assert_eq!(big("u8").as_deref(), Some("255 (0xff)"));
+        assert_eq!(big("i8").as_deref(), Some("-1"));
+        assert_eq!(big("u16").as_deref(), Some("65534 (0xfffe)"));
+        assert_eq!(big("i16").as_deref(), Some("-2"));
+        assert_eq!(big("u32").as_deref(), Some("4294836608 (0xfffe0180)"));
+        assert_eq!(big("i32").as_deref(), Some("-130688"));
+        assert_eq!(
+            big("u64").as_deref(),
+            Some("18446182773023572096 (0xfffe018000000080)")
+        );
+        assert_eq!(big("i64").as_deref(), Some("-561300685979520"));
+        assert_eq!(big("LEB128").as_deref(), Some("32639 (3 bytes)"));
+
+        let little = |name| reading(&bytes, Endian::Little, name);
+        assert_eq!(little("u8").as_deref(), Some("255 (0xff)"));
+        assert_eq!(little("u16").as_deref(), Some("65279 (0xfeff)"));
+        assert_eq!(little("i16").as_deref(), Some("-257"));
+        assert_eq!(little("u32").as_deref(), Some("2147614463 (0x8001feff)"));
+        assert_eq!(little("i32").as_deref(), Some("-2147352833"));
+        assert_eq!(
+            little("u64").as_deref(),
+            Some("9223372039002390271 (0x800000008001feff)")
+        );
+        assert_eq!(little("i64").as_deref(), Some("-9223372034707161345"));
+
+        // Positive values are not extended
+        assert_eq!(
+            reading(&[0x7f, 0xff], Endian::Big, "i16").as_deref(),
+            Some("32767")
+        );
+        assert_eq!(
+            reading(&[0x00, 0x80], Endian::Little, "i16").as_deref(),
+            Some("-32768")
+        );
+        assert_eq!(reading(&[0x01], Endian::Big, "u16"), None);
+        assert_eq!(
+            reading(&[0x01], Endian::Big, "LEB128").as_deref(),
+            Some("1 (1 byte)")
+        );
+    }
+
+    #[test]
+    fn floats() {
+        let one = [0x3f, 0xf0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(reading(&one, Endian::Big, "f64").as_deref(), Some("1"));
+        assert_eq!(reading(&one, Endian::Big, "f32").as_deref(), Some("1.875"));
+        let mut reversed = one;
+        reversed.reverse();
+        assert_eq!(
+            reading(&reversed, Endian::Little, "f64").as_deref(),
+            Some("1")
+        );
+        assert_eq!(
+            reading(&[0x00, 0x00, 0xc0, 0x7f], Endian::Little, "f32").as_deref(),
+            Some("NaN")
+        );
+        assert_eq!(
+            reading(&1e-7f32.to_be_bytes(), Endian::Big, "f32").as_deref(),
+            Some("1e-7")
+        );
+        assert_eq!(
+            reading(&1e20f64.to_le_bytes(), Endian::Little, "f64").as_deref(),
+            Some("1e20")
+        );
+        assert_eq!(reading(&one[..4], Endian::Big, "f64"), None);
+    }
+
+    #[test]
+    fn groups() {
+        let layout = |group, endian| Layout {
+            bytes_per_row: 16,
+            group,
+            endian,
+        };
+        assert_eq!(
+            layout(2, Endian::Big).groups(5),
+            [vec![0, 1], vec![2, 3], vec![4]]
+        );
+        assert_eq!(
+            layout(2, Endian::Little).groups(5),
+            [vec![1, 0], vec![3, 2], vec![4]]
+        );
+        assert_eq!(layout(4, Endian::Little).groups(3), [vec![2, 1, 0]]);
+        assert_eq!(layout(0, Endian::Big).groups(2), [vec![0], vec![1]]);
+        assert!(layout(2, Endian::Big).groups(0).is_empty());
+        assert_eq!(layout(2, Endian::Big).hex_width(), 39);
+        assert_eq!(layout(16, Endian::Big).hex_width(), 32);
+    }
+
+    #[test]
+    fn xxd_rows() {
+        let big = Layout {
+            bytes_per_row: 4,
+            group: 2,
+            endian: Endian::Big,
+        };
+        assert_eq!(
+            xxd(b"ABC\n\x00F", 0x10, big),
+            "00000010: 4142 430a  ABC.\n00000014: 0046       .F\n"
+        );
+        let little = Layout {
+            group: 4,
+            endian: Endian::Little,
+            ..big
+        };
+        assert_eq!(
+            xxd(b"ABCDEF", 0, little),
+            "00000000: 44434241  ABCD\n00000004: 4645      EF\n"
+        );
+        assert_eq!(xxd(b"", 0, little), "");
+    }
+
+    #[test]
+    fn text_encodings() {
+        assert_eq!(TextEncoding::Utf8.encode("é").unwrap(), [0xc3, 0xa9]);
+        assert_eq!(TextEncoding::Utf16Le.encode("é").unwrap(), [0xe9, 0x00]);
+        assert_eq!(
+            TextEncoding::Utf16Be.encode("😀").unwrap(),
+            [0xd8, 0x3d, 0xde, 0x00]
+        );
+        assert_eq!(TextEncoding::Latin1.encode("aÿ").unwrap(), [0x61, 0xff]);
+        assert_eq!(
+            TextEncoding::Latin1.encode("ab€").unwrap_err(),
+            "'€' at position 3 is not in Latin-1"
+        );
+    }
+}
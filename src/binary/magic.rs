@@ -0,0 +1,200 @@
+//! File type detection from leading "magic number" bytes.
+
+/// A file type recognised from its signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileType {
+    pub name: &'static str,
+    pub mime: &'static str,
+    /// Usual extension, without the dot
+    pub extension: &'static str,
+}
+
+const fn file_type(name: &'static str, mime: &'static str, extension: &'static str) -> FileType {
+    FileType {
+        name,
+        mime,
+        extension,
+    }
+}
+
+/// Byte offset, expected bytes and the type they identify. Longer and more
+/// specific signatures come before shorter ones that share a prefix.
+const SIGNATURES: &[(usize, &[u8], FileType)] = &[
+    (
+        0,
+        b"\x89PNG\r\n\x1a\n",
+        file_type("PNG image", "image/png", "png"),
+    ),
+    (
+        0,
+        b"\xff\xd8\xff",
+        file_type("JPEG image", "image/jpeg", "jpg"),
+    ),
+    (0, b"GIF87a", file_type("GIF image", "image/gif", "gif")),
+    (0, b"GIF89a", file_type("GIF image", "image/gif", "gif")),
+    (8, b"WEBP", file_type("WebP image", "image/webp", "webp")),
+    (8, b"WAVE", file_type("WAV audio", "audio/wav", "wav")),
+    (8, b"AVI ", file_type("AVI video", "video/x-msvideo", "avi")),
+    (
+        4,
+        b"ftypavif",
+        file_type("AVIF image", "image/avif", "avif"),
+    ),
+    (
+        4,
+        b"ftypheic",
+        file_type("HEIC image", "image/heic", "heic"),
+    ),
+    (
+        4,
+        b"ftypqt",
+        file_type("QuickTime video", "video/quicktime", "mov"),
+    ),
+    (4, b"ftyp", file_type("MP4 media", "video/mp4", "mp4")),
+    (0, b"II*\x00", file_type("TIFF image", "image/tiff", "tif")),
+    (0, b"MM\x00*", file_type("TIFF image", "image/tiff", "tif")),
+    (
+        0,
+        b"\x00\x00\x01\x00",
+        file_type("ICO icon", "image/x-icon", "ico"),
+    ),
+    (0, b"BM", file_type("BMP image", "image/bmp", "bmp")),
+    (
+        0,
+        b"%PDF-",
+        file_type("PDF document", "application/pdf", "pdf"),
+    ),
+    (
+        0,
+        b"PK\x03\x04",
+        file_type("ZIP archive", "application/zip", "zip"),
+    ),
+    (
+        0,
+        b"\x1f\x8b",
+        file_type("gzip archive", "application/gzip", "gz"),
+    ),
+    (
+        0,
+        b"BZh",
+        file_type("bzip2 archive", "application/x-bzip2", "bz2"),
+    ),
+    (
+        0,
+        b"\xfd7zXZ\x00",
+        file_type("xz archive", "application/x-xz", "xz"),
+    ),
+    (
+        0,
+        b"7z\xbc\xaf\x27\x1c",
+        file_type("7-Zip archive", "application/x-7z-compressed", "7z"),
+    ),
+    (
+        0,
+        b"\x28\xb5\x2f\xfd",
+        file_type("Zstandard archive", "application/zstd", "zst"),
+    ),
+    (
+        0,
+        b"Rar!\x1a\x07",
+        file_type("RAR archive", "application/vnd.rar", "rar"),
+    ),
+    (
+        257,
+        b"ustar",
+        file_type("tar archive", "application/x-tar", "tar"),
+    ),
+    (
+        0,
+        b"\x7fELF",
+        file_type("ELF executable", "application/x-executable", "elf"),
+    ),
+    (
+        0,
+        b"MZ",
+        file_type(
+            "Windows executable",
+            "application/vnd.microsoft.portable-executable",
+            "exe",
+        ),
+    ),
+    (
+        0,
+        b"\xcf\xfa\xed\xfe",
+        file_type("Mach-O binary", "application/x-mach-binary", "macho"),
+    ),
+    (
+        0,
+        b"\xca\xfe\xba\xbe",
+        file_type(
+            "Java class or Mach-O universal binary",
+            "application/java-vm",
+            "class",
+        ),
+    ),
+    (
+        0,
+        b"\x00asm",
+        file_type("WebAssembly module", "application/wasm", "wasm"),
+    ),
+    (
+        0,
+        b"SQLite format 3\x00",
+        file_type("SQLite database", "application/vnd.sqlite3", "sqlite"),
+    ),
+    (0, b"ID3", file_type("MP3 audio", "audio/mpeg", "mp3")),
+    (0, b"OggS", file_type("Ogg media", "audio/ogg", "ogg")),
+    (0, b"fLaC", file_type("FLAC audio", "audio/flac", "flac")),
+    (
+        0,
+        b"\x1a\x45\xdf\xa3",
+        file_type("WebM/Matroska video", "video/webm", "webm"),
+    ),
+    (0, b"wOFF", file_type("WOFF font", "font/woff", "woff")),
+    (0, b"wOF2", file_type("WOFF2 font", "font/woff2", "woff2")),
+    (0, b"OTTO", file_type("OpenType font", "font/otf", "otf")),
+    (
+        0,
+        b"\x00\x01\x00\x00\x00",
+        file_type("TrueType font", "font/ttf", "ttf"),
+    ),
+    (
+        0,
+        b"-----BEGIN CERTIFICATE-----",
+        file_type("PEM certificate", "application/x-pem-file", "pem"),
+    ),
+    (
+        0,
+        b"-----BEGIN ",
+        file_type("PEM data", "application/x-pem-file", "pem"),
+    ),
+    (
+        0,
+        b"\x30\x82",
+        file_type(
+            "DER (ASN.1) data, e.g. a certificate or key",
+            "application/pkix-cert",
+            "der",
+        ),
+    ),
+    (0, b"<svg", file_type("SVG image", "image/svg+xml", "svg")),
+    (
+        0,
+        b"<?xml",
+        file_type("XML document", "application/xml", "xml"),
+    ),
+];
+
+/// Identifies `bytes` from its signature, if it has a known one
+pub fn detect(bytes: &[u8]) -> Option<FileType> {
+    // RIFF containers are told apart by the form type at offset 8
+    let riff = bytes.starts_with(b"RIFF");
+    SIGNATURES
+        .iter()
+        .find(|(offset, signature, _)| {
+            let form_type = *offset == 8;
+            bytes.get(*offset..*offset + signature.len()) == Some(*signature)
+                && (!form_type || riff)
+        })
+        .map(|(_, _, file_type)| *file_type)
+}
@@ -0,0 +1,63 @@
+//! Helpers for showing and identifying binary data.
+
+pub mod inspect;
+pub mod magic;
+
+/// Bytes shown per hex dump line
+pub const BYTES_PER_LINE: usize = 16;
+
+/// `hexdump -C` style listing: offset, hex bytes in two groups of eight and
+/// the printable ASCII characters
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (line, chunk) in bytes.chunks(BYTES_PER_LINE).enumerate() {
+        out.push_str(&format!("{:08x} ", line * BYTES_PER_LINE));
+        for i in 0..BYTES_PER_LINE {
+            if i % 8 == 0 {
+                out.push(' ');
+            }
+            match chunk.get(i) {
+                Some(byte) => out.push_str(&format!("{:02x} ", byte)),
+                None => out.push_str("   "),
+            }
+        }
+        out.push_str(" |");
+        out.extend(chunk.iter().map(|&byte| printable(byte)));
+        out.push_str("|\n");
+    }
+    out
+}
+
+/// The byte as an ASCII character, or `.` when it has no visible form
+pub fn printable(byte: u8) -> char {
+    if byte.is_ascii_graphic() || byte == b' ' {
+        byte as char
+    } else {
+        '.'
+    }
+}
+
+/// Whether `bytes` reads as text: valid UTF-8 without control characters
+/// other than whitespace
+pub fn is_text(bytes: &[u8]) -> bool {
+    std::str::from_utf8(bytes).is_ok_and(|text| {
+        !text
+            .chars()
+            .any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t'))
+    })
+}
+
+/// Byte count with a binary unit, e.g. "1.5 KiB"
+pub fn format_size(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} byte{}", bytes, if bytes == 1 { "" } else { "s" });
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
@@ -0,0 +1,461 @@
+use crate::components::tools::registry::Tool;
+use crate::components::tools::shared::*;
+use crate::convert::csv::{self, CsvOptions, Table, DELIMITERS, QUOTES};
+use crate::convert::Diagnostic;
+use crate::json::lenient::{self, Dialect};
+use crate::types::ToolQuery;
+use dioxus::prelude::*;
+use std::rc::Rc;
+
+/// Rows shown in the table preview
+const PREVIEW_ROWS: usize = 200;
+
+/// Output, table preview and notes for the current input
+#[derive(Debug, Clone, PartialEq)]
+struct Converted {
+    output: String,
+    table: Table,
+    options: CsvOptions,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// The selected character for `id`, or `None` for "auto"
+fn choice(choices: &[(&str, char, &str)], id: &str) -> Option<char> {
+    choices
+        .iter()
+        .find(|(c, _, _)| *c == id)
+        .map(|(_, c, _)| *c)
+}
+
+fn choice_modes(choices: &[(&str, char, &str)]) -> Vec<(String, String)> {
+    std::iter::once(("auto".to_string(), "Auto-detect".to_string()))
+        .chain(
+            choices
+                .iter()
+                .map(|(id, _, label)| (id.to_string(), label.to_string())),
+        )
+        .collect()
+}
+
+/// Converts in the direction of `mode`; `None` while the input is empty
+fn run(
+    input: &str,
+    mode: &str,
+    delimiter: &str,
+    quote: &str,
+    header: &str,
+    infer_types: bool,
+) -> Result<Option<Rc<Converted>>, String> {
+    if input.trim().is_empty() {
+        return Ok(None);
+    }
+
+    if mode == "to-csv" {
+        let (value, _) = lenient::parse(input, Dialect::Strict).map_err(|e| e.message)?;
+        let options = CsvOptions {
+            delimiter: choice(&DELIMITERS, delimiter).unwrap_or(','),
+            quote: choice(&QUOTES, quote).unwrap_or('"'),
+            has_header: true,
+        };
+        let conversion = csv::from_json(&value, &options)?;
+        let table = csv::parse(&conversion.output, &options)?;
+        return Ok(Some(Rc::new(Converted {
+            output: conversion.output,
+            table,
+            options,
+            diagnostics: conversion.diagnostics,
+        })));
+    }
+
+    let detected = csv::detect(input);
+    let options = CsvOptions {
+        delimiter: choice(&DELIMITERS, delimiter).unwrap_or(detected.delimiter),
+        quote: choice(&QUOTES, quote).unwrap_or(detected.quote),
+        has_header: match header {
+            "yes" => true,
+            "no" => false,
+            _ => detected.has_header,
+        },
+    };
+    let table = csv::parse(input, &options)?;
+    let output = serde_json::to_string_pretty(&csv::to_json(&table, infer_types))
+        .map_err(|e| e.to_string())?;
+    Ok(Some(Rc::new(Converted {
+        output,
+        table,
+        options,
+        diagnostics: Vec::new(),
+    })))
+}
+
+/// Sortable preview of the first [`PREVIEW_ROWS`] rows
+fn table_preview(table: &Table, sort: Signal<Option<(usize, bool)>>) -> Element {
+    let mut sort = sort;
+    let order = sort().filter(|(column, _)| *column < table.headers.len());
+    let mut rows: Vec<&Vec<String>> = table.rows.iter().collect();
+    if let Some((column, ascending)) = order {
+        rows.sort_by(|a, b| {
+            let ordering = csv::compare_cells(&a[column], &b[column]);
+            if ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+    }
+    rows.truncate(PREVIEW_ROWS);
+
+    rsx! {
+        div { class: "max-h-96 overflow-auto bg-ctp-surface0 border border-ctp-surface2 rounded-md",
+            table { class: "min-w-full text-sm font-mono",
+                thead { class: "sticky top-0 bg-ctp-surface1",
+                    tr {
+                        for (i , header) in table.headers.iter().enumerate() {
+                            th { key: "{i}", class: "px-3 py-2 text-left font-medium text-ctp-text whitespace-nowrap",
+                                button {
+                                    class: "hover:text-ctp-blue",
+                                    onclick: move |_| {
+                                        let ascending = !matches!(sort(), Some((column, true)) if column == i);
+                                        sort.set(Some((i, ascending)));
+                                    },
+                                    "{header}"
+                                    match order {
+                                        Some((column, true)) if column == i => " ▲",
+                                        Some((column, false)) if column == i => " ▼",
+                                        _ => "",
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                tbody { class: "divide-y divide-ctp-surface1",
+                    for (i , row) in rows.into_iter().enumerate() {
+                        tr { key: "{i}",
+                            for (j , cell) in row.iter().enumerate() {
+                                td { key: "{j}", class: "px-3 py-1.5 text-ctp-subtext1 whitespace-pre", "{cell}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// CSV Converter component
+#[component]
+pub fn CsvConverter(
+    input: Signal<String>,
+    mode: Signal<String>,
+    delimiter: Signal<String>,
+    quote: Signal<String>,
+    header: Signal<String>,
+    infer_types: Signal<bool>,
+) -> Element {
+    let mut input = input;
+    let mut mode = mode;
+    let mut delimiter = delimiter;
+    let mut quote = quote;
+    let mut header = header;
+    let mut infer_types = infer_types;
+    let sort = use_signal(|| None::<(usize, bool)>);
+
+    let result = use_memo(move || {
+        run(
+            &input(),
+            &mode(),
+            &delimiter(),
+            &quote(),
+            &header(),
+            infer_types(),
+        )
+    });
+
+    let (converted, error) = match result() {
+        Ok(converted) => (converted, String::new()),
+        Err(e) => (None, e),
+    };
+    let output = converted
+        .as_ref()
+        .map(|c| c.output.clone())
+        .unwrap_or_default();
+    let to_csv = mode() == "to-csv";
+    let (source, target) = if to_csv {
+        ("JSON", "CSV")
+    } else {
+        ("CSV", "JSON")
+    };
+
+    let swap = {
+        let output = output.clone();
+        move |_| {
+            if !output.is_empty() {
+                input.set(output.clone());
+            }
+            mode.set(if to_csv { "to-json" } else { "to-csv" }.to_string());
+        }
+    };
+
+    let clear_all = move |_| input.set(String::new());
+
+    let left_content = rsx! {
+        InputSection {
+            label: format!("Input {}", source),
+            helper_text: Some(
+                if to_csv {
+                    "An array of objects; nested objects become dotted columns".to_string()
+                } else {
+                    "CSV or TSV; dotted column names become nested objects".to_string()
+                },
+            ),
+            input: rsx! {
+                ToolTextarea {
+                    value: input(),
+                    placeholder: format!("Paste {} here...", source),
+                    rows: Some(10),
+                    oninput: Some(EventHandler::new(move |event: FormEvent| input.set(event.value()))),
+                }
+            },
+        }
+    };
+
+    let right_content = rsx! {
+        OutputSection {
+            label: format!("{} Output", target),
+            helper_text: converted.as_ref().map(|c| format!("Read as {}", c.options.describe())),
+            copy_button: if !output.is_empty() { Some(rsx! {
+                CopyButton { text: output.clone() }
+            }) } else { None },
+            output: rsx! {
+                ToolTextarea {
+                    value: output.clone(),
+                    placeholder: format!("{} will appear here...", target),
+                    rows: Some(10),
+                    readonly: Some(true),
+                }
+            },
+        }
+    };
+
+    let actions = rsx! {
+        ActionButton {
+            text: "Swap".to_string(),
+            onclick: swap,
+            variant: Some("secondary".to_string()),
+        }
+        ActionButton {
+            text: "Clear All".to_string(),
+            onclick: clear_all,
+            variant: Some("secondary".to_string()),
+        }
+    };
+
+    let preview = converted.map(|converted| {
+        let table = &converted.table;
+        let mut helper = format!(
+            "{} row{} × {} column{} · click a column to sort",
+            table.rows.len(),
+            if table.rows.len() == 1 { "" } else { "s" },
+            table.headers.len(),
+            if table.headers.len() == 1 { "" } else { "s" },
+        );
+        if table.rows.len() > PREVIEW_ROWS {
+            helper = format!("First {} of {}", PREVIEW_ROWS, helper);
+        }
+        if table.ragged > 0 {
+            helper.push_str(&format!(
+                " · {} row{} padded to the header width",
+                table.ragged,
+                if table.ragged == 1 { "" } else { "s" }
+            ));
+        }
+        let notes: Vec<String> = converted
+            .diagnostics
+            .iter()
+            .map(Diagnostic::describe)
+            .collect();
+
+        rsx! {
+            if !notes.is_empty() {
+                div { class: "px-4 py-3 text-sm text-ctp-yellow bg-ctp-surface0 border border-ctp-yellow/50 rounded-md",
+                    ul { class: "list-disc pl-5 space-y-0.5",
+                        for (i , note) in notes.into_iter().enumerate() {
+                            li { key: "{i}", class: "break-all", "{note}" }
+                        }
+                    }
+                }
+            }
+            OutputSection {
+                label: "Table Preview".to_string(),
+                helper_text: Some(helper),
+                output: table_preview(table, sort),
+            }
+        }
+    });
+
+    rsx! {
+        div { class: "space-y-4",
+            ModeSelector {
+                current_mode: mode(),
+                modes: vec![
+                    ("to-json".to_string(), "CSV → JSON".to_string()),
+                    ("to-csv".to_string(), "JSON → CSV".to_string()),
+                ],
+                on_change: move |new_mode| mode.set(new_mode),
+            }
+
+            div { class: "flex flex-wrap items-end justify-center gap-6",
+                ModeSelector {
+                    current_mode: delimiter(),
+                    modes: choice_modes(&DELIMITERS),
+                    on_change: move |id| delimiter.set(id),
+                    label: Some("Delimiter".to_string()),
+                }
+                ModeSelector {
+                    current_mode: quote(),
+                    modes: choice_modes(&QUOTES),
+                    on_change: move |id| quote.set(id),
+                    label: Some("Quotes".to_string()),
+                }
+                if !to_csv {
+                    ModeSelector {
+                        current_mode: header(),
+                        modes: vec![
+                            ("auto".to_string(), "Auto-detect".to_string()),
+                            ("yes".to_string(), "Yes".to_string()),
+                            ("no".to_string(), "No".to_string()),
+                        ],
+                        on_change: move |id| header.set(id),
+                        label: Some("Header Row".to_string()),
+                    }
+                    ToolCheckbox {
+                        label: "Infer numbers & booleans".to_string(),
+                        checked: infer_types(),
+                        on_change: move |on| infer_types.set(on),
+                    }
+                }
+            }
+
+            ToolGrid {
+                left_content,
+                right_content,
+                actions: Some(actions),
+                error_message: Some(error),
+            }
+
+            {preview}
+        }
+    }
+}
+
+/// Signals owned by the CSV tab
+#[derive(Clone, Copy, PartialEq)]
+pub struct CsvConverterState {
+    input: Signal<String>,
+    mode: Signal<String>,
+    delimiter: Signal<String>,
+    quote: Signal<String>,
+    header: Signal<String>,
+    infer_types: Signal<bool>,
+}
+
+/// Registry entry for [`CsvConverter`]
+pub struct CsvConverterTool;
+
+impl Tool for CsvConverterTool {
+    type State = CsvConverterState;
+
+    fn id(&self) -> &'static str {
+        "csv"
+    }
+
+    fn icon(&self) -> &'static str {
+        "▦"
+    }
+
+    fn title(&self) -> &'static str {
+        "CSV"
+    }
+
+    fn subtitle(&self) -> &'static str {
+        "CSV · TSV ⇄ JSON"
+    }
+
+    fn create_state(&self) -> Self::State {
+        CsvConverterState {
+            input: Signal::new(String::new()),
+            mode: Signal::new("to-json".to_string()),
+            delimiter: Signal::new("auto".to_string()),
+            quote: Signal::new("auto".to_string()),
+            header: Signal::new("auto".to_string()),
+            infer_types: Signal::new(true),
+        }
+    }
+
+    fn render(&self, state: Self::State) -> Element {
+        rsx! {
+            CsvConverter {
+                input: state.input,
+                mode: state.mode,
+                delimiter: state.delimiter,
+                quote: state.quote,
+                header: state.header,
+                infer_types: state.infer_types,
+            }
+        }
+    }
+
+    fn apply_query(&self, mut state: Self::State, query: &ToolQuery) {
+        let mode = match query.get("mode") {
+            Some("to-csv") => "to-csv",
+            _ => "to-json",
+        };
+        let delimiter = query
+            .get("delimiter")
+            .filter(|id| choice(&DELIMITERS, id).is_some())
+            .unwrap_or("auto");
+        let quote = query
+            .get("quote")
+            .filter(|id| choice(&QUOTES, id).is_some())
+            .unwrap_or("auto");
+        let header = query
+            .get("header")
+            .filter(|id| matches!(*id, "yes" | "no"))
+            .unwrap_or("auto");
+        state
+            .input
+            .set(query.get("input").unwrap_or_default().to_string());
+        state.mode.set(mode.to_string());
+        state.delimiter.set(delimiter.to_string());
+        state.quote.set(quote.to_string());
+        state.header.set(header.to_string());
+        state
+            .infer_types
+            .set(query.get("infer").is_none_or(|v| v == "1"));
+    }
+
+    fn to_query(&self, state: Self::State) -> ToolQuery {
+        let non_default = |value: String, default: &str| {
+            if value == default {
+                String::new()
+            } else {
+                value
+            }
+        };
+        let flag = |on: bool| if on { "1" } else { "0" };
+        ToolQuery::default()
+            .with("input", state.input.peek().clone())
+            .with("mode", non_default(state.mode.peek().clone(), "to-json"))
+            .with(
+                "delimiter",
+                non_default(state.delimiter.peek().clone(), "auto"),
+            )
+            .with("quote", non_default(state.quote.peek().clone(), "auto"))
+            .with("header", non_default(state.header.peek().clone(), "auto"))
+            .with(
+                "infer",
+                non_default(flag(*state.infer_types.peek()).to_string(), "1"),
+            )
+    }
+}
@@ -0,0 +1,752 @@
+use crate::binary::{format_size, hex_dump, is_text, magic};
+use crate::components::tools::registry::Tool;
+use crate::components::tools::shared::*;
+use crate::encoding::{self, hex, percent, Encoding, Options};
+use crate::types::ToolQuery;
+use base64::{engine::general_purpose, Engine as _};
+use dioxus::prelude::*;
+
+/// Bytes of a binary decode shown in the hex preview
+const PREVIEW_BYTES: usize = 256;
+
+/// MIME type for bytes with no recognisable signature
+const OCTET_STREAM: &str = "application/octet-stream";
+
+/// Encoding settings chosen in the UI
+#[derive(Clone, Copy, PartialEq)]
+struct Settings {
+    encoding: Encoding,
+    options: Options,
+    /// Base64 only: wrap the output in a `data:` URI, which ignores line wrapping
+    data_uri: bool,
+}
+
+/// Result of encoding or decoding the current input
+#[derive(Clone, PartialEq, Default)]
+struct Converted {
+    /// Encoded text, decoded text, or a hex preview of binary data
+    output: String,
+    /// Decoded bytes when they are not text
+    binary: Option<Vec<u8>>,
+    /// What was detected about the input
+    note: String,
+}
+
+/// MIME type of uploaded bytes, from their signature or content
+fn file_mime(bytes: &[u8]) -> &'static str {
+    match magic::detect(bytes) {
+        Some(file_type) => file_type.mime,
+        None if is_text(bytes) => "text/plain",
+        None => OCTET_STREAM,
+    }
+}
+
+fn encode_bytes(bytes: &[u8], mime: &str, settings: Settings) -> Result<String, String> {
+    if settings.encoding == Encoding::Base64 && settings.data_uri {
+        let options = Options {
+            wrap: false,
+            ..settings.options
+        };
+        let encoded = encoding::encode(Encoding::Base64, bytes, &options)?;
+        return Ok(format!("data:{};base64,{}", mime, encoded));
+    }
+    encoding::encode(settings.encoding, bytes, &settings.options)
+}
+
+/// Splits a `data:` URI into its MIME type and Base64 payload
+fn strip_data_uri(text: &str) -> Result<(Option<&str>, &str), String> {
+    let Some(rest) = text.trim_start().strip_prefix("data:") else {
+        return Ok((None, text));
+    };
+    let Some((header, payload)) = rest.split_once(',') else {
+        return Err("Decode error: data: URI has no comma before its payload".to_string());
+    };
+    let Some(mime) = header.strip_suffix(";base64") else {
+        return Err("Decode error: only base64 data: URIs can be decoded".to_string());
+    };
+    let mime = mime.split(';').next().unwrap_or_default();
+    Ok(((!mime.is_empty()).then_some(mime), payload))
+}
+
+/// Decodes `text`, accepting `data:` URIs for Base64. Returns the bytes, the
+/// URI's MIME type if any, and what the decoder detected.
+fn decode_text(
+    text: &str,
+    encoding: Encoding,
+) -> Result<(Vec<u8>, Option<String>, String), String> {
+    let (mime, text) = match encoding {
+        Encoding::Base64 => strip_data_uri(text)?,
+        _ => (None, text),
+    };
+    let decoded = encoding::decode(encoding, text)?;
+    Ok((decoded.bytes, mime.map(str::to_string), decoded.note))
+}
+
+/// Type name, MIME type and extension for decoded bytes, preferring the
+/// signature over a `data:` URI's claim
+fn describe_binary(bytes: &[u8], uri_mime: Option<&str>) -> (String, String, String) {
+    match magic::detect(bytes) {
+        Some(file_type) => (
+            file_type.name.to_string(),
+            file_type.mime.to_string(),
+            file_type.extension.to_string(),
+        ),
+        None => (
+            "Unrecognised binary data".to_string(),
+            uri_mime.unwrap_or(OCTET_STREAM).to_string(),
+            "bin".to_string(),
+        ),
+    }
+}
+
+/// Encodes the text or uploaded file, or decodes `text`, according to `mode`
+/// ("encode" or "decode")
+fn convert(mode: &str, text: &str, file: Option<&UploadedFile>, settings: Settings) -> Converted {
+    let failed = |message: String| Converted {
+        output: message,
+        ..Converted::default()
+    };
+
+    if mode == "encode" {
+        if let Some(file) = file {
+            let mime = file_mime(&file.bytes);
+            return match encode_bytes(&file.bytes, mime, settings) {
+                Ok(output) => Converted {
+                    output,
+                    binary: None,
+                    note: format!(
+                        "Encoded {} ({}, {})",
+                        file.name,
+                        format_size(file.bytes.len()),
+                        mime
+                    ),
+                },
+                Err(e) => failed(e),
+            };
+        }
+    }
+    if text.trim().is_empty() {
+        return Converted::default();
+    }
+
+    if mode == "encode" {
+        return match encode_bytes(text.as_bytes(), "text/plain;charset=utf-8", settings) {
+            Ok(output) => Converted {
+                output,
+                ..Converted::default()
+            },
+            Err(e) => failed(e),
+        };
+    }
+    match decode_text(text, settings.encoding) {
+        Ok((bytes, _, note)) if is_text(&bytes) => Converted {
+            output: String::from_utf8(bytes).unwrap_or_default(),
+            binary: None,
+            note,
+        },
+        Ok((bytes, uri_mime, note)) => {
+            let (name, _, _) = describe_binary(&bytes, uri_mime.as_deref());
+            let summary = format!("{}, {}", name, format_size(bytes.len()));
+            Converted {
+                output: hex_dump(&bytes[..bytes.len().min(PREVIEW_BYTES)]),
+                note: if note.is_empty() {
+                    summary
+                } else {
+                    format!("{}: {}", note, summary)
+                },
+                binary: Some(bytes),
+            }
+        }
+        Err(e) => failed(e),
+    }
+}
+
+/// Hint for the decode input box
+fn decode_hint(encoding: Encoding) -> &'static str {
+    match encoding {
+        Encoding::Base64 => {
+            "Standard or URL-safe, with or without padding, line breaks or a data: prefix"
+        }
+        Encoding::Base32 => "RFC 4648, any case, padding optional",
+        Encoding::Base32Crockford => {
+            "Any case; hyphens are ignored and I, L and O read as 1, 1 and 0"
+        }
+        Encoding::Base58 => "Bitcoin alphabet; leading 1s are zero bytes",
+        Encoding::Base85 => "RFC 1924 alphabet",
+        Encoding::Ascii85 => "With or without the <~ ~> delimiters",
+        Encoding::Z85 => "ZeroMQ alphabet, a multiple of 5 characters",
+        Encoding::Hex => "Separators, whitespace and 0x or \\x prefixes are ignored",
+        Encoding::Percent => "%XX escapes; + is left as is",
+        Encoding::Html => "Named, decimal and hex character references",
+        Encoding::QuotedPrintable => "=XX escapes and soft line breaks",
+    }
+}
+
+/// Multi-format encoder/decoder component
+#[component]
+pub fn EncodingWorkbench(
+    input: Signal<String>,
+    output: Signal<String>,
+    mode: Signal<String>,
+    encoding: Signal<String>,
+    alphabet: Signal<String>,
+    padding: Signal<bool>,
+    wrap: Signal<bool>,
+    data_uri: Signal<bool>,
+    hex_separator: Signal<String>,
+    uppercase: Signal<bool>,
+    percent_mode: Signal<String>,
+    html_ascii: Signal<bool>,
+    file: Signal<Option<UploadedFile>>,
+    binary: Signal<Option<Vec<u8>>>,
+    note: Signal<String>,
+) -> Element {
+    let mut input = input;
+    let mut output = output;
+    let mut mode = mode;
+    let mut encoding = encoding;
+    let mut alphabet = alphabet;
+    let mut padding = padding;
+    let mut wrap = wrap;
+    let mut data_uri = data_uri;
+    let mut hex_separator = hex_separator;
+    let mut uppercase = uppercase;
+    let mut percent_mode = percent_mode;
+    let mut html_ascii = html_ascii;
+    let mut file = file;
+    let mut binary = binary;
+    let mut note = note;
+
+    let mut process = move |_| {
+        let settings = Settings {
+            encoding: Encoding::from_id(&encoding()).unwrap_or_default(),
+            options: Options {
+                url_safe: alphabet() == "url",
+                padding: padding(),
+                wrap: wrap(),
+                hex_separator: hex::Separator::from_id(&hex_separator()).unwrap_or_default(),
+                hex_uppercase: uppercase(),
+                percent_mode: percent::Mode::from_id(&percent_mode()).unwrap_or_default(),
+                html_ascii_only: html_ascii(),
+            },
+            data_uri: data_uri(),
+        };
+        let converted = convert(&mode(), &input(), file().as_ref(), settings);
+        output.set(converted.output);
+        binary.set(converted.binary);
+        note.set(converted.note);
+    };
+
+    let clear_all = move |_| {
+        input.set(String::new());
+        output.set(String::new());
+        file.set(None);
+        binary.set(None);
+        note.set(String::new());
+    };
+
+    let encode_file = move |upload: UploadedFile| {
+        input.set(String::new());
+        file.set(Some(upload));
+        process(());
+    };
+
+    let modes = vec![
+        ("encode".to_string(), "Encode".to_string()),
+        ("decode".to_string(), "Decode".to_string()),
+    ];
+    let encodings: Vec<(String, String)> = Encoding::ALL
+        .iter()
+        .map(|e| (e.id().to_string(), e.label().to_string()))
+        .collect();
+
+    let current = Encoding::from_id(&encoding()).unwrap_or_default();
+    let name = current.label();
+    let encoding_mode = mode() == "encode";
+    let uploaded = if encoding_mode { file() } else { None };
+
+    let left_content = rsx! {
+        InputSection {
+            label: if encoding_mode { "Text or File to Encode".to_string() } else { format!("{} to Decode", name) },
+            helper_text: Some(
+                if encoding_mode {
+                    "Enter plain text, or upload a file to encode its bytes".to_string()
+                } else {
+                    decode_hint(current).to_string()
+                },
+            ),
+            input: rsx! {
+                div { class: "space-y-3",
+                    ToolTextarea {
+                        value: input(),
+                        placeholder: if encoding_mode { "Enter text to encode...".to_string() } else { format!("Enter {} to decode...", name) },
+                        rows: Some(6),
+                        oninput: Some(
+                            EventHandler::new(move |event: FormEvent| {
+                                input.set(event.value());
+                                file.set(None);
+                                process(());
+                            }),
+                        ),
+                    }
+                    if encoding_mode {
+                        FileDropZone {
+                            label: "Or upload a file to encode".to_string(),
+                            on_file: encode_file,
+                        }
+                    }
+                    if let Some(upload) = uploaded {
+                        div { class: "flex items-center justify-between gap-3 px-3 py-2 text-xs text-ctp-subtext1 bg-ctp-surface0 border border-ctp-surface2 rounded-md",
+                            span { class: "break-all",
+                                "{upload.name} · {format_size(upload.bytes.len())} · {file_mime(&upload.bytes)}"
+                            }
+                            button {
+                                class: "text-ctp-red hover:underline",
+                                onclick: move |_| {
+                                    file.set(None);
+                                    process(());
+                                },
+                                "Remove"
+                            }
+                        }
+                    }
+                }
+            },
+        }
+    };
+
+    let decoded_binary = if encoding_mode { None } else { binary() };
+
+    let right_content = match decoded_binary {
+        Some(bytes) => {
+            let (kind, mime, _) = describe_binary(&bytes, None);
+            let image_uri = mime.starts_with("image/").then(|| {
+                format!(
+                    "data:{};base64,{}",
+                    mime,
+                    general_purpose::STANDARD.encode(&bytes)
+                )
+            });
+            rsx! {
+                OutputSection {
+                    label: "Decoded Binary Data".to_string(),
+                    helper_text: Some(
+                        if bytes.len() > PREVIEW_BYTES {
+                            format!(
+                                "{} · {} · first {} bytes shown",
+                                kind,
+                                format_size(bytes.len()),
+                                PREVIEW_BYTES,
+                            )
+                        } else {
+                            format!("{} · {}", kind, format_size(bytes.len()))
+                        },
+                    ),
+                    output: rsx! {
+                        div { class: "space-y-3",
+                            ToolTextarea {
+                                value: output(),
+                                placeholder: String::new(),
+                                rows: Some(6),
+                                readonly: Some(true),
+                            }
+                            if let Some(uri) = image_uri {
+                                img {
+                                    src: "{uri}",
+                                    alt: "Decoded image",
+                                    class: "max-h-40 object-contain border border-ctp-surface2 rounded-md bg-white",
+                                }
+                            }
+                        }
+                    },
+                }
+            }
+        }
+        None => rsx! {
+            OutputSection {
+                label: if encoding_mode { format!("{} Output", name) } else { "Decoded Text".to_string() },
+                helper_text: Some(
+                    if encoding_mode {
+                        format!("{} encoded result", name)
+                    } else {
+                        format!("Plain text decoded from {}", name)
+                    },
+                ),
+                copy_button: if !output().is_empty() { Some(rsx! {
+                    CopyButton { text: output() }
+                }) } else { None },
+                output: rsx! {
+                    ToolTextarea {
+                        value: output(),
+                        placeholder: "Output will appear here...".to_string(),
+                        rows: Some(6),
+                        readonly: Some(true),
+                    }
+                },
+            }
+        },
+    };
+
+    let download = move |_| {
+        if let Some(bytes) = binary() {
+            let uri_mime = strip_data_uri(&input())
+                .ok()
+                .and_then(|(mime, _)| mime.map(str::to_string));
+            let (_, mime, extension) = describe_binary(&bytes, uri_mime.as_deref());
+            download_bytes(&format!("decoded.{}", extension), &mime, &bytes);
+        }
+    };
+
+    let actions = rsx! {
+        if !encoding_mode && binary().is_some() {
+            ActionButton {
+                text: "Download File".to_string(),
+                onclick: download,
+                variant: Some("primary".to_string()),
+            }
+        }
+        ActionButton {
+            text: "Clear All".to_string(),
+            onclick: clear_all,
+            variant: Some("secondary".to_string()),
+        }
+    };
+
+    let options = match current {
+        Encoding::Base64 => rsx! {
+            ModeSelector {
+                current_mode: alphabet(),
+                modes: vec![
+                    ("standard".to_string(), "Standard".to_string()),
+                    ("url".to_string(), "URL-safe".to_string()),
+                ],
+                on_change: move |id| {
+                    alphabet.set(id);
+                    process(());
+                },
+                label: Some("Alphabet".to_string()),
+            }
+            ToolCheckbox {
+                label: "Padding".to_string(),
+                checked: padding(),
+                on_change: move |on| {
+                    padding.set(on);
+                    process(());
+                },
+            }
+            ToolCheckbox {
+                label: "Wrap at 76 columns (MIME)".to_string(),
+                checked: wrap(),
+                on_change: move |on| {
+                    wrap.set(on);
+                    process(());
+                },
+            }
+            ToolCheckbox {
+                label: "data: URI".to_string(),
+                checked: data_uri(),
+                on_change: move |on| {
+                    data_uri.set(on);
+                    process(());
+                },
+            }
+        },
+        Encoding::Base32 => rsx! {
+            ToolCheckbox {
+                label: "Padding".to_string(),
+                checked: padding(),
+                on_change: move |on| {
+                    padding.set(on);
+                    process(());
+                },
+            }
+        },
+        Encoding::Hex => rsx! {
+            ModeSelector {
+                current_mode: hex_separator(),
+                modes: hex::Separator::ALL
+                    .iter()
+                    .map(|s| (s.id().to_string(), s.label().to_string()))
+                    .collect::<Vec<_>>(),
+                on_change: move |id| {
+                    hex_separator.set(id);
+                    process(());
+                },
+                label: Some("Separator".to_string()),
+            }
+            ToolCheckbox {
+                label: "Uppercase".to_string(),
+                checked: uppercase(),
+                on_change: move |on| {
+                    uppercase.set(on);
+                    process(());
+                },
+            }
+        },
+        Encoding::Percent => rsx! {
+            ModeSelector {
+                current_mode: percent_mode(),
+                modes: percent::Mode::ALL
+                    .iter()
+                    .map(|m| (m.id().to_string(), m.label().to_string()))
+                    .collect::<Vec<_>>(),
+                on_change: move |id| {
+                    percent_mode.set(id);
+                    process(());
+                },
+                label: Some("Escape".to_string()),
+            }
+        },
+        Encoding::Html => rsx! {
+            ToolCheckbox {
+                label: "Escape all non-ASCII characters".to_string(),
+                checked: html_ascii(),
+                on_change: move |on| {
+                    html_ascii.set(on);
+                    process(());
+                },
+            }
+        },
+        _ => rsx! {},
+    };
+    let has_options = matches!(
+        current,
+        Encoding::Base64 | Encoding::Base32 | Encoding::Hex | Encoding::Percent | Encoding::Html
+    );
+
+    rsx! {
+        div { class: "space-y-4",
+            // Mode selection
+            ModeSelector {
+                current_mode: mode(),
+                modes,
+                on_change: move |new_mode| {
+                    mode.set(new_mode);
+                    process(());
+                },
+            }
+
+            ModeSelector {
+                current_mode: current.id().to_string(),
+                modes: encodings,
+                on_change: move |id| {
+                    encoding.set(id);
+                    process(());
+                },
+                label: Some("Encoding".to_string()),
+            }
+
+            if encoding_mode && has_options {
+                div { class: "flex flex-wrap items-end justify-center gap-6", {options} }
+            }
+
+            // Main tool grid
+            ToolGrid {
+                left_content,
+                right_content,
+                actions: Some(actions),
+                success_message: Some(note()),
+            }
+        }
+    }
+}
+
+/// Signals owned by the encoding tab
+#[derive(Clone, Copy, PartialEq)]
+pub struct EncodingWorkbenchState {
+    input: Signal<String>,
+    output: Signal<String>,
+    mode: Signal<String>,
+    encoding: Signal<String>,
+    alphabet: Signal<String>,
+    padding: Signal<bool>,
+    wrap: Signal<bool>,
+    data_uri: Signal<bool>,
+    hex_separator: Signal<String>,
+    uppercase: Signal<bool>,
+    percent_mode: Signal<String>,
+    html_ascii: Signal<bool>,
+    file: Signal<Option<UploadedFile>>,
+    binary: Signal<Option<Vec<u8>>>,
+    note: Signal<String>,
+}
+
+/// Registry entry for [`EncodingWorkbench`]
+pub struct EncodingWorkbenchTool;
+
+impl Tool for EncodingWorkbenchTool {
+    type State = EncodingWorkbenchState;
+
+    // Kept from when this tab only handled Base64, so old links still work
+    fn id(&self) -> &'static str {
+        "base64"
+    }
+
+    fn icon(&self) -> &'static str {
+        "🔒"
+    }
+
+    fn title(&self) -> &'static str {
+        "Encode"
+    }
+
+    fn subtitle(&self) -> &'static str {
+        "Base64 · Hex · URL & more"
+    }
+
+    fn create_state(&self) -> Self::State {
+        EncodingWorkbenchState {
+            input: Signal::new(String::new()),
+            output: Signal::new(String::new()),
+            mode: Signal::new("encode".to_string()),
+            encoding: Signal::new(Encoding::Base64.id().to_string()),
+            alphabet: Signal::new("standard".to_string()),
+            padding: Signal::new(true),
+            wrap: Signal::new(false),
+            data_uri: Signal::new(false),
+            hex_separator: Signal::new(hex::Separator::None.id().to_string()),
+            uppercase: Signal::new(false),
+            percent_mode: Signal::new(percent::Mode::Component.id().to_string()),
+            html_ascii: Signal::new(false),
+            file: Signal::new(None),
+            binary: Signal::new(None),
+            note: Signal::new(String::new()),
+        }
+    }
+
+    fn render(&self, state: Self::State) -> Element {
+        rsx! {
+            EncodingWorkbench {
+                input: state.input,
+                output: state.output,
+                mode: state.mode,
+                encoding: state.encoding,
+                alphabet: state.alphabet,
+                padding: state.padding,
+                wrap: state.wrap,
+                data_uri: state.data_uri,
+                hex_separator: state.hex_separator,
+                uppercase: state.uppercase,
+                percent_mode: state.percent_mode,
+                html_ascii: state.html_ascii,
+                file: state.file,
+                binary: state.binary,
+                note: state.note,
+            }
+        }
+    }
+
+    fn apply_query(&self, mut state: Self::State, query: &ToolQuery) {
+        let mode = match query.get("mode") {
+            Some("decode") => "decode",
+            _ => "encode",
+        };
+        let settings = Settings {
+            encoding: query
+                .get("encoding")
+                .and_then(Encoding::from_id)
+                .unwrap_or_default(),
+            options: Options {
+                url_safe: query.get("alphabet") == Some("url"),
+                padding: query.get("padding").is_none_or(|v| v == "1"),
+                wrap: query.get("wrap") == Some("1"),
+                hex_separator: query
+                    .get("separator")
+                    .and_then(hex::Separator::from_id)
+                    .unwrap_or_default(),
+                hex_uppercase: query.get("upper") == Some("1"),
+                percent_mode: query
+                    .get("escape")
+                    .and_then(percent::Mode::from_id)
+                    .unwrap_or_default(),
+                html_ascii_only: query.get("ascii") == Some("1"),
+            },
+            data_uri: query.get("uri") == Some("1"),
+        };
+        let input = query.get("input").unwrap_or_default().to_string();
+        let converted = convert(mode, &input, None, settings);
+        let options = settings.options;
+        state.output.set(converted.output);
+        state.binary.set(converted.binary);
+        state.note.set(converted.note);
+        state.file.set(None);
+        state.mode.set(mode.to_string());
+        state.encoding.set(settings.encoding.id().to_string());
+        state
+            .alphabet
+            .set(if options.url_safe { "url" } else { "standard" }.to_string());
+        state.padding.set(options.padding);
+        state.wrap.set(options.wrap);
+        state.data_uri.set(settings.data_uri);
+        state
+            .hex_separator
+            .set(options.hex_separator.id().to_string());
+        state.uppercase.set(options.hex_uppercase);
+        state
+            .percent_mode
+            .set(options.percent_mode.id().to_string());
+        state.html_ascii.set(options.html_ascii_only);
+        state.input.set(input);
+    }
+
+    fn to_query(&self, state: Self::State) -> ToolQuery {
+        let flag = |on: bool| if on { "1" } else { "" };
+        let encoding = Encoding::from_id(&state.encoding.peek()).unwrap_or_default();
+        let query = ToolQuery::default()
+            .with("mode", state.mode.peek().clone())
+            .with(
+                "encoding",
+                if encoding == Encoding::default() {
+                    ""
+                } else {
+                    encoding.id()
+                },
+            )
+            .with("input", state.input.peek().clone());
+        // Encoding options only matter when encoding
+        if *state.mode.peek() != "encode" {
+            return query;
+        }
+        match encoding {
+            Encoding::Base64 => query
+                .with(
+                    "alphabet",
+                    if *state.alphabet.peek() == "url" {
+                        "url"
+                    } else {
+                        ""
+                    },
+                )
+                .with("padding", if *state.padding.peek() { "" } else { "0" })
+                .with("wrap", flag(*state.wrap.peek()))
+                .with("uri", flag(*state.data_uri.peek())),
+            Encoding::Base32 => query.with("padding", if *state.padding.peek() { "" } else { "0" }),
+            Encoding::Hex => {
+                let separator = state.hex_separator.peek().clone();
+                query
+                    .with(
+                        "separator",
+                        if separator == hex::Separator::None.id() {
+                            String::new()
+                        } else {
+                            separator
+                        },
+                    )
+                    .with("upper", flag(*state.uppercase.peek()))
+            }
+            Encoding::Percent => {
+                let escape = state.percent_mode.peek().clone();
+                query.with(
+                    "escape",
+                    if escape == percent::Mode::Component.id() {
+                        String::new()
+                    } else {
+                        escape
+                    },
+                )
+            }
+            Encoding::Html => query.with("ascii", flag(*state.html_ascii.peek())),
+            _ => query,
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use crate::components::tools::registry::Tool;
+use crate::components::tools::shared::*;
+use crate::convert::{convert, Format};
+use crate::types::ToolQuery;
+use dioxus::prelude::*;
+
+fn format_modes() -> Vec<(String, String)> {
+    Format::ALL
+        .iter()
+        .map(|format| (format.id().to_string(), format.label().to_string()))
+        .collect()
+}
+
+/// Format Converter component
+#[component]
+pub fn FormatConverter(input: Signal<String>, from: Signal<String>, to: Signal<String>) -> Element {
+    let mut input = input;
+    let mut from = from;
+    let mut to = to;
+
+    let source = Format::from_id(&from()).unwrap_or_default();
+    let target = Format::from_id(&to()).unwrap_or(Format::Yaml);
+
+    let conversion = use_memo(move || {
+        let source = Format::from_id(&from()).unwrap_or_default();
+        let target = Format::from_id(&to()).unwrap_or(Format::Yaml);
+        if input().trim().is_empty() {
+            None
+        } else {
+            Some(convert(&input(), source, target))
+        }
+    });
+
+    let (output, diagnostics, error) = match conversion() {
+        Some(Ok(conversion)) => (conversion.output, conversion.diagnostics, String::new()),
+        Some(Err(e)) => (String::new(), Vec::new(), e),
+        None => (String::new(), Vec::new(), String::new()),
+    };
+
+    let swap = {
+        let output = output.clone();
+        move |_| {
+            if !output.is_empty() {
+                input.set(output.clone());
+            }
+            let original = from();
+            from.set(to());
+            to.set(original);
+        }
+    };
+
+    let clear_all = move |_| input.set(String::new());
+
+    let left_content = rsx! {
+        InputSection {
+            label: format!("Input {}", source.label()),
+            helper_text: Some(format!("Paste {} to convert", source.label())),
+            input: rsx! {
+                ToolTextarea {
+                    value: input(),
+                    placeholder: format!("Paste {} here...", source.label()),
+                    rows: Some(12),
+                    oninput: Some(EventHandler::new(move |event: FormEvent| input.set(event.value()))),
+                }
+            },
+        }
+    };
+
+    let right_content = rsx! {
+        OutputSection {
+            label: format!("{} Output", target.label()),
+            helper_text: Some("Key order is kept wherever the target format allows".to_string()),
+            copy_button: if !output.is_empty() { Some(rsx! {
+                CopyButton { text: output.clone() }
+            }) } else { None },
+            output: rsx! {
+                ToolTextarea {
+                    value: output.clone(),
+                    placeholder: format!("{} will appear here...", target.label()),
+                    rows: Some(12),
+                    readonly: Some(true),
+                }
+            },
+        }
+    };
+
+    let summary = format!(
+        "{} thing{} did not carry over exactly",
+        diagnostics.len(),
+        if diagnostics.len() == 1 { "" } else { "s" }
+    );
+
+    let actions = rsx! {
+        ActionButton {
+            text: "Swap".to_string(),
+            onclick: swap,
+            variant: Some("secondary".to_string()),
+        }
+        ActionButton {
+            text: "Clear All".to_string(),
+            onclick: clear_all,
+            variant: Some("secondary".to_string()),
+        }
+    };
+
+    rsx! {
+        div { class: "space-y-4",
+            div { class: "flex flex-wrap justify-center gap-6",
+                ModeSelector {
+                    current_mode: source.id().to_string(),
+                    modes: format_modes(),
+                    on_change: move |id| from.set(id),
+                    label: Some("From".to_string()),
+                }
+                ModeSelector {
+                    current_mode: target.id().to_string(),
+                    modes: format_modes(),
+                    on_change: move |id| to.set(id),
+                    label: Some("To".to_string()),
+                }
+            }
+
+            ToolGrid {
+                left_content,
+                right_content,
+                actions: Some(actions),
+                error_message: Some(error),
+            }
+
+            if !diagnostics.is_empty() {
+                div { class: "px-4 py-3 text-sm text-ctp-yellow bg-ctp-surface0 border border-ctp-yellow/50 rounded-md space-y-1",
+                    div { class: "font-medium", "{summary}" }
+                    ul { class: "list-disc pl-5 space-y-0.5",
+                        for (i , diagnostic) in diagnostics.iter().enumerate() {
+                            li { key: "{i}", class: "break-all", "{diagnostic.describe()}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Signals owned by the format converter tab
+#[derive(Clone, Copy, PartialEq)]
+pub struct FormatConverterState {
+    input: Signal<String>,
+    from: Signal<String>,
+    to: Signal<String>,
+}
+
+/// Registry entry for [`FormatConverter`]
+pub struct FormatConverterTool;
+
+impl Tool for FormatConverterTool {
+    type State = FormatConverterState;
+
+    fn id(&self) -> &'static str {
+        "convert"
+    }
+
+    fn icon(&self) -> &'static str {
+        "🔄"
+    }
+
+    fn title(&self) -> &'static str {
+        "Convert"
+    }
+
+    fn subtitle(&self) -> &'static str {
+        "JSON · YAML · TOML · XML"
+    }
+
+    fn create_state(&self) -> Self::State {
+        FormatConverterState {
+            input: Signal::new(String::new()),
+            from: Signal::new(Format::Json.id().to_string()),
+            to: Signal::new(Format::Yaml.id().to_string()),
+        }
+    }
+
+    fn render(&self, state: Self::State) -> Element {
+        rsx! {
+            FormatConverter { input: state.input, from: state.from, to: state.to }
+        }
+    }
+
+    fn apply_query(&self, mut state: Self::State, query: &ToolQuery) {
+        let from = query
+            .get("from")
+            .and_then(Format::from_id)
+            .unwrap_or(Format::Json);
+        let to = query
+            .get("to")
+            .and_then(Format::from_id)
+            .unwrap_or(Format::Yaml);
+        state
+            .input
+            .set(query.get("input").unwrap_or_default().to_string());
+        state.from.set(from.id().to_string());
+        state.to.set(to.id().to_string());
+    }
+
+    fn to_query(&self, state: Self::State) -> ToolQuery {
+        let non_default = |value: String, default: Format| {
+            if value == default.id() {
+                String::new()
+            } else {
+                value
+            }
+        };
+        ToolQuery::default()
+            .with("input", state.input.peek().clone())
+            .with("from", non_default(state.from.peek().clone(), Format::Json))
+            .with("to", non_default(state.to.peek().clone(), Format::Yaml))
+    }
+}
@@ -0,0 +1,420 @@
+use crate::binary::format_size;
+use crate::components::tools::registry::Tool;
+use crate::components::tools::shared::*;
+use crate::encoding::base64;
+use crate::hash::{self, Algorithm, OutputFormat};
+use crate::types::ToolQuery;
+use dioxus::prelude::*;
+
+const MODES: [(&str, &str); 2] = [("hash", "Hash"), ("hmac", "HMAC")];
+const SOURCES: [(&str, &str); 2] = [("text", "Text"), ("file", "File")];
+
+/// Each algorithm with its digest or HMAC
+type Digests = Vec<(Algorithm, Vec<u8>)>;
+
+fn key_bytes(text: &str, key_base64: bool) -> Result<Vec<u8>, String> {
+    if key_base64 {
+        base64::decode(text)
+            .map(|(bytes, _)| bytes)
+            .map_err(|e| format!("Key is not Base64: {}", e))
+    } else {
+        Ok(text.as_bytes().to_vec())
+    }
+}
+
+/// Hash and HMAC generator component
+#[component]
+pub fn HashGenerator(
+    mode: Signal<String>,
+    source: Signal<String>,
+    input: Signal<String>,
+    file: Signal<Option<UploadedFile>>,
+    key_text: Signal<String>,
+    key_base64: Signal<bool>,
+    format: Signal<String>,
+    expected: Signal<String>,
+) -> Element {
+    let mut mode = mode;
+    let mut source = source;
+    let mut input = input;
+    let mut file = file;
+    let mut key_text = key_text;
+    let mut key_base64 = key_base64;
+    let mut format = format;
+    let mut expected = expected;
+
+    let hmac = mode() == "hmac";
+    let from_file = source() == "file";
+
+    // Digests of every algorithm, or None while there is nothing to hash
+    let digests = use_memo(move || -> Result<Option<Digests>, String> {
+        let file = file();
+        let bytes = match (source().as_str(), &file) {
+            ("file", Some(upload)) => upload.bytes.as_slice(),
+            ("file", None) => return Ok(None),
+            _ => &input().into_bytes(),
+        };
+        if mode() != "hmac" {
+            return Ok(Some(
+                Algorithm::ALL
+                    .into_iter()
+                    .map(|algorithm| (algorithm, hash::digest(algorithm, bytes)))
+                    .collect(),
+            ));
+        }
+        let key = key_bytes(&key_text(), key_base64())?;
+        Ok(Some(
+            Algorithm::ALL
+                .into_iter()
+                .filter_map(|algorithm| Some((algorithm, hash::hmac(algorithm, &key, bytes)?)))
+                .collect(),
+        ))
+    });
+
+    let output_format = OutputFormat::from_id(&format()).unwrap_or_default();
+    let expected_text = expected();
+    let wanted = if expected_text.trim().is_empty() {
+        None
+    } else {
+        Some(hash::parse_expected(&expected_text))
+    };
+
+    let (rows, mut error) = match digests() {
+        Ok(rows) => (rows.unwrap_or_default(), None),
+        Err(e) => (Vec::new(), Some(e)),
+    };
+    let matches: Vec<Algorithm> = match &wanted {
+        Some(Ok(bytes)) => rows
+            .iter()
+            .filter(|(_, digest)| digest == bytes)
+            .map(|(algorithm, _)| *algorithm)
+            .collect(),
+        _ => Vec::new(),
+    };
+    let kind = if hmac { "HMAC" } else { "hash" };
+    let mut success = None;
+    if error.is_none() && !rows.is_empty() {
+        match &wanted {
+            Some(Err(e)) => error = Some(e.clone()),
+            Some(Ok(_)) if matches.is_empty() => {
+                error = Some(format!("No {} matches the expected value", kind))
+            }
+            Some(Ok(_)) => {
+                let names: Vec<&str> = matches.iter().map(|a| a.label()).collect();
+                success = Some(format!("✓ Matches {}", names.join(", ")));
+            }
+            None => {}
+        }
+    }
+
+    let rows: Vec<(Algorithm, String)> = rows
+        .iter()
+        .map(|(algorithm, digest)| (*algorithm, output_format.format(digest)))
+        .collect();
+    let all_text: String = rows
+        .iter()
+        .map(|(algorithm, value)| format!("{}  {}\n", algorithm.label(), value))
+        .collect();
+
+    let left_content = rsx! {
+        InputSection {
+            label: if from_file { "File to Hash".to_string() } else { "Text to Hash".to_string() },
+            helper_text: Some(
+                if from_file {
+                    "Files are hashed in your browser and never uploaded".to_string()
+                } else {
+                    "Hashed as UTF-8, exactly as typed".to_string()
+                },
+            ),
+            input: rsx! {
+                if from_file {
+                    div { class: "space-y-3",
+                        FileDropZone {
+                            label: "Upload a file to hash".to_string(),
+                            on_file: move |upload| file.set(Some(upload)),
+                        }
+                        if let Some(upload) = file() {
+                            div { class: "flex items-center justify-between gap-3 px-3 py-2 text-xs text-ctp-subtext1 bg-ctp-surface0 border border-ctp-surface2 rounded-md",
+                                span { class: "break-all",
+                                    "{upload.name} · {format_size(upload.bytes.len())}"
+                                }
+                                button {
+                                    class: "text-ctp-red hover:underline",
+                                    onclick: move |_| file.set(None),
+                                    "Remove"
+                                }
+                            }
+                        }
+                    }
+                } else {
+                    ToolTextarea {
+                        value: input(),
+                        placeholder: "Enter text to hash...".to_string(),
+                        rows: Some(6),
+                        oninput: Some(EventHandler::new(move |event: FormEvent| input.set(event.value()))),
+                    }
+                }
+            },
+        }
+        if hmac {
+            InputSection {
+                label: "Key".to_string(),
+                helper_text: Some("The shared secret, e.g. a webhook signing secret".to_string()),
+                input: rsx! {
+                    div { class: "space-y-3",
+                        ToolTextarea {
+                            value: key_text(),
+                            placeholder: "your-secret".to_string(),
+                            rows: Some(2),
+                            oninput: Some(EventHandler::new(move |event: FormEvent| key_text.set(event.value()))),
+                        }
+                        ToolCheckbox {
+                            label: "Key is Base64-encoded".to_string(),
+                            checked: key_base64(),
+                            on_change: move |on| key_base64.set(on),
+                        }
+                    }
+                },
+            }
+        }
+        InputSection {
+            label: "Expected Value".to_string(),
+            helper_text: Some(
+                "Hex or Base64; sha256sum lines and sha256= prefixes are understood"
+                    .to_string(),
+            ),
+            input: rsx! {
+                ToolInput {
+                    value: expected_text.clone(),
+                    placeholder: "Paste a checksum or signature to compare".to_string(),
+                    oninput: move |event: FormEvent| expected.set(event.value()),
+                }
+            },
+        }
+    };
+
+    let output = if rows.is_empty() {
+        rsx! {
+            ToolTextarea {
+                value: String::new(),
+                placeholder: "Hashes will appear here...".to_string(),
+                rows: Some(6),
+                readonly: Some(true),
+            }
+        }
+    } else {
+        rsx! {
+            div { class: "space-y-2",
+                for (algorithm , value) in rows {
+                    {
+                        let row_class = if matches.contains(&algorithm) {
+                            "border-ctp-green bg-ctp-green/10"
+                        } else {
+                            "border-ctp-surface2 bg-ctp-surface0"
+                        };
+                        rsx! {
+                            div {
+                                key: "{algorithm.label()}",
+                                class: "flex items-center gap-3 px-3 py-2 border rounded-md {row_class}",
+                                div { class: "flex-1 min-w-0",
+                                    div { class: "text-xs text-ctp-subtext0",
+                                        "{algorithm.label()}"
+                                        if matches.contains(&algorithm) {
+                                            span { class: "ml-2 text-ctp-green", "✓ match" }
+                                        }
+                                    }
+                                    div { class: "font-mono text-sm text-ctp-text break-all",
+                                        "{value}"
+                                    }
+                                }
+                                CopyButton { text: value.clone() }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    let right_content = rsx! {
+        OutputSection {
+            label: if hmac { "HMACs".to_string() } else { "Hashes".to_string() },
+            helper_text: Some(
+                if hmac {
+                    "BLAKE3 and CRC32 have no HMAC and are left out".to_string()
+                } else {
+                    "CRC32 is a checksum for catching corruption, not tampering".to_string()
+                },
+            ),
+            copy_button: if !all_text.is_empty() { Some(rsx! {
+                CopyButton { text: all_text.clone() }
+            }) } else { None },
+            output,
+        }
+    };
+
+    let actions = rsx! {
+        ActionButton {
+            text: "Clear All".to_string(),
+            onclick: move |_| {
+                input.set(String::new());
+                file.set(None);
+                key_text.set(String::new());
+                expected.set(String::new());
+            },
+            variant: Some("secondary".to_string()),
+        }
+    };
+
+    rsx! {
+        div { class: "space-y-4",
+            div { class: "flex flex-wrap items-end justify-center gap-6",
+                ModeSelector {
+                    current_mode: mode(),
+                    modes: MODES.iter().map(|(id, label)| (id.to_string(), label.to_string())).collect::<Vec<_>>(),
+                    on_change: move |id| mode.set(id),
+                    label: Some("Mode".to_string()),
+                }
+                ModeSelector {
+                    current_mode: source(),
+                    modes: SOURCES.iter().map(|(id, label)| (id.to_string(), label.to_string())).collect::<Vec<_>>(),
+                    on_change: move |id| source.set(id),
+                    label: Some("Input".to_string()),
+                }
+                ModeSelector {
+                    current_mode: format(),
+                    modes: OutputFormat::ALL
+                        .iter()
+                        .map(|f| (f.id().to_string(), f.label().to_string()))
+                        .collect::<Vec<_>>(),
+                    on_change: move |id| format.set(id),
+                    label: Some("Output".to_string()),
+                }
+            }
+
+            ToolGrid {
+                left_content,
+                right_content,
+                actions: Some(actions),
+                error_message: error,
+                success_message: success,
+            }
+        }
+    }
+}
+
+/// Signals owned by the hash generator tab
+#[derive(Clone, Copy, PartialEq)]
+pub struct HashGeneratorState {
+    mode: Signal<String>,
+    source: Signal<String>,
+    input: Signal<String>,
+    file: Signal<Option<UploadedFile>>,
+    key_text: Signal<String>,
+    key_base64: Signal<bool>,
+    format: Signal<String>,
+    expected: Signal<String>,
+}
+
+/// Registry entry for [`HashGenerator`]
+pub struct HashGeneratorTool;
+
+impl Tool for HashGeneratorTool {
+    type State = HashGeneratorState;
+
+    fn id(&self) -> &'static str {
+        "hash"
+    }
+
+    fn icon(&self) -> &'static str {
+        "#️⃣"
+    }
+
+    fn title(&self) -> &'static str {
+        "Hash"
+    }
+
+    fn subtitle(&self) -> &'static str {
+        "SHA · MD5 · BLAKE · HMAC"
+    }
+
+    fn create_state(&self) -> Self::State {
+        HashGeneratorState {
+            mode: Signal::new("hash".to_string()),
+            source: Signal::new("text".to_string()),
+            input: Signal::new(String::new()),
+            file: Signal::new(None),
+            key_text: Signal::new(String::new()),
+            key_base64: Signal::new(false),
+            format: Signal::new(OutputFormat::Hex.id().to_string()),
+            expected: Signal::new(String::new()),
+        }
+    }
+
+    fn render(&self, state: Self::State) -> Element {
+        rsx! {
+            HashGenerator {
+                mode: state.mode,
+                source: state.source,
+                input: state.input,
+                file: state.file,
+                key_text: state.key_text,
+                key_base64: state.key_base64,
+                format: state.format,
+                expected: state.expected,
+            }
+        }
+    }
+
+    fn apply_query(&self, mut state: Self::State, query: &ToolQuery) {
+        // Tab switches route here with the tab's own query; keep the file
+        // and key, which a link cannot carry
+        if *query == self.to_query(state) {
+            return;
+        }
+        let mode = query
+            .get("mode")
+            .filter(|id| MODES.iter().any(|(mode, _)| mode == id))
+            .unwrap_or("hash");
+        let format = query
+            .get("format")
+            .and_then(OutputFormat::from_id)
+            .unwrap_or_default();
+        state.mode.set(mode.to_string());
+        // Files cannot travel in a link
+        state.source.set("text".to_string());
+        state
+            .input
+            .set(query.get("input").unwrap_or_default().to_string());
+        state.file.set(None);
+        state.key_text.set(String::new());
+        state.key_base64.set(query.get("b64") == Some("1"));
+        state.format.set(format.id().to_string());
+        state
+            .expected
+            .set(query.get("expected").unwrap_or_default().to_string());
+    }
+
+    fn to_query(&self, state: Self::State) -> ToolQuery {
+        let non_default = |value: String, default: &str| {
+            if value == default {
+                String::new()
+            } else {
+                value
+            }
+        };
+        if *state.source.peek() == "file" {
+            return ToolQuery::default();
+        }
+        // The HMAC key is a secret and stays out of the URL
+        ToolQuery::default()
+            .with("mode", non_default(state.mode.peek().clone(), "hash"))
+            .with(
+                "format",
+                non_default(state.format.peek().clone(), OutputFormat::Hex.id()),
+            )
+            .with("b64", if *state.key_base64.peek() { "1" } else { "" })
+            .with("input", state.input.peek().clone())
+            .with("expected", state.expected.peek().clone())
+    }
+}
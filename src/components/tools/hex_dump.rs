@@ -0,0 +1,534 @@
+use crate::binary::inspect::{interpret, xxd, Endian, Layout, TextEncoding};
+use crate::binary::{format_size, magic, printable};
+use crate::components::tools::registry::Tool;
+use crate::components::tools::shared::*;
+use crate::encoding::{self, Encoding};
+use crate::types::ToolQuery;
+use dioxus::prelude::*;
+
+/// Bytes rendered at once; larger inputs show the window around the selection
+const WINDOW_BYTES: usize = 2048;
+
+const SOURCES: [(&str, &str); 4] = [
+    ("text", "Text"),
+    ("hex", "Hex"),
+    ("base64", "Base64"),
+    ("file", "File"),
+];
+const ROW_WIDTHS: [&str; 3] = ["8", "16", "32"];
+const GROUPS: [&str; 4] = ["1", "2", "4", "8"];
+
+/// Bytes to inspect, from the input as read by `source`
+fn read_bytes(
+    source: &str,
+    input: &str,
+    text_encoding: TextEncoding,
+    file: Option<&UploadedFile>,
+) -> Result<Vec<u8>, String> {
+    match source {
+        "file" => Ok(file.map(|file| file.bytes.clone()).unwrap_or_default()),
+        "hex" => encoding::decode(Encoding::Hex, input).map(|decoded| decoded.bytes),
+        "base64" => encoding::decode(Encoding::Base64, input).map(|decoded| decoded.bytes),
+        _ => text_encoding.encode(input),
+    }
+}
+
+/// Parses a decimal or `0x` prefixed offset
+fn parse_offset(text: &str) -> Option<usize> {
+    let text = text.trim();
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+fn choices(ids: &[&str]) -> Vec<(String, String)> {
+    ids.iter()
+        .map(|id| (id.to_string(), id.to_string()))
+        .collect()
+}
+
+/// Clickable rows of a hex dump for `bytes`, which start at offset `start`.
+/// Clicking selects a byte; shift-clicking extends the selection.
+#[component]
+fn DumpRows(
+    bytes: Vec<u8>,
+    start: usize,
+    layout: Layout,
+    selection: Signal<Option<(usize, usize)>>,
+) -> Element {
+    let mut selection = selection;
+    let range = selection().map(|(anchor, cursor)| anchor.min(cursor)..=anchor.max(cursor));
+    let selected = move |offset: usize| range.as_ref().is_some_and(|r| r.contains(&offset));
+    let select = move |offset: usize| {
+        move |event: MouseEvent| match selection() {
+            Some((anchor, _)) if event.modifiers().shift() => {
+                selection.set(Some((anchor, offset)));
+            }
+            _ => selection.set(Some((offset, offset))),
+        }
+    };
+    let byte_class = move |offset: usize| {
+        if selected(offset) {
+            "cursor-pointer bg-ctp-mauve/40 text-ctp-text"
+        } else {
+            "cursor-pointer hover:bg-ctp-surface2"
+        }
+    };
+
+    let rows: Vec<(usize, &[u8])> = bytes
+        .chunks(layout.bytes_per_row)
+        .enumerate()
+        .map(|(row, chunk)| (start + row * layout.bytes_per_row, chunk))
+        .collect();
+
+    rsx! {
+        div { class: "max-h-96 overflow-auto p-4 font-mono text-sm text-ctp-subtext1 whitespace-pre bg-ctp-surface0 border border-ctp-surface2 rounded-md",
+            for (row_offset , chunk) in rows {
+                {
+                    let groups = layout.groups(chunk.len());
+                    let hex_len = chunk.len() * 2 + groups.len().saturating_sub(1);
+                    let padding = " ".repeat(layout.hex_width().saturating_sub(hex_len));
+                    rsx! {
+                        div { key: "{row_offset}",
+                            span { class: "text-ctp-overlay1", "{row_offset:08x}: " }
+                            for (g , group) in groups.into_iter().enumerate() {
+                                if g > 0 {
+                                    " "
+                                }
+                                for i in group {
+                                    span {
+                                        key: "{i}",
+                                        class: byte_class(row_offset + i),
+                                        onclick: select(row_offset + i),
+                                        "{chunk[i]:02x}"
+                                    }
+                                }
+                            }
+                            "{padding}  "
+                            for (i , byte) in chunk.iter().enumerate() {
+                                span {
+                                    key: "a{i}",
+                                    class: byte_class(row_offset + i),
+                                    onclick: select(row_offset + i),
+                                    "{printable(*byte)}"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Hex dump and binary inspector component
+#[component]
+pub fn HexDump(
+    input: Signal<String>,
+    source: Signal<String>,
+    text_encoding: Signal<String>,
+    file: Signal<Option<UploadedFile>>,
+    bytes_per_row: Signal<String>,
+    group: Signal<String>,
+    endian: Signal<String>,
+    selection: Signal<Option<(usize, usize)>>,
+) -> Element {
+    let mut input = input;
+    let mut source = source;
+    let mut text_encoding = text_encoding;
+    let mut file = file;
+    let mut bytes_per_row = bytes_per_row;
+    let mut group = group;
+    let mut endian = endian;
+    let mut selection = selection;
+
+    let data = use_memo(move || {
+        read_bytes(
+            &source(),
+            &input(),
+            TextEncoding::from_id(&text_encoding()).unwrap_or_default(),
+            file().as_ref(),
+        )
+    });
+    let mut offset_text = use_signal(String::new);
+
+    let layout = Layout {
+        bytes_per_row: bytes_per_row().parse().unwrap_or(16),
+        group: group().parse().unwrap_or(2),
+        endian: Endian::from_id(&endian()).unwrap_or_default(),
+    };
+    let (bytes, error) = match data() {
+        Ok(bytes) => (bytes, None),
+        Err(e) => (Vec::new(), Some(e)),
+    };
+
+    // Drop a selection left over from longer input
+    let range = selection()
+        .filter(|&(anchor, cursor)| anchor.max(cursor) < bytes.len())
+        .map(|(anchor, cursor)| (anchor.min(cursor), anchor.max(cursor)));
+    let window_start = range.map_or(0, |(start, _)| start / WINDOW_BYTES * WINDOW_BYTES);
+    let window = &bytes[window_start..bytes.len().min(window_start + WINDOW_BYTES)];
+    let dump_text = xxd(window, window_start, layout);
+
+    let summary = if bytes.is_empty() {
+        String::new()
+    } else {
+        match magic::detect(&bytes) {
+            Some(file_type) => format!("{} · {}", format_size(bytes.len()), file_type.name),
+            None => format_size(bytes.len()),
+        }
+    };
+    let dump_helper = if bytes.len() > WINDOW_BYTES {
+        format!(
+            "Bytes 0x{:x}–0x{:x} of {}; go to an offset to see the rest",
+            window_start,
+            window_start + window.len() - 1,
+            format_size(bytes.len())
+        )
+    } else {
+        "Click a byte to inspect it; shift-click to select a range".to_string()
+    };
+
+    let reading = range.map(|(start, end)| {
+        let selected = &bytes[start..=end];
+        let hex: Vec<String> = selected.iter().map(|b| format!("{:02x}", b)).collect();
+        (
+            start,
+            hex.join(" "),
+            interpret(&bytes[start..], layout.endian),
+        )
+    });
+
+    let length = bytes.len();
+    let from_text = source() == "text";
+    let from_file = source() == "file";
+
+    let left_content = rsx! {
+        InputSection {
+            label: match source().as_str() {
+                "file" => "File to Inspect".to_string(),
+                "hex" => "Hex Bytes".to_string(),
+                "base64" => "Base64 Data".to_string(),
+                _ => "Text to Inspect".to_string(),
+            },
+            helper_text: Some(
+                match source().as_str() {
+                    "file" => "Upload any file to see its bytes".to_string(),
+                    "hex" => "Whitespace, separators and 0x prefixes are ignored".to_string(),
+                    "base64" => "Standard or URL-safe, with or without padding".to_string(),
+                    _ => "Shown as the bytes of the chosen text encoding".to_string(),
+                },
+            ),
+            input: rsx! {
+                if from_file {
+                    div { class: "space-y-3",
+                        FileDropZone {
+                            label: "Upload a file to inspect".to_string(),
+                            on_file: move |upload| {
+                                file.set(Some(upload));
+                                selection.set(None);
+                            },
+                        }
+                        if let Some(upload) = file() {
+                            div { class: "flex items-center justify-between gap-3 px-3 py-2 text-xs text-ctp-subtext1 bg-ctp-surface0 border border-ctp-surface2 rounded-md",
+                                span { class: "break-all",
+                                    "{upload.name} · {format_size(upload.bytes.len())}"
+                                }
+                                button {
+                                    class: "text-ctp-red hover:underline",
+                                    onclick: move |_| {
+                                        file.set(None);
+                                        selection.set(None);
+                                    },
+                                    "Remove"
+                                }
+                            }
+                        }
+                    }
+                } else {
+                    ToolTextarea {
+                        value: input(),
+                        placeholder: "Enter data to inspect...".to_string(),
+                        rows: Some(6),
+                        oninput: Some(EventHandler::new(move |event: FormEvent| input.set(event.value()))),
+                    }
+                }
+            },
+        }
+        InputSection {
+            label: "Selection".to_string(),
+            helper_text: Some(
+                format!(
+                    "Values read from the first selected byte, {}",
+                    layout.endian.label().to_lowercase(),
+                ),
+            ),
+            input: rsx! {
+                div { class: "space-y-3",
+                    ToolInput {
+                        value: offset_text(),
+                        placeholder: "Go to offset, e.g. 128 or 0x80".to_string(),
+                        oninput: move |event: FormEvent| {
+                            let text = event.value();
+                            if let Some(offset) = parse_offset(&text).filter(|&o| o < length) {
+                                selection.set(Some((offset, offset)));
+                            }
+                            offset_text.set(text);
+                        },
+                    }
+                    if let Some((start, hex, readings)) = reading {
+                        div { class: "px-3 py-2 font-mono text-xs text-ctp-subtext1 break-all bg-ctp-surface0 border border-ctp-surface2 rounded-md",
+                            "0x{start:x}: {hex}"
+                        }
+                        div { class: "bg-ctp-surface0 border border-ctp-surface2 rounded-md",
+                            table { class: "min-w-full text-sm font-mono",
+                                tbody { class: "divide-y divide-ctp-surface1",
+                                    for (label , value) in readings {
+                                        tr { key: "{label}",
+                                            td { class: "px-3 py-1.5 text-ctp-text whitespace-nowrap", "{label}" }
+                                            td { class: "px-3 py-1.5 text-ctp-subtext1 break-all",
+                                                {value.unwrap_or_else(|| "—".to_string())}
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+        }
+    };
+
+    let dump = if window.is_empty() {
+        rsx! {
+            ToolTextarea {
+                value: String::new(),
+                placeholder: "Hex dump will appear here...".to_string(),
+                rows: Some(6),
+                readonly: Some(true),
+            }
+        }
+    } else {
+        rsx! {
+            DumpRows {
+                bytes: window.to_vec(),
+                start: window_start,
+                layout,
+                selection,
+            }
+        }
+    };
+
+    let right_content = rsx! {
+        OutputSection {
+            label: "Hex Dump".to_string(),
+            helper_text: Some(dump_helper),
+            copy_button: if !dump_text.is_empty() { Some(rsx! {
+                CopyButton { text: dump_text.clone() }
+            }) } else { None },
+            output: dump,
+        }
+    };
+
+    let actions = rsx! {
+        ActionButton {
+            text: "Clear All".to_string(),
+            onclick: move |_| {
+                input.set(String::new());
+                file.set(None);
+                selection.set(None);
+                offset_text.set(String::new());
+            },
+            variant: Some("secondary".to_string()),
+        }
+    };
+
+    rsx! {
+        div { class: "space-y-4",
+            ModeSelector {
+                current_mode: source(),
+                modes: SOURCES.iter().map(|(id, label)| (id.to_string(), label.to_string())).collect::<Vec<_>>(),
+                on_change: move |id| {
+                    source.set(id);
+                    selection.set(None);
+                },
+                label: Some("Input".to_string()),
+            }
+
+            div { class: "flex flex-wrap items-end justify-center gap-6",
+                if from_text {
+                    ModeSelector {
+                        current_mode: text_encoding(),
+                        modes: TextEncoding::ALL
+                            .iter()
+                            .map(|e| (e.id().to_string(), e.label().to_string()))
+                            .collect::<Vec<_>>(),
+                        on_change: move |id| text_encoding.set(id),
+                        label: Some("Text Encoding".to_string()),
+                    }
+                }
+                ModeSelector {
+                    current_mode: bytes_per_row(),
+                    modes: choices(&ROW_WIDTHS),
+                    on_change: move |id| bytes_per_row.set(id),
+                    label: Some("Bytes per Row".to_string()),
+                }
+                ModeSelector {
+                    current_mode: group(),
+                    modes: choices(&GROUPS),
+                    on_change: move |id| group.set(id),
+                    label: Some("Group".to_string()),
+                }
+                ModeSelector {
+                    current_mode: endian(),
+                    modes: Endian::ALL
+                        .iter()
+                        .map(|e| (e.id().to_string(), e.label().to_string()))
+                        .collect::<Vec<_>>(),
+                    on_change: move |id| endian.set(id),
+                    label: Some("Byte Order".to_string()),
+                }
+            }
+
+            ToolGrid {
+                left_content,
+                right_content,
+                actions: Some(actions),
+                error_message: error,
+                success_message: Some(summary),
+            }
+        }
+    }
+}
+
+/// Signals owned by the hex dump tab
+#[derive(Clone, Copy, PartialEq)]
+pub struct HexDumpState {
+    input: Signal<String>,
+    source: Signal<String>,
+    text_encoding: Signal<String>,
+    file: Signal<Option<UploadedFile>>,
+    bytes_per_row: Signal<String>,
+    group: Signal<String>,
+    endian: Signal<String>,
+    selection: Signal<Option<(usize, usize)>>,
+}
+
+/// Registry entry for [`HexDump`]
+pub struct HexDumpTool;
+
+impl Tool for HexDumpTool {
+    type State = HexDumpState;
+
+    fn id(&self) -> &'static str {
+        "hexdump"
+    }
+
+    fn icon(&self) -> &'static str {
+        "🔬"
+    }
+
+    fn title(&self) -> &'static str {
+        "Hex Dump"
+    }
+
+    fn subtitle(&self) -> &'static str {
+        "Inspect Binary Data"
+    }
+
+    fn create_state(&self) -> Self::State {
+        HexDumpState {
+            input: Signal::new(String::new()),
+            source: Signal::new("text".to_string()),
+            text_encoding: Signal::new(TextEncoding::Utf8.id().to_string()),
+            file: Signal::new(None),
+            bytes_per_row: Signal::new("16".to_string()),
+            group: Signal::new("2".to_string()),
+            endian: Signal::new(Endian::Big.id().to_string()),
+            selection: Signal::new(None),
+        }
+    }
+
+    fn render(&self, state: Self::State) -> Element {
+        rsx! {
+            HexDump {
+                input: state.input,
+                source: state.source,
+                text_encoding: state.text_encoding,
+                file: state.file,
+                bytes_per_row: state.bytes_per_row,
+                group: state.group,
+                endian: state.endian,
+                selection: state.selection,
+            }
+        }
+    }
+
+    fn apply_query(&self, mut state: Self::State, query: &ToolQuery) {
+        // Switching tabs routes here with the tab's own query; only a
+        // different link replaces the input, file and selection
+        if *query == self.to_query(state) {
+            return;
+        }
+        // Files cannot travel in a link
+        let source = query
+            .get("source")
+            .filter(|id| *id != "file" && SOURCES.iter().any(|(source, _)| source == id))
+            .unwrap_or("text");
+        let text_encoding = query
+            .get("encoding")
+            .and_then(TextEncoding::from_id)
+            .unwrap_or_default();
+        let bytes_per_row = query
+            .get("row")
+            .filter(|id| ROW_WIDTHS.contains(id))
+            .unwrap_or("16");
+        let group = query
+            .get("group")
+            .filter(|id| GROUPS.contains(id))
+            .unwrap_or("2");
+        let endian = query
+            .get("endian")
+            .and_then(Endian::from_id)
+            .unwrap_or_default();
+        state
+            .input
+            .set(query.get("input").unwrap_or_default().to_string());
+        state.source.set(source.to_string());
+        state.text_encoding.set(text_encoding.id().to_string());
+        state.file.set(None);
+        state.bytes_per_row.set(bytes_per_row.to_string());
+        state.group.set(group.to_string());
+        state.endian.set(endian.id().to_string());
+        state.selection.set(None);
+    }
+
+    fn to_query(&self, state: Self::State) -> ToolQuery {
+        let non_default = |value: String, default: &str| {
+            if value == default {
+                String::new()
+            } else {
+                value
+            }
+        };
+        let source = state.source.peek().clone();
+        if source == "file" {
+            return ToolQuery::default();
+        }
+        ToolQuery::default()
+            .with("source", non_default(source, "text"))
+            .with(
+                "encoding",
+                non_default(state.text_encoding.peek().clone(), TextEncoding::Utf8.id()),
+            )
+            .with("row", non_default(state.bytes_per_row.peek().clone(), "16"))
+            .with("group", non_default(state.group.peek().clone(), "2"))
+            .with(
+                "endian",
+                non_default(state.endian.peek().clone(), Endian::Big.id()),
+            )
+            .with("input", state.input.peek().clone())
+    }
+}
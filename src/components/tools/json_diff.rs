@@ -0,0 +1,397 @@
+use crate::components::tools::registry::Tool;
+use crate::components::tools::shared::*;
+use crate::json::diff::{annotated_lines, diff, ArrayMatch, ChangeKind, Diff};
+use crate::json::lenient::{self, Dialect};
+use crate::types::ToolQuery;
+use dioxus::prelude::*;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Longest value shown inline in the change list
+const INLINE_CHARS: usize = 80;
+
+/// Both parsed documents and their differences
+#[derive(Debug, Clone, PartialEq)]
+struct Comparison {
+    left: Value,
+    right: Value,
+    diff: Diff,
+}
+
+/// Parses and compares both inputs; `None` until both are filled in
+fn compare(
+    left: &str,
+    right: &str,
+    arrays: &str,
+    key_field: &str,
+) -> Result<Option<Rc<Comparison>>, String> {
+    if left.trim().is_empty() || right.trim().is_empty() {
+        return Ok(None);
+    }
+    let (left, _) =
+        lenient::parse(left, Dialect::Strict).map_err(|e| format!("Original: {}", e.message))?;
+    let (right, _) =
+        lenient::parse(right, Dialect::Strict).map_err(|e| format!("Modified: {}", e.message))?;
+
+    let arrays = if arrays == "key" && !key_field.trim().is_empty() {
+        ArrayMatch::Key(key_field.trim().to_string())
+    } else {
+        ArrayMatch::Index
+    };
+    let diff = diff(&left, &right, &arrays);
+    Ok(Some(Rc::new(Comparison { left, right, diff })))
+}
+
+fn highlight_class(kind: ChangeKind) -> &'static str {
+    match kind {
+        ChangeKind::Added => "bg-ctp-green/20",
+        ChangeKind::Removed => "bg-ctp-red/20",
+        ChangeKind::Changed => "bg-ctp-yellow/20",
+        ChangeKind::Moved => "bg-ctp-blue/20",
+    }
+}
+
+fn badge_class(kind: ChangeKind) -> &'static str {
+    match kind {
+        ChangeKind::Added => "text-ctp-green border-ctp-green/50",
+        ChangeKind::Removed => "text-ctp-red border-ctp-red/50",
+        ChangeKind::Changed => "text-ctp-yellow border-ctp-yellow/50",
+        ChangeKind::Moved => "text-ctp-blue border-ctp-blue/50",
+    }
+}
+
+/// Mark of the closest changed ancestor of `pointer`, including itself
+fn mark_for(pointer: &str, marks: &HashMap<&str, ChangeKind>) -> Option<ChangeKind> {
+    let mut pointer = pointer;
+    loop {
+        if let Some(kind) = marks.get(pointer) {
+            return Some(*kind);
+        }
+        pointer = &pointer[..pointer.rfind('/')?];
+    }
+}
+
+fn inline_value(value: &Option<Value>) -> String {
+    let text = value.as_ref().map(Value::to_string).unwrap_or_default();
+    if text.chars().count() > INLINE_CHARS {
+        format!("{}…", text.chars().take(INLINE_CHARS).collect::<String>())
+    } else {
+        text
+    }
+}
+
+/// One document with the lines touched by the diff highlighted
+fn diff_pane(title: &str, value: &Value, marks: &HashMap<&str, ChangeKind>) -> Element {
+    let lines: Vec<(String, &'static str)> = annotated_lines(value)
+        .into_iter()
+        .map(|(pointer, text)| (text, mark_for(&pointer, marks).map_or("", highlight_class)))
+        .collect();
+
+    rsx! {
+        div { class: "space-y-2 min-w-0",
+            div { class: "text-sm font-medium text-ctp-subtext1", "{title}" }
+            div { class: "max-h-96 overflow-auto py-1 font-mono text-sm bg-ctp-surface0 border border-ctp-surface2 rounded-md",
+                for (i , (text , class)) in lines.into_iter().enumerate() {
+                    div { key: "{i}", class: "flex whitespace-pre {class}",
+                        span { class: "w-10 shrink-0 pr-2 text-right text-ctp-overlay0 select-none",
+                            "{i + 1}"
+                        }
+                        span { class: "text-ctp-text", "{text}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// JSON Diff component
+#[component]
+pub fn JsonDiff(
+    left: Signal<String>,
+    right: Signal<String>,
+    arrays: Signal<String>,
+    key_field: Signal<String>,
+    view: Signal<String>,
+) -> Element {
+    let mut left = left;
+    let mut right = right;
+    let mut arrays = arrays;
+    let mut key_field = key_field;
+    let mut view = view;
+
+    let result = use_memo(move || compare(&left(), &right(), &arrays(), &key_field()));
+
+    let swap = move |_| {
+        let original = left();
+        left.set(right());
+        right.set(original);
+    };
+
+    let clear_all = move |_| {
+        left.set(String::new());
+        right.set(String::new());
+    };
+
+    let left_content = rsx! {
+        InputSection {
+            label: "Original JSON".to_string(),
+            helper_text: Some("The document to compare from".to_string()),
+            input: rsx! {
+                ToolTextarea {
+                    value: left(),
+                    placeholder: "Paste the original JSON here...".to_string(),
+                    rows: Some(6),
+                    oninput: Some(EventHandler::new(move |event: FormEvent| left.set(event.value()))),
+                }
+            },
+        }
+    };
+
+    let right_content = rsx! {
+        InputSection {
+            label: "Modified JSON".to_string(),
+            helper_text: Some("The document to compare to".to_string()),
+            input: rsx! {
+                ToolTextarea {
+                    value: right(),
+                    placeholder: "Paste the modified JSON here...".to_string(),
+                    rows: Some(6),
+                    oninput: Some(EventHandler::new(move |event: FormEvent| right.set(event.value()))),
+                }
+            },
+        }
+    };
+
+    let actions = rsx! {
+        ActionButton {
+            text: "Swap".to_string(),
+            onclick: swap,
+            variant: Some("secondary".to_string()),
+        }
+        ActionButton {
+            text: "Clear All".to_string(),
+            onclick: clear_all,
+            variant: Some("secondary".to_string()),
+        }
+    };
+
+    let (comparison, error) = match result() {
+        Ok(comparison) => (comparison, String::new()),
+        Err(e) => (None, e),
+    };
+
+    let output = comparison.map(|comparison| {
+        let diff = &comparison.diff;
+        let patch = serde_json::to_string_pretty(&diff.patch).unwrap_or_default();
+
+        let body = match view().as_str() {
+            "changes" => rsx! {
+                div { class: "max-h-96 overflow-auto divide-y divide-ctp-surface1 bg-ctp-surface0 border border-ctp-surface2 rounded-md",
+                    if diff.changes.is_empty() {
+                        div { class: "px-3 py-2 text-sm text-ctp-subtext0", "No differences" }
+                    }
+                    for (i , change) in diff.changes.iter().enumerate() {
+                        div { key: "{i}", class: "flex items-center gap-3 px-3 py-2 text-sm",
+                            span { class: "w-20 shrink-0 px-1.5 text-xs text-center border rounded {badge_class(change.kind)}",
+                                "{change.kind.label()}"
+                            }
+                            span { class: "font-mono text-ctp-blue break-all", "{change.path}" }
+                            span { class: "font-mono text-ctp-subtext1 break-all",
+                                match change.kind {
+                                    ChangeKind::Changed => format!("{} → {}", inline_value(&change.old), inline_value(&change.new)),
+                                    ChangeKind::Added => inline_value(&change.new),
+                                    ChangeKind::Removed => inline_value(&change.old),
+                                    ChangeKind::Moved => format!("from {}", change.left_pointer.clone().unwrap_or_default()),
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "patch" => rsx! {
+                ToolTextarea { value: patch.clone(), placeholder: String::new(), rows: Some(12), readonly: Some(true) }
+            },
+            _ => {
+                let mut left_marks = HashMap::new();
+                let mut right_marks = HashMap::new();
+                for change in &diff.changes {
+                    if let Some(pointer) = &change.left_pointer {
+                        left_marks.insert(pointer.as_str(), change.kind);
+                    }
+                    if let Some(pointer) = &change.right_pointer {
+                        right_marks.insert(pointer.as_str(), change.kind);
+                    }
+                }
+                rsx! {
+                    div { class: "grid grid-cols-1 lg:grid-cols-2 gap-4",
+                        {diff_pane("Original", &comparison.left, &left_marks)}
+                        {diff_pane("Modified", &comparison.right, &right_marks)}
+                    }
+                }
+            }
+        };
+
+        rsx! {
+            StatsDisplay {
+                stats: vec![
+                    ("Added".to_string(), diff.count(ChangeKind::Added).to_string()),
+                    ("Removed".to_string(), diff.count(ChangeKind::Removed).to_string()),
+                    ("Changed".to_string(), diff.count(ChangeKind::Changed).to_string()),
+                    ("Moved".to_string(), diff.count(ChangeKind::Moved).to_string()),
+                ],
+            }
+            ModeSelector {
+                current_mode: view(),
+                modes: vec![
+                    ("side".to_string(), "Side by Side".to_string()),
+                    ("changes".to_string(), "Changes".to_string()),
+                    ("patch".to_string(), "JSON Patch".to_string()),
+                ],
+                on_change: move |mode| view.set(mode),
+                label: Some("Output View".to_string()),
+            }
+            OutputSection {
+                label: "Differences".to_string(),
+                helper_text: Some(
+                    if view() == "patch" {
+                        "RFC 6902 operations that turn the original into the modified document".to_string()
+                    } else {
+                        "Key order and whitespace are ignored".to_string()
+                    },
+                ),
+                copy_button: if view() == "patch" { Some(rsx! {
+                    CopyButton { text: patch }
+                }) } else { None },
+                output: body,
+            }
+        }
+    });
+
+    rsx! {
+        div { class: "space-y-4",
+            div { class: "flex flex-wrap items-end justify-center gap-6",
+                ModeSelector {
+                    current_mode: arrays(),
+                    modes: vec![
+                        ("index".to_string(), "By Index".to_string()),
+                        ("key".to_string(), "By Key Field".to_string()),
+                    ],
+                    on_change: move |mode| arrays.set(mode),
+                    label: Some("Match Array Elements".to_string()),
+                }
+                if arrays() == "key" {
+                    div { class: "w-40",
+                        ToolInput {
+                            value: key_field(),
+                            placeholder: "id".to_string(),
+                            oninput: Some(EventHandler::new(move |event: FormEvent| key_field.set(event.value()))),
+                        }
+                    }
+                }
+            }
+
+            ToolGrid {
+                left_content,
+                right_content,
+                actions: Some(actions),
+                error_message: Some(error),
+            }
+
+            {output}
+        }
+    }
+}
+
+/// Signals owned by the JSON diff tab
+#[derive(Clone, Copy, PartialEq)]
+pub struct JsonDiffState {
+    left: Signal<String>,
+    right: Signal<String>,
+    arrays: Signal<String>,
+    key_field: Signal<String>,
+    view: Signal<String>,
+}
+
+/// Registry entry for [`JsonDiff`]
+pub struct JsonDiffTool;
+
+impl Tool for JsonDiffTool {
+    type State = JsonDiffState;
+
+    fn id(&self) -> &'static str {
+        "json-diff"
+    }
+
+    fn icon(&self) -> &'static str {
+        "⇄"
+    }
+
+    fn title(&self) -> &'static str {
+        "JSON Diff"
+    }
+
+    fn subtitle(&self) -> &'static str {
+        "Compare & Patch"
+    }
+
+    fn create_state(&self) -> Self::State {
+        JsonDiffState {
+            left: Signal::new(String::new()),
+            right: Signal::new(String::new()),
+            arrays: Signal::new("index".to_string()),
+            key_field: Signal::new("id".to_string()),
+            view: Signal::new("side".to_string()),
+        }
+    }
+
+    fn render(&self, state: Self::State) -> Element {
+        rsx! {
+            JsonDiff {
+                left: state.left,
+                right: state.right,
+                arrays: state.arrays,
+                key_field: state.key_field,
+                view: state.view,
+            }
+        }
+    }
+
+    fn apply_query(&self, mut state: Self::State, query: &ToolQuery) {
+        let arrays = match query.get("arrays") {
+            Some("key") => "key",
+            _ => "index",
+        };
+        let view = query
+            .get("view")
+            .filter(|view| matches!(*view, "changes" | "patch"))
+            .unwrap_or("side");
+        state
+            .left
+            .set(query.get("left").unwrap_or_default().to_string());
+        state
+            .right
+            .set(query.get("right").unwrap_or_default().to_string());
+        state.arrays.set(arrays.to_string());
+        state
+            .key_field
+            .set(query.get("key").unwrap_or("id").to_string());
+        state.view.set(view.to_string());
+    }
+
+    fn to_query(&self, state: Self::State) -> ToolQuery {
+        let arrays = state.arrays.peek().clone();
+        let view = state.view.peek().clone();
+        let query = ToolQuery::default()
+            .with("left", state.left.peek().clone())
+            .with("right", state.right.peek().clone());
+        if arrays == "key" {
+            query
+                .with("arrays", arrays)
+                .with("key", state.key_field.peek().clone())
+        } else {
+            query
+        }
+        .with("view", if view == "side" { String::new() } else { view })
+    }
+}
@@ -1,5 +1,52 @@
+use crate::components::tools::json_tree::JsonTree;
+use crate::components::tools::registry::Tool;
 use crate::components::tools::shared::*;
+use crate::json::error::{ErrorLocation, ParseError};
+use crate::json::format::{format_value, FormatMode, FormatOptions, Indent};
+use crate::json::lenient::{self, Dialect};
+use crate::json::query::{self, Language};
+use crate::json::schema::{self, Draft, Violation};
+use crate::json::types::{self, Target};
+use crate::types::ToolQuery;
 use dioxus::prelude::*;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// Id of the input textarea, used to scroll it to parse errors
+const INPUT_ID: &str = "json-formatter-input";
+
+/// Reads `json_str` in `dialect` and formats it per `options`, returning the
+/// formatted text and a list of what was normalized on the way in
+fn format_json_str(
+    json_str: &str,
+    dialect: Dialect,
+    options: &FormatOptions,
+) -> Result<(String, Vec<String>), ParseError> {
+    let (parsed, normalized) = lenient::parse(json_str, dialect)?;
+    let formatted = format_value(parsed, options).map_err(|e| ParseError {
+        message: format!("Formatting error: {}", e),
+        location: None,
+    })?;
+    Ok((formatted, normalized.summary()))
+}
+
+/// Selects the error position in the textarea `input_id` and scrolls it into view
+fn reveal_in_input(input_id: &str, location: &ErrorLocation) {
+    let eval = document::eval(
+        r#"
+        const [id, offset, line] = await dioxus.recv();
+        const area = document.getElementById(id);
+        if (area) {
+            area.focus();
+            area.setSelectionRange(offset, offset + 1);
+            const lineHeight = parseFloat(getComputedStyle(area).lineHeight) || 20;
+            area.scrollTop = Math.max(0, (line - 1) * lineHeight - area.clientHeight / 2);
+        }
+        "#,
+    );
+    let _ = eval.send((input_id, location.offset, location.line));
+}
 
 /// JSON Formatter component
 #[component]
@@ -7,43 +54,124 @@ pub fn JsonFormatter(
     input: Signal<String>,
     output: Signal<String>,
     error: Signal<String>,
+    location: Signal<Option<ErrorLocation>>,
+    dialect: Signal<Dialect>,
+    notes: Signal<Vec<String>>,
+    options: Signal<FormatOptions>,
+    view: Signal<String>,
+    expanded: Signal<HashSet<String>>,
+    query: Signal<String>,
+    schema: Signal<String>,
+    schema_draft: Signal<String>,
+    check_formats: Signal<bool>,
+    root_name: Signal<String>,
 ) -> Element {
     let mut input = input;
     let mut output = output;
     let mut error = error;
+    let mut location = location;
+    let mut dialect = dialect;
+    let mut notes = notes;
+    let mut options = options;
+    let mut view = view;
+    let mut query = query;
+    let mut schema = schema;
+    let mut schema_draft = schema_draft;
+    let mut check_formats = check_formats;
+    let mut root_name = root_name;
+
+    // Only parse the output while the tree, a query or a schema needs it
+    let document = use_memo(move || {
+        if view() == "text" && query().trim().is_empty() && schema().trim().is_empty() {
+            return None;
+        }
+        serde_json::from_str::<Value>(&output()).ok().map(Rc::new)
+    });
+
+    // Result count and pretty-printed results of the current query
+    let query_result = use_memo(move || {
+        let text = query();
+        if text.trim().is_empty() {
+            return None;
+        }
+        let document = document()?;
+        let language = Language::detect(&text);
+        Some(query::evaluate(&text, &document).map(|results| {
+            let count = results.len();
+            (count, query::format_results(language, results))
+        }))
+    });
+
+    // Rust or TypeScript source for the type views
+    let generated = use_memo(move || {
+        let target = Target::from_id(&view())?;
+        let document = document()?;
+        Some(types::generate(&document, &root_name(), target))
+    });
+
+    // Draft used and violations found, once both schema and document parse
+    let validation = use_memo(move || {
+        let text = schema();
+        if text.trim().is_empty() {
+            return None;
+        }
+        let schema_value = match serde_json::from_str::<Value>(&text) {
+            Ok(value) => value,
+            Err(e) => {
+                let message = ParseError::from_serde(&text, &e).message;
+                return Some(Err(format!("Schema: {}", message)));
+            }
+        };
+        if !schema_value.is_object() && !schema_value.is_boolean() {
+            return Some(Err("Schema: must be an object or a boolean".to_string()));
+        }
+        let document = document()?;
+        let draft = Draft::from_id(&schema_draft()).unwrap_or_else(|| Draft::detect(&schema_value));
+        let violations = schema::validate(&schema_value, &document, draft, check_formats());
+        Some(Ok((draft, violations)))
+    });
 
-    let mut format_json = move |_| {
+    // With `reveal`, a parse error is also selected in the input. Typing
+    // leaves the caret alone, since a selection there would eat the next key.
+    let mut format_json = move |reveal: bool| {
         let json_str = input();
         error.set(String::new());
+        location.set(None);
+        notes.set(Vec::new());
 
         if json_str.trim().is_empty() {
             output.set(String::new());
             return;
         }
 
-        match serde_json::from_str::<serde_json::Value>(&json_str) {
-            Ok(parsed) => match serde_json::to_string_pretty(&parsed) {
-                Ok(formatted) => output.set(formatted),
-                Err(e) => error.set(format!("Formatting error: {}", e)),
-            },
+        match format_json_str(&json_str, dialect(), &options()) {
+            Ok((formatted, normalized)) => {
+                output.set(formatted);
+                notes.set(normalized);
+            }
             Err(e) => {
-                error.set(format!("JSON Parse Error: {}", e));
+                if let (true, Some(at)) = (reveal, &e.location) {
+                    reveal_in_input(INPUT_ID, at);
+                }
+                error.set(e.message);
+                location.set(e.location);
                 output.set(String::new());
             }
         }
     };
 
+    let mut update_options = move |change: fn(&mut FormatOptions, &str), value: String| {
+        options.with_mut(|o| change(o, &value));
+        format_json(true);
+    };
+
     let clear_all = move |_| {
         input.set(String::new());
+        schema.set(String::new());
         output.set(String::new());
         error.set(String::new());
-    };
-
-    let copy_output = move |_| {
-        if !output().is_empty() {
-            // TODO: Implement actual clipboard functionality
-            println!("📋 Copied JSON to clipboard");
-        }
+        location.set(None);
+        notes.set(Vec::new());
     };
 
     let left_content = rsx! {
@@ -52,13 +180,17 @@ pub fn JsonFormatter(
             helper_text: Some("Paste JSON to format & validate".to_string()),
             input: rsx! {
                 ToolTextarea {
+                    id: Some(INPUT_ID.to_string()),
                     value: input(),
                     placeholder: "Paste JSON here...".to_string(),
                     rows: Some(6),
                     oninput: Some(
                         EventHandler::new(move |event: FormEvent| {
-                            input.set(event.value());
-                            format_json(());
+                            let value = event.value();
+                            // More than one new character means a paste or a drop
+                            let pasted = value.chars().count() > input.peek().chars().count() + 1;
+                            input.set(value);
+                            format_json(pasted);
                         }),
                     ),
                 }
@@ -66,24 +198,131 @@ pub fn JsonFormatter(
         }
     };
 
+    let target = Target::from_id(&view());
+    let shown = match target {
+        Some(_) => generated().unwrap_or_default(),
+        None => output(),
+    };
+
+    let output_view = if view() == "tree" {
+        rsx! {
+            JsonTree { value: document, expanded }
+        }
+    } else {
+        rsx! {
+            ToolTextarea {
+                value: shown.clone(),
+                placeholder: match target {
+                    Some(target) => format!("{} types will appear here...", target.label()),
+                    None => "Formatted JSON will appear here...".to_string(),
+                },
+                rows: Some(6),
+                readonly: Some(true),
+            }
+        }
+    };
+
     let right_content = rsx! {
         OutputSection {
-            label: "Formatted JSON".to_string(),
-            helper_text: Some("Formatted & validated output".to_string()),
-            copy_button: if !output().is_empty() { Some(rsx! {
-                CopyButton { text: output(), onclick: copy_output }
+            label: match target {
+                Some(target) => format!("{} Types", target.label()),
+                None => "Formatted JSON".to_string(),
+            },
+            helper_text: Some(
+                match target {
+                    Some(Target::Rust) => "serde structs inferred from the sample".to_string(),
+                    Some(Target::TypeScript) => "Interfaces inferred from the sample".to_string(),
+                    None => "Formatted & validated output".to_string(),
+                },
+            ),
+            copy_button: if !shown.is_empty() { Some(rsx! {
+                CopyButton { text: shown.clone() }
             }) } else { None },
-            output: rsx! {
+            output: output_view,
+        }
+    };
+
+    let notes_summary = notes().join(", ");
+    let language = Language::detect(&query()).label();
+    let (validation, schema_error) = match validation() {
+        Some(Ok(result)) => (Some(result), String::new()),
+        Some(Err(e)) => (None, e),
+        None => (None, String::new()),
+    };
+    let validation_message = match &validation {
+        Some((draft, violations)) if violations.is_empty() => {
+            format!("Document is valid against the {} schema", draft.label())
+        }
+        _ => String::new(),
+    };
+
+    let schema_input = rsx! {
+        InputSection {
+            label: "JSON Schema".to_string(),
+            helper_text: Some("Paste a Draft 7 or 2020-12 schema to validate the document".to_string()),
+            input: rsx! {
                 ToolTextarea {
-                    value: output(),
-                    placeholder: "Formatted JSON will appear here...".to_string(),
+                    value: schema(),
+                    placeholder: "Paste JSON Schema here...".to_string(),
                     rows: Some(6),
-                    readonly: Some(true),
+                    oninput: Some(EventHandler::new(move |event: FormEvent| schema.set(event.value()))),
                 }
             },
         }
     };
 
+    let violations_view = match &validation {
+        Some((draft, violations)) if !violations.is_empty() => {
+            let report = violations_json(violations);
+            rsx! {
+                OutputSection {
+                    label: format!("{} Violation{}", violations.len(), if violations.len() == 1 { "" } else { "s" }),
+                    helper_text: Some(format!("Validated as {}", draft.label())),
+                    copy_button: Some(rsx! {
+                        CopyButton { text: report }
+                    }),
+                    output: rsx! {
+                        div { class: "max-h-96 overflow-auto divide-y divide-ctp-surface1 bg-ctp-surface0 border border-ctp-surface2 rounded-md",
+                            for (i , violation) in violations.iter().enumerate() {
+                                div { key: "{i}", class: "px-3 py-2 text-sm space-y-1",
+                                    div { class: "text-ctp-text", "{violation.message}" }
+                                    div { class: "flex flex-wrap gap-x-4 font-mono text-xs",
+                                        span { class: "text-ctp-blue break-all",
+                                            "instance {display_pointer(&violation.instance_path)}"
+                                        }
+                                        span { class: "text-ctp-overlay1 break-all",
+                                            "schema {display_pointer(&violation.schema_path)}"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                }
+            }
+        }
+        _ => rsx! {
+            OutputSection {
+                label: "Violations".to_string(),
+                helper_text: Some("Each violation lists its instance path and schema path".to_string()),
+                output: rsx! {
+                    div { class: "px-3 py-2 text-sm text-ctp-subtext0 bg-ctp-surface0 border border-ctp-surface2 rounded-md",
+                        if validation.is_some() {
+                            "No violations"
+                        } else {
+                            "Paste a schema and a document to validate"
+                        }
+                    }
+                },
+            }
+        },
+    };
+
+    let error_message = match query_result() {
+        Some(Err(e)) if error().is_empty() => e,
+        _ => error(),
+    };
+
     let actions = rsx! {
         ActionButton {
             text: "Clear All".to_string(),
@@ -93,11 +332,411 @@ pub fn JsonFormatter(
     };
 
     rsx! {
-        ToolGrid {
-            left_content,
-            right_content,
-            actions: Some(actions),
-            error_message: Some(error()),
+        div { class: "space-y-4",
+            div { class: "flex flex-wrap justify-center gap-6",
+                ModeSelector {
+                    current_mode: dialect().id().to_string(),
+                    modes: Dialect::ALL
+                        .iter()
+                        .map(|d| (d.id().to_string(), d.label().to_string()))
+                        .collect(),
+                    on_change: move |id: String| {
+                        dialect.set(Dialect::from_id(&id).unwrap_or_default());
+                        format_json(true);
+                    },
+                    label: Some("Input Dialect".to_string()),
+                }
+                ModeSelector {
+                    current_mode: options().mode.id().to_string(),
+                    modes: FormatMode::ALL
+                        .iter()
+                        .map(|mode| (mode.id().to_string(), mode.label().to_string()))
+                        .collect(),
+                    on_change: move |id: String| {
+                        update_options(|o, id| o.mode = FormatMode::from_id(id).unwrap_or_default(), id)
+                    },
+                    label: Some("Format".to_string()),
+                }
+                if options().mode != FormatMode::Minify {
+                    ModeSelector {
+                        current_mode: options().indent.id().to_string(),
+                        modes: Indent::ALL
+                            .iter()
+                            .map(|indent| (indent.id().to_string(), indent.label().to_string()))
+                            .collect(),
+                        on_change: move |id: String| {
+                            update_options(|o, id| o.indent = Indent::from_id(id).unwrap_or_default(), id)
+                        },
+                        label: Some("Indentation".to_string()),
+                    }
+                }
+                ModeSelector {
+                    current_mode: view(),
+                    modes: [("text", "Text"), ("tree", "Tree")]
+                        .into_iter()
+                        .chain(Target::ALL.iter().map(|t| (t.id(), t.label())))
+                        .map(|(id, label)| (id.to_string(), label.to_string()))
+                        .collect(),
+                    on_change: move |mode| view.set(mode),
+                    label: Some("Output View".to_string()),
+                }
+                if target.is_some() {
+                    div { class: "w-48",
+                        ToolInput {
+                            value: root_name(),
+                            placeholder: "Root type name".to_string(),
+                            oninput: Some(EventHandler::new(move |event: FormEvent| root_name.set(event.value()))),
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex flex-wrap justify-center gap-6",
+                ToolCheckbox {
+                    label: "Escape non-ASCII as \\uXXXX".to_string(),
+                    checked: options().escape_unicode,
+                    on_change: move |on: bool| {
+                        update_options(|o, v| o.escape_unicode = v == "true", on.to_string())
+                    },
+                }
+                ToolCheckbox {
+                    label: "Keep big numbers exact".to_string(),
+                    checked: options().exact_numbers,
+                    on_change: move |on: bool| {
+                        update_options(|o, v| o.exact_numbers = v == "true", on.to_string())
+                    },
+                }
+            }
+
+            ToolGrid {
+                left_content,
+                right_content,
+                actions: Some(actions),
+                error_message: Some(error_message),
+            }
+
+            InputSection {
+                label: format!("Query ({})", language),
+                helper_text: Some(
+                    "JSONPath starts with $; anything else is read as jq".to_string(),
+                ),
+                input: rsx! {
+                    ToolInput {
+                        value: query(),
+                        placeholder: "$.items[*].id or .items[] | select(.active) | .id".to_string(),
+                        oninput: Some(EventHandler::new(move |event: FormEvent| query.set(event.value()))),
+                    }
+                },
+            }
+
+            div { class: "flex flex-wrap justify-center gap-6",
+                ModeSelector {
+                    current_mode: schema_draft(),
+                    modes: std::iter::once(("auto".to_string(), "Auto-detect".to_string()))
+                        .chain(Draft::ALL.iter().map(|d| (d.id().to_string(), d.label().to_string())))
+                        .collect(),
+                    on_change: move |id| schema_draft.set(id),
+                    label: Some("Schema Draft".to_string()),
+                }
+                ToolCheckbox {
+                    label: "Validate formats".to_string(),
+                    checked: check_formats(),
+                    on_change: move |on| check_formats.set(on),
+                }
+            }
+
+            ToolGrid {
+                left_content: schema_input,
+                right_content: violations_view,
+                error_message: Some(schema_error),
+                success_message: Some(validation_message),
+            }
+
+            if let Some(Ok((count, results))) = query_result() {
+                OutputSection {
+                    label: "Query Result".to_string(),
+                    helper_text: Some(
+                        format!("{} result{} ({})", count, if count == 1 { "" } else { "s" }, language),
+                    ),
+                    copy_button: Some(rsx! {
+                        CopyButton { text: results.clone() }
+                    }),
+                    output: rsx! {
+                        ToolTextarea {
+                            value: results,
+                            placeholder: "No results".to_string(),
+                            rows: Some(6),
+                            readonly: Some(true),
+                        }
+                    },
+                }
+            }
+
+            if !notes().is_empty() {
+                div { class: "px-4 py-3 text-sm text-ctp-yellow bg-ctp-surface0 border border-ctp-yellow/50 rounded-md",
+                    "Normalized to strict JSON: {notes_summary}"
+                }
+            }
+
+            if let Some(at) = location() {
+                ErrorExcerpt { location: at, input_id: INPUT_ID.to_string() }
+            }
+        }
+    }
+}
+
+/// JSON Pointer for display, with the document root spelled out
+fn display_pointer(pointer: &str) -> &str {
+    if pointer.is_empty() {
+        "(root)"
+    } else {
+        pointer
+    }
+}
+
+/// Violations as a JSON array, for copying into bug reports
+fn violations_json(violations: &[Violation]) -> String {
+    let report: Vec<Value> = violations
+        .iter()
+        .map(|v| {
+            serde_json::json!({
+                "instancePath": v.instance_path,
+                "schemaPath": v.schema_path,
+                "message": v.message,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&report).unwrap_or_default()
+}
+
+/// Offending input line with a caret under the error and suggested fixes.
+/// `input_id` is the textarea the error is revealed in.
+#[component]
+pub fn ErrorExcerpt(location: ErrorLocation, input_id: String) -> Element {
+    let caret = location.caret();
+    let gutter = location.line.to_string();
+    let blank_gutter = " ".repeat(gutter.len());
+
+    rsx! {
+        div { class: "bg-ctp-surface0 border border-ctp-red/50 rounded-md p-4 space-y-3",
+            div { class: "flex items-center justify-between text-sm",
+                span { class: "text-ctp-red font-medium",
+                    "Line {location.line}, column {location.column}"
+                }
+                button {
+                    class: "px-2 py-1 text-xs bg-ctp-surface1 hover:bg-ctp-surface2 text-ctp-text rounded-md transition-colors",
+                    onclick: {
+                        let location = location.clone();
+                        move |_| reveal_in_input(&input_id, &location)
+                    },
+                    "Show in input"
+                }
+            }
+            pre { class: "overflow-x-auto font-mono text-sm text-ctp-text",
+                span { class: "text-ctp-overlay0", "{gutter} | " }
+                "{location.excerpt}\n"
+                span { class: "text-ctp-overlay0", "{blank_gutter} | " }
+                span { class: "text-ctp-red font-bold", "{caret}" }
+            }
+            if !location.suggestions.is_empty() {
+                ul { class: "list-disc list-inside text-sm text-ctp-subtext1 space-y-1",
+                    for suggestion in location.suggestions.iter() {
+                        li { "{suggestion}" }
+                    }
+                }
+            }
         }
     }
 }
+
+/// Signals owned by the JSON formatter tab
+#[derive(Clone, Copy, PartialEq)]
+pub struct JsonFormatterState {
+    input: Signal<String>,
+    output: Signal<String>,
+    error: Signal<String>,
+    location: Signal<Option<ErrorLocation>>,
+    dialect: Signal<Dialect>,
+    notes: Signal<Vec<String>>,
+    options: Signal<FormatOptions>,
+    view: Signal<String>,
+    expanded: Signal<HashSet<String>>,
+    query: Signal<String>,
+    schema: Signal<String>,
+    schema_draft: Signal<String>,
+    check_formats: Signal<bool>,
+    root_name: Signal<String>,
+}
+
+/// Registry entry for [`JsonFormatter`]
+pub struct JsonFormatterTool;
+
+impl Tool for JsonFormatterTool {
+    type State = JsonFormatterState;
+
+    fn id(&self) -> &'static str {
+        "json"
+    }
+
+    fn icon(&self) -> &'static str {
+        "📄"
+    }
+
+    fn title(&self) -> &'static str {
+        "JSON"
+    }
+
+    fn subtitle(&self) -> &'static str {
+        "Format & Validate"
+    }
+
+    fn create_state(&self) -> Self::State {
+        JsonFormatterState {
+            input: Signal::new(String::new()),
+            output: Signal::new(String::new()),
+            error: Signal::new(String::new()),
+            location: Signal::new(None),
+            dialect: Signal::new(Dialect::default()),
+            notes: Signal::new(Vec::new()),
+            options: Signal::new(FormatOptions::default()),
+            view: Signal::new("text".to_string()),
+            expanded: Signal::new(HashSet::from(["$".to_string()])),
+            query: Signal::new(String::new()),
+            schema: Signal::new(String::new()),
+            schema_draft: Signal::new("auto".to_string()),
+            check_formats: Signal::new(true),
+            root_name: Signal::new("Root".to_string()),
+        }
+    }
+
+    fn render(&self, state: Self::State) -> Element {
+        rsx! {
+            JsonFormatter {
+                input: state.input,
+                output: state.output,
+                error: state.error,
+                location: state.location,
+                dialect: state.dialect,
+                notes: state.notes,
+                options: state.options,
+                view: state.view,
+                expanded: state.expanded,
+                query: state.query,
+                schema: state.schema,
+                schema_draft: state.schema_draft,
+                check_formats: state.check_formats,
+                root_name: state.root_name,
+            }
+        }
+    }
+
+    fn apply_query(&self, mut state: Self::State, query: &ToolQuery) {
+        let defaults = FormatOptions::default();
+        let options = FormatOptions {
+            mode: query
+                .get("format")
+                .and_then(FormatMode::from_id)
+                .unwrap_or(defaults.mode),
+            indent: query
+                .get("indent")
+                .and_then(Indent::from_id)
+                .unwrap_or(defaults.indent),
+            escape_unicode: query
+                .get("ascii")
+                .map_or(defaults.escape_unicode, |v| v == "1"),
+            exact_numbers: query
+                .get("exact")
+                .map_or(defaults.exact_numbers, |v| v == "1"),
+        };
+        state.options.set(options);
+        let dialect = query
+            .get("dialect")
+            .and_then(Dialect::from_id)
+            .unwrap_or_default();
+        state.dialect.set(dialect);
+
+        let input = query.get("input").unwrap_or_default().to_string();
+        let (output, notes, error) = if input.trim().is_empty() {
+            (String::new(), Vec::new(), None)
+        } else {
+            match format_json_str(&input, dialect, &options) {
+                Ok((formatted, notes)) => (formatted, notes, None),
+                Err(e) => (String::new(), Vec::new(), Some(e)),
+            }
+        };
+        state.output.set(output);
+        state.notes.set(notes);
+        state.error.set(
+            error
+                .as_ref()
+                .map(|e| e.message.clone())
+                .unwrap_or_default(),
+        );
+        state.location.set(error.and_then(|e| e.location));
+        state.input.set(input);
+        let view = query
+            .get("view")
+            .filter(|v| *v == "tree" || Target::from_id(v).is_some())
+            .unwrap_or("text");
+        state.view.set(view.to_string());
+        state
+            .root_name
+            .set(query.get("root").unwrap_or("Root").to_string());
+        state
+            .query
+            .set(query.get("q").unwrap_or_default().to_string());
+        state
+            .schema
+            .set(query.get("schema").unwrap_or_default().to_string());
+        let draft = query
+            .get("draft")
+            .and_then(Draft::from_id)
+            .map_or("auto", Draft::id);
+        state.schema_draft.set(draft.to_string());
+        state
+            .check_formats
+            .set(query.get("formats").is_none_or(|v| v == "1"));
+    }
+
+    fn to_query(&self, state: Self::State) -> ToolQuery {
+        let view = state.view.peek().clone();
+        let options = *state.options.peek();
+        let defaults = FormatOptions::default();
+        let non_default = |value: &str, default: &str| {
+            if value == default {
+                String::new()
+            } else {
+                value.to_string()
+            }
+        };
+        let flag = |on: bool| if on { "1" } else { "0" };
+        ToolQuery::default()
+            .with("input", state.input.peek().clone())
+            .with(
+                "dialect",
+                non_default(state.dialect.peek().id(), Dialect::default().id()),
+            )
+            .with("format", non_default(options.mode.id(), defaults.mode.id()))
+            .with(
+                "indent",
+                non_default(options.indent.id(), defaults.indent.id()),
+            )
+            .with(
+                "ascii",
+                non_default(flag(options.escape_unicode), flag(defaults.escape_unicode)),
+            )
+            .with(
+                "exact",
+                non_default(flag(options.exact_numbers), flag(defaults.exact_numbers)),
+            )
+            .with("view", non_default(&view, "text"))
+            .with("root", non_default(&state.root_name.peek(), "Root"))
+            .with("q", state.query.peek().clone())
+            .with("schema", state.schema.peek().clone())
+            .with("draft", non_default(&state.schema_draft.peek(), "auto"))
+            .with(
+                "formats",
+                non_default(flag(*state.check_formats.peek()), "1"),
+            )
+    }
+}
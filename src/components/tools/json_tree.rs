@@ -0,0 +1,157 @@
+use crate::components::tools::shared::copy_to_clipboard;
+use crate::json::tree::{container_paths, visible_rows, ValueKind};
+use dioxus::prelude::*;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// Rows rendered per page; "Show more" reveals the next page
+const PAGE_ROWS: usize = 500;
+
+fn value_class(kind: ValueKind) -> &'static str {
+    match kind {
+        ValueKind::String => "text-ctp-green",
+        ValueKind::Number => "text-ctp-peach",
+        ValueKind::Bool => "text-ctp-mauve",
+        ValueKind::Null => "text-ctp-overlay0",
+        ValueKind::Object | ValueKind::Array => "text-ctp-subtext0",
+    }
+}
+
+/// Collapsible tree view of a parsed JSON document
+#[component]
+pub fn JsonTree(value: Memo<Option<Rc<Value>>>, expanded: Signal<HashSet<String>>) -> Element {
+    let mut expanded = expanded;
+    let mut row_limit = use_signal(|| PAGE_ROWS);
+    let mut status = use_signal(String::new);
+
+    let Some(root) = value() else {
+        return rsx! {
+            div { class: "px-3 py-2 text-sm text-ctp-overlay0 bg-ctp-surface0 border border-ctp-surface2 rounded-md",
+                "Tree view will appear here..."
+            }
+        };
+    };
+
+    let (rows, truncated) = visible_rows(&root, &expanded.read(), row_limit());
+
+    let copy = move |label: String, text: String| {
+        spawn(async move {
+            if copy_to_clipboard(&text).await {
+                status.set(format!("Copied {}", label));
+            } else {
+                status.set("Copy failed".to_string());
+            }
+            gloo_timers::future::TimeoutFuture::new(1500).await;
+            status.set(String::new());
+        });
+    };
+
+    let copy_value = move |pointer: String, path: String| {
+        let text = value()
+            .and_then(|root| root.pointer(&pointer).map(serde_json::to_string_pretty))
+            .and_then(Result::ok);
+        if let Some(text) = text {
+            copy(format!("value of {}", path), text);
+        }
+    };
+
+    let mut toggle = move |path: String| {
+        let mut set = expanded.write();
+        if !set.remove(&path) {
+            set.insert(path);
+        }
+    };
+
+    let expand_all = move |_| {
+        if let Some(root) = value() {
+            expanded.set(container_paths(&root));
+        }
+    };
+
+    let collapse_all = move |_| {
+        expanded.set(HashSet::from(["$".to_string()]));
+        row_limit.set(PAGE_ROWS);
+    };
+
+    rsx! {
+        div { class: "space-y-2",
+            div { class: "flex items-center gap-2 text-xs",
+                button {
+                    class: "px-2 py-1 bg-ctp-surface1 hover:bg-ctp-surface2 text-ctp-text rounded-md transition-colors",
+                    onclick: expand_all,
+                    "Expand all"
+                }
+                button {
+                    class: "px-2 py-1 bg-ctp-surface1 hover:bg-ctp-surface2 text-ctp-text rounded-md transition-colors",
+                    onclick: collapse_all,
+                    "Collapse all"
+                }
+                span { class: "ml-auto text-ctp-subtext0", "{status}" }
+            }
+            div { class: "max-h-96 overflow-auto py-1 font-mono text-sm bg-ctp-surface0 border border-ctp-surface2 rounded-md",
+                for row in rows {
+                    div {
+                        key: "{row.path}",
+                        class: "group flex items-center gap-2 pr-2 py-0.5 hover:bg-ctp-surface1 whitespace-nowrap",
+                        style: "padding-left: {row.depth as f32 * 1.25 + 0.5}rem",
+                        if row.child_count.is_some() {
+                            button {
+                                class: "w-4 text-ctp-subtext1 hover:text-ctp-text",
+                                onclick: {
+                                    let path = row.path.clone();
+                                    move |_| toggle(path.clone())
+                                },
+                                if row.expanded {
+                                    "▾"
+                                } else {
+                                    "▸"
+                                }
+                            }
+                        } else {
+                            span { class: "w-4" }
+                        }
+                        button {
+                            class: "text-ctp-blue hover:underline",
+                            title: "Copy path {row.path}",
+                            onclick: {
+                                let path = row.path.clone();
+                                move |_| copy(path.clone(), path.clone())
+                            },
+                            {row.label.clone().unwrap_or_else(|| "$".to_string())}
+                        }
+                        match row.child_count {
+                            Some(count) => {
+                                let (open, close) = if row.kind == ValueKind::Array { ("[", "]") } else { ("{", "}") };
+                                rsx! {
+                                    span { class: "text-ctp-subtext0", "{open}{count}{close}" }
+                                }
+                            }
+                            None => rsx! {
+                                span { class: "truncate {value_class(row.kind)}", "{row.preview}" }
+                            },
+                        }
+                        span { class: "px-1.5 text-xs text-ctp-overlay1 bg-ctp-surface1 rounded", "{row.kind.label()}" }
+                        button {
+                            class: "ml-auto text-xs text-ctp-subtext0 hover:text-ctp-text opacity-0 group-hover:opacity-100",
+                            title: "Copy value",
+                            onclick: {
+                                let pointer = row.pointer.clone();
+                                let path = row.path.clone();
+                                move |_| copy_value(pointer.clone(), path.clone())
+                            },
+                            "copy value"
+                        }
+                    }
+                }
+            }
+            if truncated {
+                button {
+                    class: "w-full px-2 py-1 text-xs bg-ctp-surface1 hover:bg-ctp-surface2 text-ctp-text rounded-md transition-colors",
+                    onclick: move |_| row_limit += PAGE_ROWS,
+                    "Show more"
+                }
+            }
+        }
+    }
+}
@@ -1,11 +1,27 @@
-pub mod base64_tool;
+pub mod csv_converter;
+pub mod encoding_tool;
+pub mod format_converter;
+pub mod hash_generator;
+pub mod hex_dump;
+pub mod json_diff;
 pub mod json_formatter;
+pub mod json_tree;
+pub mod jwt_decoder;
+pub mod qr_decoder;
 pub mod qr_generator;
+pub mod registry;
 pub mod shared;
 pub mod text_utilities;
 
-pub use base64_tool::*;
+pub use csv_converter::*;
+pub use encoding_tool::*;
+pub use format_converter::*;
+pub use hash_generator::*;
+pub use hex_dump::*;
+pub use json_diff::*;
 pub use json_formatter::*;
+pub use jwt_decoder::*;
 pub use qr_generator::*;
+pub use registry::*;
 pub use shared::*;
 pub use text_utilities::*;
@@ -0,0 +1,132 @@
+use crate::components::tools::shared::*;
+use crate::qr::decode::{decode_qr_image, DecodedQr};
+use crate::qr::payload::PayloadKind;
+use base64::{engine::general_purpose, Engine as _};
+use dioxus::prelude::*;
+
+/// QR Code Decoder component
+#[component]
+pub fn QRDecoder(
+    upload: Signal<Option<UploadedFile>>,
+    decoded: Signal<Vec<DecodedQr>>,
+    error: Signal<String>,
+) -> Element {
+    let mut upload = upload;
+    let mut decoded = decoded;
+    let mut error = error;
+
+    let decode_file = move |file: UploadedFile| {
+        match decode_qr_image(&file.bytes) {
+            Ok(codes) => {
+                decoded.set(codes);
+                error.set(String::new());
+            }
+            Err(e) => {
+                decoded.set(Vec::new());
+                error.set(e);
+            }
+        }
+        upload.set(Some(file));
+    };
+
+    let clear_all = move |_| {
+        upload.set(None);
+        decoded.set(Vec::new());
+        error.set(String::new());
+    };
+
+    let preview_uri = upload().map(|file| {
+        let mime = if file.bytes.starts_with(&[0xFF, 0xD8]) {
+            "image/jpeg"
+        } else {
+            "image/png"
+        };
+        format!(
+            "data:{};base64,{}",
+            mime,
+            general_purpose::STANDARD.encode(&file.bytes)
+        )
+    });
+
+    let left_content = rsx! {
+        InputSection {
+            label: "QR Code Image".to_string(),
+            helper_text: Some("PNG or JPEG; decoded locally in your browser".to_string()),
+            input: rsx! {
+                div { class: "space-y-3",
+                    FileDropZone {
+                        label: "Upload a QR code image".to_string(),
+                        accept: Some("image/png,image/jpeg".to_string()),
+                        on_file: decode_file,
+                    }
+                    if let Some(uri) = preview_uri {
+                        div { class: "flex items-center gap-3 text-xs text-ctp-subtext1",
+                            img {
+                                src: "{uri}",
+                                alt: "Uploaded image",
+                                class: "w-16 h-16 object-contain border border-ctp-surface2 rounded-md bg-white",
+                            }
+                            span { {upload().map(|f| f.name).unwrap_or_default()} }
+                        }
+                    }
+                }
+            },
+        }
+    };
+
+    let first = decoded().into_iter().next();
+
+    let right_content = rsx! {
+        OutputSection {
+            label: "Decoded Content".to_string(),
+            helper_text: Some(
+                if decoded().len() > 1 {
+                    format!("{} codes found; showing the first", decoded().len())
+                } else {
+                    "Text stored in the QR code".to_string()
+                },
+            ),
+            copy_button: if let Some(code) = first.clone() { Some(rsx! {
+                CopyButton { text: code.text }
+            }) } else { None },
+            output: rsx! {
+                ToolTextarea {
+                    value: first.clone().map(|code| code.text).unwrap_or_default(),
+                    placeholder: "Decoded text will appear here...".to_string(),
+                    rows: Some(6),
+                    readonly: Some(true),
+                }
+            },
+        }
+    };
+
+    let actions = rsx! {
+        ActionButton {
+            text: "Clear All".to_string(),
+            onclick: clear_all,
+            variant: Some("secondary".to_string()),
+        }
+    };
+
+    rsx! {
+        div { class: "space-y-4",
+            ToolGrid {
+                left_content,
+                right_content,
+                actions: Some(actions),
+                error_message: Some(error()),
+            }
+
+            if let Some(code) = first {
+                StatsDisplay {
+                    stats: vec![
+                        ("Version".to_string(), code.version.to_string()),
+                        ("ECC".to_string(), code.ecc.to_string()),
+                        ("Type".to_string(), PayloadKind::detect(&code.text).label().to_string()),
+                        ("Length".to_string(), code.text.len().to_string()),
+                    ],
+                }
+            }
+        }
+    }
+}
@@ -1,60 +1,149 @@
+use crate::components::tools::qr_decoder::QRDecoder;
+use crate::components::tools::registry::Tool;
 use crate::components::tools::shared::*;
+use crate::qr::decode::DecodedQr;
+use crate::qr::payload::{
+    suggested_filename, FieldKind, FieldSpec, FieldValues, PayloadKind, Template,
+};
+use crate::qr::{parse_hex_color, EcLevel, QrImage, QrStyle};
 use crate::server::generate_qr_code;
+use crate::types::ToolQuery;
 use dioxus::prelude::*;
 
 /// QR Code Generator component
 #[component]
-pub fn QRGenerator(input: Signal<String>, qr_url: Signal<String>) -> Element {
+pub fn QRGenerator(
+    input: Signal<String>,
+    qr_image: Signal<Option<QrImage>>,
+    ecc: Signal<EcLevel>,
+    style: Signal<QrStyle>,
+    template: Signal<Template>,
+    fields: Signal<FieldValues>,
+) -> Element {
     let mut input = input;
-    let mut qr_url = qr_url;
+    let mut qr_image = qr_image;
+    let mut ecc = ecc;
+    let mut style = style;
+    let mut template = template;
+    let mut fields = fields;
     let mut loading = use_signal(|| false);
+    let mut error = use_signal(String::new);
+    let mut payload_errors = use_signal(Vec::<String>::new);
 
-    let generate_qr = move |_| {
+    let mut generate = move || {
         let text = input();
         if text.trim().is_empty() {
             return;
         }
 
         loading.set(true);
+        error.set(String::new());
         spawn(async move {
-            match generate_qr_code(text).await {
-                Ok(url) => qr_url.set(url),
-                Err(e) => println!("QR generation error: {:?}", e),
+            match generate_qr_code(text, ecc(), style()).await {
+                Ok(image) => qr_image.set(Some(image)),
+                Err(ServerFnError::ServerError(e)) => error.set(e),
+                Err(e) => error.set(format!("QR generation error: {}", e)),
             }
             loading.set(false);
         });
     };
 
+    let generate_qr = move |_| generate();
+
+    // Builds the payload from the template form; free text is edited directly
+    let mut rebuild_payload = move || {
+        if template() == Template::Text {
+            payload_errors.set(Vec::new());
+            return;
+        }
+        match template().build(&fields.read()) {
+            Ok(payload) => {
+                input.set(payload);
+                payload_errors.set(Vec::new());
+                if qr_image().is_some() {
+                    generate();
+                }
+            }
+            Err(errors) => {
+                input.set(String::new());
+                payload_errors.set(errors);
+            }
+        }
+    };
+
+    // Re-render an existing code whenever an option changes
+    let mut update_style = move |apply: fn(&mut QrStyle, String), value: String| {
+        apply(&mut style.write(), value);
+        if qr_image().is_some() {
+            generate();
+        }
+    };
+
     let clear_all = move |_| {
         input.set(String::new());
-        qr_url.set(String::new());
+        fields.set(FieldValues::new());
+        payload_errors.set(Vec::new());
+        qr_image.set(None);
+        error.set(String::new());
     };
 
-    let copy_url = move |_| {
-        if !qr_url().is_empty() {
-            // TODO: Implement actual clipboard functionality
-            println!("📋 Copied QR code URL to clipboard");
+    let download_png = move |_: MouseEvent| {
+        if let Some(image) = qr_image() {
+            let filename = suggested_filename(&input(), "png");
+            download_bytes(&filename, "image/png", &image.png);
         }
     };
 
-    let download_qr = move |_: MouseEvent| {
-        if !qr_url().is_empty() {
-            // TODO: Implement actual download functionality
-            println!("💾 Downloaded QR code image");
+    let download_svg = move |_: MouseEvent| {
+        if let Some(image) = qr_image() {
+            let filename = suggested_filename(&input(), "svg");
+            download_bytes(&filename, "image/svg+xml", image.svg.as_bytes());
         }
     };
 
     let left_content = rsx! {
         InputSection {
-            label: "Text or URL".to_string(),
-            helper_text: Some("Enter text/URL to generate QR code".to_string()),
+            label: if template() == Template::Text { "Text or URL".to_string() } else { format!("{} Details", template().label()) },
+            helper_text: Some(
+                if template() == Template::Text {
+                    "Enter text/URL to generate QR code".to_string()
+                } else {
+                    "Fill in the fields; the payload is escaped for you".to_string()
+                },
+            ),
             input: rsx! {
                 div { class: "space-y-3",
-                    ToolInput {
-                        value: input(),
-                        placeholder: "Enter text or URL...".to_string(),
-                        input_type: Some("text".to_string()),
-                        oninput: Some(EventHandler::new(move |event: FormEvent| input.set(event.value()))),
+                    if template() == Template::Text {
+                        ToolInput {
+                            value: input(),
+                            placeholder: "Enter text or URL...".to_string(),
+                            input_type: Some("text".to_string()),
+                            oninput: Some(EventHandler::new(move |event: FormEvent| input.set(event.value()))),
+                        }
+                    } else {
+                        TemplateForm {
+                            specs: template().fields().to_vec(),
+                            values: fields(),
+                            on_change: move |(key, value): (String, String)| {
+                                fields.write().insert(key, value);
+                                rebuild_payload();
+                            },
+                        }
+                        if fields().values().any(|v| !v.trim().is_empty()) && !payload_errors().is_empty() {
+                            ul { class: "text-sm text-ctp-red space-y-1",
+                                for message in payload_errors() {
+                                    li { "• {message}" }
+                                }
+                            }
+                        }
+                        if !input().is_empty() {
+                            ToolTextarea {
+                                value: input(),
+                                placeholder: String::new(),
+                                rows: Some(4),
+                                readonly: Some(true),
+                            }
+                        }
                     }
                     ActionButton {
                         text: if loading() { "Generating...".to_string() } else { "Generate".to_string() },
@@ -71,13 +160,18 @@ pub fn QRGenerator(input: Signal<String>, qr_url: Signal<String>) -> Element {
         OutputSection {
             label: "Generated QR Code".to_string(),
             helper_text: Some("Scan with your device's camera".to_string()),
-            copy_button: if !qr_url().is_empty() { Some(rsx! {
+            copy_button: if let Some(image) = qr_image() { Some(rsx! {
                 div { class: "flex gap-2",
-                    CopyButton { text: qr_url(), onclick: copy_url }
+                    CopyButton { text: image.svg }
                     button {
                         class: "px-3 py-1 text-xs bg-ctp-surface2 hover:bg-ctp-surface0 text-ctp-text transition-colors rounded-md",
-                        onclick: move |event| download_qr(event),
-                        "💾 Download"
+                        onclick: download_png,
+                        "💾 PNG"
+                    }
+                    button {
+                        class: "px-3 py-1 text-xs bg-ctp-surface2 hover:bg-ctp-surface0 text-ctp-text transition-colors rounded-md",
+                        onclick: download_svg,
+                        "💾 SVG"
                     }
                 }
             }) } else { None },
@@ -88,10 +182,10 @@ pub fn QRGenerator(input: Signal<String>, qr_url: Signal<String>) -> Element {
                             div { class: "loading-pulse text-4xl mb-4", "⏳" }
                             div { class: "text-sm text-ctp-subtext1", "Generating QR code..." }
                         }
-                    } else if !qr_url().is_empty() {
+                    } else if let Some(image) = qr_image() {
                         div { class: "text-center space-y-4",
                             img {
-                                src: "{qr_url}",
+                                src: "{image.svg_data_uri()}",
                                 alt: "Generated QR Code",
                                 class: "max-w-full h-auto mx-auto border border-ctp-surface2 rounded-md bg-white p-4",
                             }
@@ -115,33 +209,129 @@ pub fn QRGenerator(input: Signal<String>, qr_url: Signal<String>) -> Element {
             onclick: clear_all,
             variant: Some("secondary".to_string()),
         }
-        if !qr_url().is_empty() {
+        if qr_image().is_some() {
+            ActionButton {
+                text: "Download PNG".to_string(),
+                onclick: download_png,
+                variant: Some("secondary".to_string()),
+            }
             ActionButton {
-                text: "Download QR Code".to_string(),
-                onclick: download_qr,
+                text: "Download SVG".to_string(),
+                onclick: download_svg,
                 variant: Some("secondary".to_string()),
             }
         }
     };
 
+    let ecc_levels = EcLevel::ALL
+        .iter()
+        .map(|level| (level.to_string(), level.to_string()))
+        .collect::<Vec<_>>();
+
+    let templates = Template::ALL
+        .iter()
+        .map(|t| (t.id().to_string(), t.label().to_string()))
+        .collect::<Vec<_>>();
+
     rsx! {
         div { class: "space-y-4",
+            // Payload template selection
+            ModeSelector {
+                label: Some("Payload Type".to_string()),
+                current_mode: template().id().to_string(),
+                modes: templates,
+                on_change: move |id: String| {
+                    template.set(Template::from_id(&id).unwrap_or_default());
+                    rebuild_payload();
+                },
+            }
+
+            // Error correction selection
+            ModeSelector {
+                label: Some("Error Correction".to_string()),
+                current_mode: ecc().to_string(),
+                modes: ecc_levels,
+                on_change: move |level: String| {
+                    ecc.set(level.parse().unwrap_or_default());
+                    if qr_image().is_some() {
+                        generate();
+                    }
+                },
+            }
+
+            // Rendering options
+            div { class: "grid grid-cols-2 lg:grid-cols-4 gap-3",
+                OptionField { label: "Module size (px)".to_string(),
+                    ToolInput {
+                        value: style().module_size.to_string(),
+                        placeholder: "8".to_string(),
+                        input_type: Some("number".to_string()),
+                        oninput: Some(
+                            EventHandler::new(move |event: FormEvent| {
+                                update_style(|s, v| s.module_size = v.parse().unwrap_or(0), event.value())
+                            }),
+                        ),
+                    }
+                }
+                OptionField { label: "Margin (modules)".to_string(),
+                    ToolInput {
+                        value: style().margin.to_string(),
+                        placeholder: "4".to_string(),
+                        input_type: Some("number".to_string()),
+                        oninput: Some(
+                            EventHandler::new(move |event: FormEvent| {
+                                update_style(|s, v| s.margin = v.parse().unwrap_or(u32::MAX), event.value())
+                            }),
+                        ),
+                    }
+                }
+                OptionField { label: "Dark color".to_string(), swatch: Some(style().dark),
+                    ToolInput {
+                        value: style().dark,
+                        placeholder: "#000000".to_string(),
+                        oninput: Some(
+                            EventHandler::new(move |event: FormEvent| {
+                                update_style(|s, v| s.dark = v, event.value())
+                            }),
+                        ),
+                    }
+                }
+                OptionField { label: "Light color".to_string(), swatch: Some(style().light),
+                    ToolInput {
+                        value: style().light,
+                        placeholder: "#ffffff".to_string(),
+                        oninput: Some(
+                            EventHandler::new(move |event: FormEvent| {
+                                update_style(|s, v| s.light = v, event.value())
+                            }),
+                        ),
+                    }
+                }
+            }
+
             // Main tool grid
-            ToolGrid { left_content, right_content, actions: Some(actions) }
+            ToolGrid {
+                left_content,
+                right_content,
+                actions: Some(actions),
+                error_message: Some(error()),
+            }
 
             // QR Code info
-            if !qr_url().is_empty() {
+            if let Some(image) = qr_image() {
                 div { class: "border-t border-ctp-surface1 pt-4",
-                    div { class: "grid grid-cols-3 gap-2 text-xs text-center",
+                    div { class: "grid grid-cols-4 gap-2 text-xs text-center",
                         div { class: "p-2 bg-ctp-surface0 rounded",
                             div { class: "font-medium text-ctp-text", "Length" }
                             div { class: "text-ctp-subtext1", "{input().len()}" }
                         }
                         div { class: "p-2 bg-ctp-surface0 rounded",
                             div { class: "font-medium text-ctp-text", "Type" }
-                            div { class: "text-ctp-subtext1",
-                                if input().starts_with("http") { "URL" } else { "Text" }
-                            }
+                            div { class: "text-ctp-subtext1", "{PayloadKind::detect(&input()).label()}" }
+                        }
+                        div { class: "p-2 bg-ctp-surface0 rounded",
+                            div { class: "font-medium text-ctp-text", "Version" }
+                            div { class: "text-ctp-subtext1", "{image.version} / ECC {image.ecc}" }
                         }
                         div { class: "p-2 bg-ctp-surface0 rounded",
                             div { class: "font-medium text-ctp-text", "Status" }
@@ -153,3 +343,244 @@ pub fn QRGenerator(input: Signal<String>, qr_url: Signal<String>) -> Element {
         }
     }
 }
+
+/// Form for a payload template's fields
+#[component]
+fn TemplateForm(
+    specs: Vec<FieldSpec>,
+    values: FieldValues,
+    on_change: EventHandler<(String, String)>,
+) -> Element {
+    rsx! {
+        div { class: "grid grid-cols-1 sm:grid-cols-2 gap-3",
+            for spec in specs {
+                {
+                    let key = spec.key.to_string();
+                    let value = values.get(spec.key).cloned().unwrap_or_default();
+                    let label = if spec.required { format!("{} *", spec.label) } else { spec.label.to_string() };
+                    rsx! {
+                        div {
+                            key: "{spec.key}",
+                            class: if spec.kind == FieldKind::Multiline { "space-y-2 sm:col-span-2" } else { "space-y-2" },
+                            label { class: "block text-sm font-medium text-ctp-text", "{label}" }
+                            match spec.kind {
+                                FieldKind::Choice(choices) => rsx! {
+                                    select {
+                                        class: "w-full px-4 py-3 bg-ctp-base border border-ctp-surface2 rounded-md text-ctp-text focus:outline-none focus:border-ctp-mauve",
+                                        onchange: move |event| on_change.call((key.clone(), event.value())),
+                                        for (choice , choice_label) in choices.iter() {
+                                            option {
+                                                value: "{choice}",
+                                                selected: value == *choice,
+                                                "{choice_label}"
+                                            }
+                                        }
+                                    }
+                                },
+                                FieldKind::Multiline => rsx! {
+                                    ToolTextarea {
+                                        value,
+                                        placeholder: spec.placeholder.to_string(),
+                                        rows: Some(3),
+                                        oninput: Some(EventHandler::new(move |event: FormEvent| on_change.call((key.clone(), event.value())))),
+                                    }
+                                },
+                                kind => rsx! {
+                                    ToolInput {
+                                        value,
+                                        placeholder: spec.placeholder.to_string(),
+                                        input_type: Some(kind.input_type().to_string()),
+                                        oninput: Some(EventHandler::new(move |event: FormEvent| on_change.call((key.clone(), event.value())))),
+                                    }
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Labelled control in the options panel, with an optional color swatch
+#[component]
+fn OptionField(label: String, swatch: Option<String>, children: Element) -> Element {
+    rsx! {
+        div { class: "space-y-2",
+            div { class: "flex items-center gap-2 text-sm font-medium text-ctp-text",
+                if let Some(color) = swatch.filter(|c| parse_hex_color(c).is_some()) {
+                    span {
+                        class: "inline-block w-4 h-4 rounded border border-ctp-surface2",
+                        style: "background-color: {color}",
+                    }
+                }
+                span { "{label}" }
+            }
+            {children}
+        }
+    }
+}
+
+/// Signals owned by the QR code tab
+#[derive(Clone, Copy, PartialEq)]
+pub struct QRGeneratorState {
+    input: Signal<String>,
+    qr_image: Signal<Option<QrImage>>,
+    ecc: Signal<EcLevel>,
+    style: Signal<QrStyle>,
+    template: Signal<Template>,
+    fields: Signal<FieldValues>,
+    /// "generate" or "decode"
+    mode: Signal<String>,
+    upload: Signal<Option<UploadedFile>>,
+    decoded: Signal<Vec<DecodedQr>>,
+    decode_error: Signal<String>,
+}
+
+/// Registry entry for [`QRGenerator`]
+pub struct QRGeneratorTool;
+
+impl Tool for QRGeneratorTool {
+    type State = QRGeneratorState;
+
+    fn id(&self) -> &'static str {
+        "qr"
+    }
+
+    fn icon(&self) -> &'static str {
+        "⚡"
+    }
+
+    fn title(&self) -> &'static str {
+        "QR Code"
+    }
+
+    fn subtitle(&self) -> &'static str {
+        "Generate & Decode"
+    }
+
+    fn create_state(&self) -> Self::State {
+        QRGeneratorState {
+            input: Signal::new(String::new()),
+            qr_image: Signal::new(None),
+            ecc: Signal::new(EcLevel::default()),
+            style: Signal::new(QrStyle::default()),
+            template: Signal::new(Template::default()),
+            fields: Signal::new(FieldValues::new()),
+            mode: Signal::new("generate".to_string()),
+            upload: Signal::new(None),
+            decoded: Signal::new(Vec::new()),
+            decode_error: Signal::new(String::new()),
+        }
+    }
+
+    fn render(&self, state: Self::State) -> Element {
+        let mut mode = state.mode;
+        let modes = vec![
+            ("generate".to_string(), "Generate".to_string()),
+            ("decode".to_string(), "Decode".to_string()),
+        ];
+
+        rsx! {
+            div { class: "space-y-4",
+                // Mode selection
+                ModeSelector {
+                    current_mode: mode(),
+                    modes,
+                    on_change: move |new_mode| mode.set(new_mode),
+                }
+
+                if mode() == "decode" {
+                    QRDecoder {
+                        upload: state.upload,
+                        decoded: state.decoded,
+                        error: state.decode_error,
+                    }
+                } else {
+                    QRGenerator {
+                        input: state.input,
+                        qr_image: state.qr_image,
+                        ecc: state.ecc,
+                        style: state.style,
+                        template: state.template,
+                        fields: state.fields,
+                    }
+                }
+            }
+        }
+    }
+
+    fn apply_query(&self, mut state: Self::State, query: &ToolQuery) {
+        if query.get("mode") == Some("decode") {
+            // Uploads can't travel in a URL; leave the generator untouched
+            state.mode.set("decode".to_string());
+            return;
+        }
+        state.mode.set("generate".to_string());
+
+        let input = query.get("input").unwrap_or_default().to_string();
+        let ecc = query
+            .get("ecc")
+            .and_then(|ecc| ecc.parse().ok())
+            .unwrap_or_default();
+        let defaults = QrStyle::default();
+        let style = QrStyle {
+            module_size: query
+                .get("size")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.module_size),
+            margin: query
+                .get("margin")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.margin),
+            dark: query
+                .get("dark")
+                .map(str::to_string)
+                .unwrap_or(defaults.dark),
+            light: query
+                .get("light")
+                .map(str::to_string)
+                .unwrap_or(defaults.light),
+        };
+        if input != *state.input.peek() || ecc != *state.ecc.peek() || style != *state.style.peek()
+        {
+            state.qr_image.set(None);
+            // Links carry the built payload, so show it as free text
+            state.template.set(Template::Text);
+            state.input.set(input);
+            state.ecc.set(ecc);
+            state.style.set(style);
+        }
+    }
+
+    fn to_query(&self, state: Self::State) -> ToolQuery {
+        let style = state.style.peek().clone();
+        let defaults = QrStyle::default();
+        let non_default = |value: String, default: String| {
+            if value == default {
+                String::new()
+            } else {
+                value
+            }
+        };
+        if *state.mode.peek() == "decode" {
+            return ToolQuery::default().with("mode", "decode");
+        }
+        ToolQuery::default()
+            .with("input", state.input.peek().clone())
+            .with("ecc", state.ecc.peek().to_string())
+            .with(
+                "size",
+                non_default(
+                    style.module_size.to_string(),
+                    defaults.module_size.to_string(),
+                ),
+            )
+            .with(
+                "margin",
+                non_default(style.margin.to_string(), defaults.margin.to_string()),
+            )
+            .with("dark", non_default(style.dark, defaults.dark))
+            .with("light", non_default(style.light, defaults.light))
+    }
+}
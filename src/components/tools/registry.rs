@@ -0,0 +1,123 @@
+use super::{
+    CsvConverterTool, EncodingWorkbenchTool, FormatConverterTool, HashGeneratorTool, HexDumpTool,
+    JsonDiffTool, JsonFormatterTool, JwtDecoderTool, QRGeneratorTool, TextUtilitiesTool,
+};
+use crate::types::ToolQuery;
+use dioxus::prelude::*;
+use std::any::Any;
+use std::rc::Rc;
+
+/// Type-erased tool state as stored by the tools page
+pub type ToolState = Rc<dyn Any>;
+
+/// A tool shown on the tools page.
+///
+/// Implementors describe their tab and own their state; the page only keeps
+/// one [`ToolState`] per registered tool so inputs survive tab switches.
+pub trait Tool: Sync + 'static {
+    /// Per-tool state, usually a `Copy` bundle of signals
+    type State: Clone + 'static;
+
+    /// Stable identifier used for tab selection
+    fn id(&self) -> &'static str;
+    /// Icon shown on the tab button
+    fn icon(&self) -> &'static str;
+    /// Tab title
+    fn title(&self) -> &'static str;
+    /// Short description under the title
+    fn subtitle(&self) -> &'static str;
+    /// Creates the tool's state. Called once, inside the page's scope.
+    fn create_state(&self) -> Self::State;
+    /// Renders the tool using its state
+    fn render(&self, state: Self::State) -> Element;
+
+    /// Restores state from a `/tools/:id?...` deep link. Runs inside the
+    /// page's route effect, so signals must be read with `peek`; a tracked
+    /// read would re-apply the link on every edit.
+    fn apply_query(&self, _state: Self::State, _query: &ToolQuery) {}
+
+    /// Captures the parts of the state worth putting in the URL. Reads with
+    /// `peek` too, since `apply_query` may compare against it.
+    fn to_query(&self, _state: Self::State) -> ToolQuery {
+        ToolQuery::default()
+    }
+}
+
+/// Object-safe view of a [`Tool`] so tools can live in one registry
+pub trait DynTool: Sync {
+    fn id(&self) -> &'static str;
+    fn icon(&self) -> &'static str;
+    fn title(&self) -> &'static str;
+    fn subtitle(&self) -> &'static str;
+    fn create_state(&self) -> ToolState;
+    fn render(&self, state: &ToolState) -> Element;
+    fn apply_query(&self, state: &ToolState, query: &ToolQuery);
+    fn to_query(&self, state: &ToolState) -> ToolQuery;
+}
+
+impl<T: Tool> DynTool for T {
+    fn id(&self) -> &'static str {
+        Tool::id(self)
+    }
+
+    fn icon(&self) -> &'static str {
+        Tool::icon(self)
+    }
+
+    fn title(&self) -> &'static str {
+        Tool::title(self)
+    }
+
+    fn subtitle(&self) -> &'static str {
+        Tool::subtitle(self)
+    }
+
+    fn create_state(&self) -> ToolState {
+        Rc::new(Tool::create_state(self))
+    }
+
+    fn render(&self, state: &ToolState) -> Element {
+        match state.downcast_ref::<T::State>() {
+            Some(state) => Tool::render(self, state.clone()),
+            None => rsx! {
+                div { class: "text-center text-ctp-red py-16", "Tool state mismatch" }
+            },
+        }
+    }
+
+    fn apply_query(&self, state: &ToolState, query: &ToolQuery) {
+        if let Some(state) = state.downcast_ref::<T::State>() {
+            Tool::apply_query(self, state.clone(), query);
+        }
+    }
+
+    fn to_query(&self, state: &ToolState) -> ToolQuery {
+        state
+            .downcast_ref::<T::State>()
+            .map(|state| Tool::to_query(self, state.clone()))
+            .unwrap_or_default()
+    }
+}
+
+/// All tools, in tab order. Register new tools here.
+pub static TOOLS: &[&dyn DynTool] = &[
+    &JsonFormatterTool,
+    &JsonDiffTool,
+    &FormatConverterTool,
+    &CsvConverterTool,
+    &TextUtilitiesTool,
+    &QRGeneratorTool,
+    &EncodingWorkbenchTool,
+    &HexDumpTool,
+    &JwtDecoderTool,
+    &HashGeneratorTool,
+];
+
+/// Looks up a registered tool and its index by id
+pub fn find_tool(id: &str) -> Option<(usize, &'static dyn DynTool)> {
+    TOOLS
+        .iter()
+        .copied()
+        .enumerate()
+        .find(|(_, tool)| tool.id() == id)
+}
@@ -1,4 +1,6 @@
+use dioxus::html::{FileEngine, HasFileData};
 use dioxus::prelude::*;
+use std::sync::Arc;
 
 /// Shared props for tool grid layout
 #[derive(Props, Clone, PartialEq)]
@@ -50,7 +52,7 @@ pub fn ToolGrid(props: ToolGridProps) -> Element {
                 // Input section
                 div { class: "space-y-4", {props.left_content} }
 
-                // Output section  
+                // Output section
                 div { class: "space-y-4", {props.right_content} }
             }
 
@@ -133,6 +135,7 @@ pub fn ToolTextarea(
     rows: Option<u32>,
     readonly: Option<bool>,
     oninput: Option<EventHandler<FormEvent>>,
+    id: Option<String>,
 ) -> Element {
     let rows = rows.unwrap_or(12);
     let readonly = readonly.unwrap_or(false);
@@ -146,6 +149,7 @@ pub fn ToolTextarea(
 
     rsx! {
         textarea {
+            id,
             class: "{base_classes}{readonly_classes}",
             rows: "{rows}",
             readonly,
@@ -185,15 +189,74 @@ pub fn ToolInput(
     }
 }
 
-/// Simple copy button
+/// Copies `text` to the clipboard, returning whether it succeeded.
+///
+/// Uses the async Clipboard API where available and falls back to
+/// `execCommand("copy")` on insecure (plain HTTP) origins.
+pub async fn copy_to_clipboard(text: &str) -> bool {
+    let eval = document::eval(
+        r#"
+        const text = await dioxus.recv();
+        if (navigator.clipboard && window.isSecureContext) {
+            try {
+                await navigator.clipboard.writeText(text);
+                return true;
+            } catch (_) {}
+        }
+        const area = document.createElement("textarea");
+        area.value = text;
+        area.setAttribute("readonly", "");
+        area.style.position = "fixed";
+        area.style.opacity = "0";
+        document.body.appendChild(area);
+        area.select();
+        let copied = false;
+        try {
+            copied = document.execCommand("copy");
+        } catch (_) {}
+        area.remove();
+        return copied;
+        "#,
+    );
+    if eval.send(text).is_err() {
+        return false;
+    }
+    eval.join::<bool>().await.unwrap_or(false)
+}
+
+/// Copy button that writes `text` to the clipboard and briefly shows the result
 #[component]
-pub fn CopyButton(text: String, onclick: EventHandler<MouseEvent>) -> Element {
+pub fn CopyButton(text: String, onclick: Option<EventHandler<MouseEvent>>) -> Element {
+    // None while idle, Some(copied) while showing feedback
+    let mut status = use_signal(|| None::<bool>);
+
+    let (icon, label, state_classes) = match status() {
+        Some(true) => ("✅", "Copied!", "border-ctp-green text-ctp-green"),
+        Some(false) => ("❌", "Copy failed", "border-ctp-red text-ctp-red"),
+        None => (
+            "📋",
+            "Copy",
+            "border-ctp-surface2 hover:border-ctp-mauve text-ctp-text",
+        ),
+    };
+
     rsx! {
         button {
-            class: "px-3 py-2 bg-ctp-surface1 hover:bg-ctp-surface2 text-ctp-text text-sm rounded-md transition-colors flex items-center space-x-2 border border-ctp-surface2 hover:border-ctp-mauve",
-            onclick: move |event| onclick.call(event),
-            span { "📋" }
-            span { "Copy" }
+            class: "px-3 py-2 bg-ctp-surface1 hover:bg-ctp-surface2 text-sm rounded-md transition-colors flex items-center space-x-2 border {state_classes}",
+            onclick: move |event| {
+                if let Some(handler) = onclick {
+                    handler.call(event);
+                }
+                let text = text.clone();
+                async move {
+                    let copied = copy_to_clipboard(&text).await;
+                    status.set(Some(copied));
+                    gloo_timers::future::TimeoutFuture::new(1500).await;
+                    status.set(None);
+                }
+            },
+            span { "{icon}" }
+            span { "{label}" }
         }
     }
 }
@@ -270,11 +333,14 @@ pub fn ModeSelector(
     current_mode: String,
     modes: Vec<(String, String)>, // (value, label) pairs
     on_change: EventHandler<String>,
+    label: Option<String>,
 ) -> Element {
+    let label = label.unwrap_or_else(|| "Operation Mode".to_string());
+
     rsx! {
         div { class: "space-y-3 text-center",
-            label { class: "block text-sm font-medium text-ctp-text", "Operation Mode" }
-            div { class: "inline-flex gap-1 p-1 bg-ctp-surface0 border border-ctp-surface1 rounded-md",
+            label { class: "block text-sm font-medium text-ctp-text", "{label}" }
+            div { class: "inline-flex flex-wrap justify-center gap-1 p-1 bg-ctp-surface0 border border-ctp-surface1 rounded-md",
                 for (mode_value , mode_label) in modes {
                     {
                         let mode_value_clone = mode_value.clone();
@@ -292,3 +358,105 @@ pub fn ModeSelector(
         }
     }
 }
+
+/// Labelled checkbox for boolean tool options
+#[component]
+pub fn ToolCheckbox(label: String, checked: bool, on_change: EventHandler<bool>) -> Element {
+    rsx! {
+        label { class: "inline-flex items-center gap-2 text-sm text-ctp-text cursor-pointer select-none",
+            input {
+                r#type: "checkbox",
+                class: "w-4 h-4 accent-ctp-mauve",
+                checked,
+                onchange: move |event| on_change.call(event.checked()),
+            }
+            "{label}"
+        }
+    }
+}
+
+/// A file picked or dropped by the user
+#[derive(Clone, PartialEq)]
+pub struct UploadedFile {
+    pub name: String,
+    pub bytes: Vec<u8>,
+}
+
+async fn read_first_file(files: Option<Arc<dyn FileEngine>>) -> Option<UploadedFile> {
+    let files = files?;
+    let name = files.files().into_iter().next()?;
+    let bytes = files.read_file(&name).await?;
+    Some(UploadedFile { name, bytes })
+}
+
+/// Drag-and-drop target with a file picker fallback
+#[component]
+pub fn FileDropZone(
+    label: String,
+    accept: Option<String>,
+    on_file: EventHandler<UploadedFile>,
+) -> Element {
+    let mut dragging = use_signal(|| false);
+    let accept = accept.unwrap_or_default();
+
+    let border_classes = if dragging() {
+        "border-ctp-mauve bg-ctp-surface1/50"
+    } else {
+        "border-ctp-surface2 bg-ctp-base hover:border-ctp-mauve"
+    };
+
+    rsx! {
+        label {
+            class: "{border_classes} flex flex-col items-center justify-center gap-2 w-full min-h-[140px] p-6 border-2 border-dashed rounded-md text-center cursor-pointer transition-colors",
+            ondragover: move |event| {
+                event.prevent_default();
+                dragging.set(true);
+            },
+            ondragleave: move |_| dragging.set(false),
+            ondrop: move |event: DragEvent| async move {
+                event.prevent_default();
+                dragging.set(false);
+                if let Some(file) = read_first_file(event.files()).await {
+                    on_file.call(file);
+                }
+            },
+            div { class: "text-3xl opacity-70", "📁" }
+            div { class: "text-sm text-ctp-text", "{label}" }
+            div { class: "text-xs text-ctp-subtext0", "Drop a file here or click to browse" }
+            input {
+                r#type: "file",
+                class: "hidden",
+                accept: "{accept}",
+                onchange: move |event: FormEvent| async move {
+                    if let Some(file) = read_first_file(event.files()).await {
+                        on_file.call(file);
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Saves `bytes` as a file through the browser's download prompt
+pub fn download_bytes(filename: &str, mime: &str, bytes: &[u8]) {
+    use base64::{engine::general_purpose, Engine as _};
+
+    let json = |value: &str| serde_json::to_string(value).unwrap_or_default();
+    let script = format!(
+        r#"
+        const bytes = Uint8Array.from(atob({data}), (c) => c.charCodeAt(0));
+        const url = URL.createObjectURL(new Blob([bytes], {{ type: {mime} }}));
+        const link = document.createElement("a");
+        link.href = url;
+        link.download = {filename};
+        document.body.appendChild(link);
+        link.click();
+        link.remove();
+        setTimeout(() => URL.revokeObjectURL(url), 1000);
+        "#,
+        data = json(&general_purpose::STANDARD.encode(bytes)),
+        mime = json(mime),
+        filename = json(filename),
+    );
+    document::eval(&script);
+}
@@ -1,4 +1,6 @@
+use crate::components::tools::registry::Tool;
 use crate::components::tools::shared::*;
+use crate::types::ToolQuery;
 use dioxus::prelude::*;
 
 /// Text Utilities component
@@ -19,13 +21,6 @@ pub fn TextUtilities(
         word_count.set(words as i32);
     };
 
-    let copy_text = move |_| {
-        if !input().is_empty() {
-            // TODO: Implement actual clipboard functionality
-            println!("📋 Copied text to clipboard");
-        }
-    };
-
     let clear_all = move |_| {
         input.set(String::new());
         update_counts();
@@ -56,7 +51,7 @@ pub fn TextUtilities(
             label: "Text Statistics".to_string(),
             helper_text: Some("Real-time analysis of your text".to_string()),
             copy_button: if !input().is_empty() { Some(rsx! {
-                CopyButton { text: input(), onclick: copy_text }
+                CopyButton { text: input() }
             }) } else { None },
             output: rsx! {
                 div { class: "h-full bg-ctp-base border border-ctp-surface2 rounded-md p-4 space-y-4",
@@ -180,3 +175,65 @@ pub fn TextUtilities(
         }
     }
 }
+
+/// Signals owned by the text utilities tab
+#[derive(Clone, Copy, PartialEq)]
+pub struct TextUtilitiesState {
+    input: Signal<String>,
+    word_count: Signal<i32>,
+    char_count: Signal<i32>,
+}
+
+/// Registry entry for [`TextUtilities`]
+pub struct TextUtilitiesTool;
+
+impl Tool for TextUtilitiesTool {
+    type State = TextUtilitiesState;
+
+    fn id(&self) -> &'static str {
+        "text"
+    }
+
+    fn icon(&self) -> &'static str {
+        "Aa"
+    }
+
+    fn title(&self) -> &'static str {
+        "Text"
+    }
+
+    fn subtitle(&self) -> &'static str {
+        "Transform & Count"
+    }
+
+    fn create_state(&self) -> Self::State {
+        TextUtilitiesState {
+            input: Signal::new(String::new()),
+            word_count: Signal::new(0),
+            char_count: Signal::new(0),
+        }
+    }
+
+    fn render(&self, state: Self::State) -> Element {
+        rsx! {
+            TextUtilities {
+                input: state.input,
+                word_count: state.word_count,
+                char_count: state.char_count,
+            }
+        }
+    }
+
+    fn apply_query(&self, mut state: Self::State, query: &ToolQuery) {
+        let input = query.get("input").unwrap_or_default().to_string();
+        state.char_count.set(input.len() as i32);
+        state
+            .word_count
+            .set(input.split_whitespace().count() as i32);
+        state.input.set(input);
+    }
+
+    fn to_query(&self, state: Self::State) -> ToolQuery {
+        ToolQuery::default().with("input", state.input.peek().clone())
+    }
+}
@@ -0,0 +1,616 @@
+//! CSV and TSV tables and their mapping to a JSON array of objects.
+//!
+//! Nested objects are flattened into dotted column names (`address.city`) when
+//! writing, and dotted columns are nested again when reading.
+
+use super::{child_pointer, Conversion, Diagnostic};
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+/// Delimiters offered and tried by [`detect`]: id, character and label
+pub const DELIMITERS: [(&str, char, &str); 4] = [
+    ("comma", ',', "Comma"),
+    ("tab", '\t', "Tab"),
+    ("semicolon", ';', "Semicolon"),
+    ("pipe", '|', "Pipe"),
+];
+
+/// Quote characters offered and tried by [`detect`]: id, character and label
+pub const QUOTES: [(&str, char, &str); 2] = [("double", '"', "Double"), ("single", '\'', "Single")];
+
+/// Records looked at when detecting the dialect
+const SAMPLE_RECORDS: usize = 20;
+
+/// How a CSV document is laid out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub quote: char,
+    /// Whether the first record names the columns
+    pub has_header: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            quote: '"',
+            has_header: true,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Short description such as "comma-delimited, double quotes, header row"
+    pub fn describe(&self) -> String {
+        let delimiter = DELIMITERS
+            .iter()
+            .find(|(_, c, _)| *c == self.delimiter)
+            .map_or("custom", |(id, _, _)| id);
+        let quote = QUOTES
+            .iter()
+            .find(|(_, c, _)| *c == self.quote)
+            .map_or("custom", |(id, _, _)| id);
+        format!(
+            "{}-delimited, {} quotes, {}",
+            delimiter,
+            quote,
+            if self.has_header {
+                "header row"
+            } else {
+                "no header row"
+            }
+        )
+    }
+}
+
+/// A parsed CSV document with uniquely named columns
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Table {
+    pub headers: Vec<String>,
+    /// Rows padded to the width of `headers`
+    pub rows: Vec<Vec<String>>,
+    /// Rows whose field count differed from the header's
+    pub ragged: usize,
+}
+
+/// Splits `input` into records, stopping after `limit` if given
+fn records(
+    input: &str,
+    delimiter: char,
+    quote: char,
+    limit: Option<usize>,
+) -> Result<Vec<Vec<String>>, String> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut chars = input.chars().peekable();
+    let mut at_field_start = true;
+
+    while let Some(c) = chars.next() {
+        if limit.is_some_and(|limit| records.len() >= limit) {
+            return Ok(records);
+        }
+        if c == quote && at_field_start {
+            let start_line = line;
+            loop {
+                match chars.next() {
+                    Some(c) if c == quote => {
+                        if chars.peek() == Some(&quote) {
+                            chars.next();
+                            field.push(quote);
+                        } else {
+                            break;
+                        }
+                    }
+                    Some(c) => {
+                        if c == '\n' {
+                            line += 1;
+                        }
+                        field.push(c);
+                    }
+                    None => {
+                        return Err(format!(
+                            "CSV Parse Error: quoted field starting at line {} is never closed",
+                            start_line
+                        ))
+                    }
+                }
+            }
+            at_field_start = false;
+        } else if c == delimiter {
+            record.push(std::mem::take(&mut field));
+            at_field_start = true;
+        } else if c == '\n' || c == '\r' {
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            line += 1;
+            record.push(std::mem::take(&mut field));
+            // Blank lines hold no record
+            if record.len() > 1 || !record[0].is_empty() {
+                records.push(std::mem::take(&mut record));
+            } else {
+                record.clear();
+            }
+            at_field_start = true;
+        } else {
+            field.push(c);
+            at_field_start = false;
+        }
+    }
+
+    if !record.is_empty() || !field.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    if let Some(limit) = limit {
+        records.truncate(limit);
+    }
+    Ok(records)
+}
+
+/// Whether a cell reads as a number or boolean
+fn is_typed(cell: &str) -> bool {
+    typed_value(cell).is_some_and(|value| !value.is_null())
+}
+
+/// Guesses the delimiter, quote character and whether there is a header row
+pub fn detect(input: &str) -> CsvOptions {
+    // Quotes only count where a field can start with one; ties go to the
+    // first entry of QUOTES
+    let field_starts = |quote: char| {
+        input
+            .lines()
+            .flat_map(|line| {
+                line.char_indices().filter(move |(i, c)| {
+                    *c == quote
+                        && (*i == 0 || DELIMITERS.iter().any(|(_, d, _)| line[..*i].ends_with(*d)))
+                })
+            })
+            .count()
+    };
+    let mut quote = QUOTES[0].1;
+    for (_, candidate, _) in QUOTES {
+        if field_starts(candidate) > field_starts(quote) {
+            quote = candidate;
+        }
+    }
+
+    // Prefer the delimiter that splits most records into the same number of
+    // fields, then the one giving more fields
+    let mut best = (0.0, 1, ',');
+    for (_, delimiter, _) in DELIMITERS {
+        let Ok(sample) = records(input, delimiter, quote, Some(SAMPLE_RECORDS)) else {
+            continue;
+        };
+        let mut counts: Vec<usize> = sample.iter().map(Vec::len).collect();
+        counts.sort_unstable();
+        let Some(width) = counts
+            .iter()
+            .copied()
+            .max_by_key(|width| (counts.iter().filter(|c| *c == width).count(), *width))
+        else {
+            continue;
+        };
+        let consistency =
+            counts.iter().filter(|c| **c == width).count() as f64 / counts.len() as f64;
+        if width > 1 && (consistency, width) > (best.0, best.1) {
+            best = (consistency, width, delimiter);
+        }
+    }
+    let delimiter = best.2;
+
+    // A header row names every column once and holds no numbers or booleans
+    let sample = records(input, delimiter, quote, Some(SAMPLE_RECORDS)).unwrap_or_default();
+    let has_header = sample.first().is_none_or(|first| {
+        let mut seen = HashSet::new();
+        first
+            .iter()
+            .all(|cell| !cell.trim().is_empty() && !is_typed(cell) && seen.insert(cell))
+    });
+
+    CsvOptions {
+        delimiter,
+        quote,
+        has_header,
+    }
+}
+
+/// Parses `input` into a table, naming columns `column1`, `column2`, ...
+/// where there is no header
+pub fn parse(input: &str, options: &CsvOptions) -> Result<Table, String> {
+    let mut records = records(input, options.delimiter, options.quote, None)?;
+    if records.is_empty() {
+        return Ok(Table::default());
+    }
+    let mut headers = if options.has_header {
+        records.remove(0)
+    } else {
+        Vec::new()
+    };
+    let width = records
+        .iter()
+        .map(Vec::len)
+        .chain(std::iter::once(headers.len()))
+        .max()
+        .unwrap_or(0);
+    let header_width = if options.has_header {
+        headers.len()
+    } else {
+        width
+    };
+
+    let mut ragged = 0;
+    for record in &mut records {
+        if record.len() != header_width {
+            ragged += 1;
+        }
+        record.resize(width, String::new());
+    }
+
+    while headers.len() < width {
+        headers.push(String::new());
+    }
+    let mut seen = HashSet::new();
+    for (i, header) in headers.iter_mut().enumerate() {
+        if header.trim().is_empty() {
+            *header = format!("column{}", i + 1);
+        }
+        let base = header.clone();
+        let mut suffix = 2;
+        while !seen.insert(header.clone()) {
+            *header = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+    }
+
+    Ok(Table {
+        headers,
+        rows: records,
+        ragged,
+    })
+}
+
+/// A cell as a number, boolean or null, if it reads as one
+fn typed_value(cell: &str) -> Option<Value> {
+    match cell {
+        "" => Some(Value::Null),
+        "true" => Some(Value::Bool(true)),
+        "false" => Some(Value::Bool(false)),
+        _ if cell.trim() != cell => None,
+        _ => match serde_json::from_str(cell) {
+            Ok(Value::Number(n)) => Some(Value::Number(n)),
+            _ => None,
+        },
+    }
+}
+
+/// Converts a table to an array of objects, nesting dotted column names.
+///
+/// With `infer_types`, numbers and booleans become JSON values and empty
+/// cells become `null`; otherwise every cell stays a string.
+pub fn to_json(table: &Table, infer_types: bool) -> Value {
+    // A dotted name is only nested when no other column claims a prefix of it
+    let paths: Vec<Vec<&str>> = table
+        .headers
+        .iter()
+        .map(|header| {
+            let segments: Vec<&str> = header.split('.').collect();
+            let clashes = segments.iter().any(|s| s.is_empty())
+                || (1..segments.len()).any(|n| {
+                    let prefix = segments[..n].join(".");
+                    table.headers.contains(&prefix)
+                });
+            if clashes {
+                vec![header.as_str()]
+            } else {
+                segments
+            }
+        })
+        .collect();
+
+    let rows = table
+        .rows
+        .iter()
+        .map(|row| {
+            let mut object = Map::new();
+            for (path, cell) in paths.iter().zip(row) {
+                let value = if infer_types {
+                    typed_value(cell).unwrap_or_else(|| Value::String(cell.clone()))
+                } else {
+                    Value::String(cell.clone())
+                };
+                insert_path(&mut object, path, value);
+            }
+            Value::Object(object)
+        })
+        .collect();
+    Value::Array(rows)
+}
+
+fn insert_path(object: &mut Map<String, Value>, path: &[&str], value: Value) {
+    let (last, parents) = path.split_last().expect("paths have a segment");
+    let mut object = object;
+    for segment in parents {
+        let entry = object
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        object = match entry {
+            Value::Object(map) => map,
+            _ => unreachable!("prefixes of nested columns are never columns themselves"),
+        };
+    }
+    object.insert(last.to_string(), value);
+}
+
+/// Writes an array of objects as CSV, flattening nested objects into dotted
+/// column names. Columns appear in the order they are first seen.
+pub fn from_json(value: &Value, options: &CsvOptions) -> Result<Conversion, String> {
+    let mut diagnostics = Vec::new();
+    let items = match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(_) => {
+            diagnostics.push(Diagnostic::new("", "Single object written as one row"));
+            vec![value]
+        }
+        _ => {
+            return Err("CSV needs an array of objects, or a single object".to_string());
+        }
+    };
+
+    let mut headers: Vec<String> = Vec::new();
+    let mut rows = Vec::new();
+    for (i, item) in items.into_iter().enumerate() {
+        let pointer = if value.is_array() {
+            format!("/{}", i)
+        } else {
+            String::new()
+        };
+        let mut cells = Vec::new();
+        match item {
+            Value::Object(map) => flatten(map, "", &pointer, &mut cells, &mut diagnostics),
+            other => {
+                diagnostics.push(Diagnostic::new(
+                    &pointer,
+                    "Element is not an object; written to a \"value\" column",
+                ));
+                cells.push(("value".to_string(), pointer.clone(), other.clone()));
+            }
+        }
+
+        let mut row = vec![String::new(); headers.len()];
+        for (column, cell_pointer, cell) in cells {
+            let index = match headers.iter().position(|h| *h == column) {
+                Some(index) => index,
+                None => {
+                    headers.push(column.clone());
+                    row.push(String::new());
+                    headers.len() - 1
+                }
+            };
+            row[index] = match cell {
+                Value::String(s) => s,
+                Value::Null => String::new(),
+                Value::Array(_) | Value::Object(_) => {
+                    diagnostics.push(Diagnostic::new(
+                        &cell_pointer,
+                        format!(
+                            "{} written as JSON text in column \"{}\"",
+                            if cell.is_array() {
+                                "Array"
+                            } else {
+                                "Empty object"
+                            },
+                            column
+                        ),
+                    ));
+                    cell.to_string()
+                }
+                scalar => scalar.to_string(),
+            };
+        }
+        rows.push(row);
+    }
+
+    for header in &headers {
+        let dotted = header
+            .split('.')
+            .scan(String::new(), |prefix, segment| {
+                if !prefix.is_empty() {
+                    prefix.push('.');
+                }
+                prefix.push_str(segment);
+                Some(prefix.clone())
+            })
+            .any(|prefix| prefix != *header && headers.contains(&prefix));
+        if dotted {
+            diagnostics.push(Diagnostic::new(
+                "",
+                format!(
+                    "Column \"{}\" clashes with a shorter column and stays flat when read back",
+                    header
+                ),
+            ));
+        }
+    }
+
+    // Per-row notes about the same key are only worth showing once
+    let mut messages = HashSet::new();
+    diagnostics.retain(|diagnostic| messages.insert(diagnostic.message.clone()));
+
+    let mut output = String::new();
+    for row in &mut rows {
+        row.resize(headers.len(), String::new());
+    }
+    for record in std::iter::once(&headers).chain(rows.iter()) {
+        let line: Vec<String> = record
+            .iter()
+            .map(|cell| quote_field(cell, options))
+            .collect();
+        output.push_str(&line.join(&options.delimiter.to_string()));
+        output.push('\n');
+    }
+    Ok(Conversion {
+        output,
+        diagnostics,
+    })
+}
+
+/// Collects `(column, pointer, value)` for every leaf of `map`
+fn flatten(
+    map: &Map<String, Value>,
+    prefix: &str,
+    pointer: &str,
+    cells: &mut Vec<(String, String, Value)>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for (key, value) in map {
+        let column = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        let pointer = child_pointer(pointer, key);
+        if key.contains('.') {
+            diagnostics.push(Diagnostic::new(
+                &pointer,
+                format!(
+                    "Key \"{}\" contains a dot, so it reads back as nested objects",
+                    key
+                ),
+            ));
+        }
+        match value {
+            Value::Object(inner) if !inner.is_empty() => {
+                flatten(inner, &column, &pointer, cells, diagnostics)
+            }
+            _ => cells.push((column, pointer, value.clone())),
+        }
+    }
+}
+
+fn quote_field(cell: &str, options: &CsvOptions) -> String {
+    let needs_quotes = cell.contains(options.delimiter)
+        || cell.contains(options.quote)
+        || cell.contains(['\n', '\r'])
+        || cell.trim() != cell;
+    if needs_quotes {
+        let quote = options.quote.to_string();
+        format!(
+            "{}{}{}",
+            quote,
+            cell.replace(&quote, &quote.repeat(2)),
+            quote
+        )
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Orders cells numerically when both are numbers, otherwise as text
+pub fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(x), Ok(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        _ => a
+            .to_lowercase()
+            .cmp(&b.to_lowercase())
+            .then_with(|| a.cmp(b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn table(input: &str) -> Table {
+        parse(input, &CsvOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn dotted_columns_nest() {
+        let value = to_json(
+            &table("id,address.city,address.geo.lat,address.geo.lng\n1,Oslo,59.9,10.7\n"),
+            true,
+        );
+        assert_eq!(
+            value,
+            json!([{"id": 1, "address": {"city": "Oslo", "geo": {"lat": 59.9, "lng": 10.7}}}])
+        );
+    }
+
+    #[test]
+    fn clashing_columns_stay_flat() {
+        let value = to_json(&table("a,a.b,c..d,.e\n1,2,3,4\n"), false);
+        assert_eq!(
+            value,
+            json!([{"a": "1", "a.b": "2", "c..d": "3", ".e": "4"}])
+        );
+    }
+
+    #[test]
+    fn inferred_types() {
+        let input = "n,f,b,s,empty\n42,-1.5,true,007x,\n";
+        assert_eq!(
+            to_json(&table(input), true),
+            json!([{"n": 42, "f": -1.5, "b": true, "s": "007x", "empty": null}])
+        );
+        assert_eq!(
+            to_json(&table(input), false),
+            json!([{"n": "42", "f": "-1.5", "b": "true", "s": "007x", "empty": ""}])
+        );
+    }
+
+    #[test]
+    fn flattening_round_trips() {
+        let value = json!([
+            {"id": 1, "user": {"name": "Ada", "langs": {"first": "en"}}},
+            {"id": 2, "user": {"name": "Grace, B."}, "extra": true},
+        ]);
+        let conversion = from_json(&value, &CsvOptions::default()).unwrap();
+        assert_eq!(
+            conversion.output,
+            "id,user.name,user.langs.first,extra\n1,Ada,en,\n2,\"Grace, B.\",,true\n"
+        );
+        assert!(conversion.diagnostics.is_empty());
+
+        let back = to_json(&table(&conversion.output), true);
+        assert_eq!(
+            back,
+            json!([
+                {"id": 1, "user": {"name": "Ada", "langs": {"first": "en"}}, "extra": null},
+                {"id": 2, "user": {"name": "Grace, B.", "langs": {"first": null}}, "extra": true},
+            ])
+        );
+    }
+
+    #[test]
+    fn flattening_diagnostics() {
+        let conversion = from_json(
+            &json!({"a": 1, "a.b": 2, "list": [1, 2]}),
+            &CsvOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(conversion.output, "a,a.b,list\n1,2,\"[1,2]\"\n");
+        let messages: Vec<String> = conversion
+            .diagnostics
+            .iter()
+            .map(Diagnostic::describe)
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "Single object written as one row",
+                "/a.b: Key \"a.b\" contains a dot, so it reads back as nested objects",
+                "/list: Array written as JSON text in column \"list\"",
+                "Column \"a.b\" clashes with a shorter column and stays flat when read back",
+            ]
+        );
+
+        assert!(from_json(&json!("text"), &CsvOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,301 @@
+//! Conversion between JSON, YAML, TOML and XML.
+//!
+//! Every format is read into a `serde_json::Value` and written back out from
+//! it, so key order survives wherever both formats keep it. Anything the
+//! target cannot represent is reported as a [`Diagnostic`] instead of being
+//! dropped silently.
+
+pub mod csv;
+mod toml;
+mod xml;
+mod yaml;
+
+use crate::json::diff::same;
+use crate::json::lenient::{self, Dialect};
+use serde_json::{Number, Value};
+
+/// A supported text format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Json,
+    Yaml,
+    Toml,
+    Xml,
+}
+
+impl Format {
+    pub const ALL: [Format; 4] = [Format::Json, Format::Yaml, Format::Toml, Format::Xml];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Yaml => "yaml",
+            Format::Toml => "toml",
+            Format::Xml => "xml",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Format::Json => "JSON",
+            Format::Yaml => "YAML",
+            Format::Toml => "TOML",
+            Format::Xml => "XML",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.id() == id)
+    }
+}
+
+/// Something that did not carry over exactly
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// JSON Pointer of the affected value; empty for the whole document
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(pointer: &str, message: impl Into<String>) -> Self {
+        Diagnostic {
+            pointer: pointer.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// The message, prefixed with where it applies
+    pub fn describe(&self) -> String {
+        if self.pointer.is_empty() {
+            self.message.clone()
+        } else {
+            format!("{}: {}", self.pointer, self.message)
+        }
+    }
+}
+
+/// Converted text with anything worth knowing about the conversion
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    pub output: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Converts `input` from one format to another
+pub fn convert(input: &str, from: Format, to: Format) -> Result<Conversion, String> {
+    let mut diagnostics = Vec::new();
+    let value = match from {
+        Format::Json => lenient::parse(input, Dialect::Strict)
+            .map(|(value, _)| value)
+            .map_err(|e| e.message)?,
+        Format::Yaml => yaml::read(input, &mut diagnostics)?,
+        Format::Toml => toml::read(input, &mut diagnostics)?,
+        Format::Xml => xml::read(input, &mut diagnostics)?,
+    };
+    let output = match to {
+        Format::Json => serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?,
+        Format::Yaml => yaml::write(&value, &mut diagnostics)?,
+        Format::Toml => toml::write(&value, &mut diagnostics)?,
+        Format::Xml => xml::write(&value, &mut diagnostics),
+    };
+    Ok(Conversion {
+        output,
+        diagnostics,
+    })
+}
+
+/// A JSON number as the integer or float types the other formats use
+enum NumberValue {
+    Signed(i64),
+    Unsigned(u64),
+    /// The nearest float, and whether it holds the number exactly. Numbers
+    /// beyond the range of `f64` become infinite with the same sign.
+    Float(f64, bool),
+}
+
+fn number_value(number: &Number) -> NumberValue {
+    if let Some(n) = number.as_i64() {
+        NumberValue::Signed(n)
+    } else if let Some(n) = number.as_u64() {
+        NumberValue::Unsigned(n)
+    } else {
+        // Parsing the text, unlike `as_f64`, turns overflow into ±inf
+        let float = number.to_string().parse().unwrap_or(f64::NAN);
+        let exact = Number::from_f64(float)
+            .is_some_and(|n| same(&Value::Number(n), &Value::Number(number.clone())));
+        NumberValue::Float(float, exact)
+    }
+}
+
+/// A float read from YAML or TOML; non-finite values have no JSON form
+fn float_value(float: f64, pointer: &str, diagnostics: &mut Vec<Diagnostic>) -> Value {
+    match Number::from_f64(float) {
+        Some(number) => Value::Number(number),
+        None => {
+            diagnostics.push(Diagnostic::new(
+                pointer,
+                format!("{} has no JSON equivalent; written as null", float),
+            ));
+            Value::Null
+        }
+    }
+}
+
+/// 1-based line and column of a byte offset, for parser errors
+fn line_column(input: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(input.len());
+    while !input.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &input[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+fn child_pointer(pointer: &str, key: &str) -> String {
+    format!("{}/{}", pointer, crate::json::tree::escape_pointer(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(conversion: &Conversion) -> Vec<String> {
+        conversion
+            .diagnostics
+            .iter()
+            .map(Diagnostic::describe)
+            .collect()
+    }
+
+    #[test]
+    fn round_trips() {
+        let json = r#"{"name":"ox","tags":["a","b"],"nested":{"n":1,"f":2.5,"ok":true}}"#;
+        let expected: Value = serde_json::from_str(json).unwrap();
+        for format in [Format::Yaml, Format::Toml] {
+            let there = convert(json, Format::Json, format).unwrap();
+            assert!(there.diagnostics.is_empty(), "{:?}", there.diagnostics);
+            let back = convert(&there.output, format, Format::Json).unwrap();
+            let value: Value = serde_json::from_str(&back.output).unwrap();
+            assert_eq!(value, expected, "through {}", format.label());
+        }
+    }
+
+    #[test]
+    fn out_of_range_floats() {
+        let input = r#"{"big": 1.5e400, "small": -1e400}"#;
+
+        let toml = convert(input, Format::Json, Format::Toml).unwrap();
+        assert_eq!(toml.output, "big = inf\nsmall = -inf\n");
+        assert_eq!(
+            messages(&toml),
+            [
+                "/big: 1.5e400 is out of range for a 64-bit float; written as inf",
+                "/small: -1e400 is out of range for a 64-bit float; written as -inf",
+            ]
+        );
+
+        let yaml = convert(input, Format::Json, Format::Yaml).unwrap();
+        assert_eq!(yaml.output, "big: .inf\nsmall: -.inf\n");
+        assert_eq!(yaml.diagnostics.len(), 2);
+
+        // And back again, where JSON has no infinity
+        let json = convert(&yaml.output, Format::Yaml, Format::Json).unwrap();
+        assert_eq!(json.output, "{\n  \"big\": null,\n  \"small\": null\n}");
+        assert_eq!(
+            messages(&json),
+            [
+                "/big: inf has no JSON equivalent; written as null",
+                "/small: -inf has no JSON equivalent; written as null",
+            ]
+        );
+    }
+
+    #[test]
+    fn rounded_floats() {
+        let toml = convert(
+            r#"{"x": 0.10000000000000000001}"#,
+            Format::Json,
+            Format::Toml,
+        )
+        .unwrap();
+        assert_eq!(toml.output, "x = 0.1\n");
+        assert_eq!(
+            messages(&toml),
+            ["/x: 0.10000000000000000001 rounded to 0.1; TOML floats are 64-bit"]
+        );
+
+        let exact = convert(r#"{"x": 0.1, "y": 1e3}"#, Format::Json, Format::Yaml).unwrap();
+        assert!(exact.diagnostics.is_empty(), "{:?}", exact.diagnostics);
+    }
+
+    #[test]
+    fn toml_limits() {
+        let toml = convert(
+            r#"{"a": null, "b": [1, null, 2], "c": 18446744073709551615}"#,
+            Format::Json,
+            Format::Toml,
+        )
+        .unwrap();
+        assert_eq!(
+            toml.output,
+            "b = [\n    1,\n    2,\n]\nc = \"18446744073709551615\"\n"
+        );
+        assert_eq!(
+            messages(&toml),
+            [
+                "/a: null value dropped; TOML has no null",
+                "/b/1: null array element dropped; TOML has no null, so later indices shift",
+                "/c: 18446744073709551615 is too large for a TOML integer; written as a string",
+            ]
+        );
+
+        assert_eq!(
+            convert("[1]", Format::Json, Format::Toml),
+            Err(
+                "TOML documents must be a table at the top level; wrap the value in an object"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn toml_datetimes() {
+        let json = convert("when = 1979-05-27T07:32:00Z", Format::Toml, Format::Json).unwrap();
+        assert_eq!(json.output, "{\n  \"when\": \"1979-05-27T07:32:00Z\"\n}");
+        assert_eq!(
+            messages(&json),
+            ["/when: Datetime written as a string; JSON has no date type"]
+        );
+    }
+
+    #[test]
+    fn yaml_documents() {
+        let json = convert("a: 1\n---\n2: b\n", Format::Yaml, Format::Json).unwrap();
+        let value: Value = serde_json::from_str(&json.output).unwrap();
+        assert_eq!(value, serde_json::json!([{"a": 1}, {"2": "b"}]));
+        assert_eq!(
+            messages(&json),
+            [
+                "2 YAML documents combined into one array",
+                "/1: Non-string key 2 converted to the string \"2\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            convert("a = 1\nb = [1,", Format::Toml, Format::Json).unwrap_err(),
+            "TOML Parse Error: invalid array\nexpected `]` at line 2 column 8"
+        );
+        assert!(convert("{", Format::Json, Format::Yaml)
+            .unwrap_err()
+            .starts_with("JSON Parse Error: "));
+    }
+}
@@ -0,0 +1,130 @@
+//! TOML via the `toml` crate. TOML has no null and needs a table at the top,
+//! and its datetimes have no JSON type.
+
+use super::{child_pointer, float_value, line_column, number_value, Diagnostic, NumberValue};
+use ::toml::{Table, Value as Toml};
+use serde_json::{Map, Value};
+
+pub fn read(input: &str, diagnostics: &mut Vec<Diagnostic>) -> Result<Value, String> {
+    let table = input.parse::<Table>().map_err(|e| {
+        let message = e.message().trim_end().to_string();
+        match e.span() {
+            Some(span) => {
+                let (line, column) = line_column(input, span.start);
+                format!(
+                    "TOML Parse Error: {} at line {} column {}",
+                    message, line, column
+                )
+            }
+            None => format!("TOML Parse Error: {}", message),
+        }
+    })?;
+    Ok(to_json(Toml::Table(table), "", diagnostics))
+}
+
+fn to_json(toml: Toml, pointer: &str, diagnostics: &mut Vec<Diagnostic>) -> Value {
+    match toml {
+        Toml::String(s) => Value::String(s),
+        Toml::Integer(i) => Value::from(i),
+        Toml::Float(f) => float_value(f, pointer, diagnostics),
+        Toml::Boolean(b) => Value::Bool(b),
+        Toml::Datetime(datetime) => {
+            diagnostics.push(Diagnostic::new(
+                pointer,
+                "Datetime written as a string; JSON has no date type",
+            ));
+            Value::String(datetime.to_string())
+        }
+        Toml::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| to_json(item, &format!("{}/{}", pointer, i), diagnostics))
+                .collect(),
+        ),
+        Toml::Table(table) => {
+            let mut map = Map::new();
+            for (key, value) in table {
+                let value = to_json(value, &child_pointer(pointer, &key), diagnostics);
+                map.insert(key, value);
+            }
+            Value::Object(map)
+        }
+    }
+}
+
+pub fn write(value: &Value, diagnostics: &mut Vec<Diagnostic>) -> Result<String, String> {
+    let Value::Object(_) = value else {
+        return Err(
+            "TOML documents must be a table at the top level; wrap the value in an object"
+                .to_string(),
+        );
+    };
+    match from_json(value, "", diagnostics) {
+        Some(Toml::Table(table)) => ::toml::to_string_pretty(&table).map_err(|e| e.to_string()),
+        _ => Ok(String::new()),
+    }
+}
+
+/// `None` for nulls, which TOML cannot hold
+fn from_json(value: &Value, pointer: &str, diagnostics: &mut Vec<Diagnostic>) -> Option<Toml> {
+    Some(match value {
+        Value::Null => return None,
+        Value::Bool(b) => Toml::Boolean(*b),
+        Value::Number(n) => match number_value(n) {
+            NumberValue::Signed(i) => Toml::Integer(i),
+            NumberValue::Unsigned(_) => {
+                diagnostics.push(Diagnostic::new(
+                    pointer,
+                    format!("{} is too large for a TOML integer; written as a string", n),
+                ));
+                Toml::String(n.to_string())
+            }
+            NumberValue::Float(f, exact) => {
+                if f.is_infinite() {
+                    diagnostics.push(Diagnostic::new(
+                        pointer,
+                        format!("{} is out of range for a 64-bit float; written as {}", n, f),
+                    ));
+                } else if !exact {
+                    diagnostics.push(Diagnostic::new(
+                        pointer,
+                        format!("{} rounded to {}; TOML floats are 64-bit", n, f),
+                    ));
+                }
+                Toml::Float(f)
+            }
+        },
+        Value::String(s) => Toml::String(s.clone()),
+        Value::Array(items) => {
+            let mut converted = Vec::new();
+            for (i, item) in items.iter().enumerate() {
+                let item_pointer = format!("{}/{}", pointer, i);
+                match from_json(item, &item_pointer, diagnostics) {
+                    Some(item) => converted.push(item),
+                    None => diagnostics.push(Diagnostic::new(
+                        &item_pointer,
+                        "null array element dropped; TOML has no null, so later indices shift",
+                    )),
+                }
+            }
+            Toml::Array(converted)
+        }
+        Value::Object(map) => {
+            let mut table = Table::new();
+            for (key, value) in map {
+                let key_pointer = child_pointer(pointer, key);
+                match from_json(value, &key_pointer, diagnostics) {
+                    Some(value) => {
+                        table.insert(key.clone(), value);
+                    }
+                    None => diagnostics.push(Diagnostic::new(
+                        &key_pointer,
+                        "null value dropped; TOML has no null",
+                    )),
+                }
+            }
+            Toml::Table(table)
+        }
+    })
+}
@@ -0,0 +1,388 @@
+//! XML using a common JSON mapping: elements become keys, repeated elements
+//! become arrays, attributes are keys starting with `@` and text beside
+//! child elements is kept under `#text`.
+
+use super::{child_pointer, line_column, Diagnostic};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde_json::{Map, Value};
+
+/// Prefix marking attributes among an element's keys
+const ATTRIBUTE: &str = "@";
+/// Key holding an element's text when it also has attributes or children
+const TEXT: &str = "#text";
+
+/// An element still being read
+struct Frame {
+    name: String,
+    fields: Map<String, Value>,
+    has_children: bool,
+    text: String,
+}
+
+impl Frame {
+    fn open(start: &BytesStart, input: &str, position: usize) -> Result<Frame, String> {
+        let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+        let mut fields = Map::new();
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(|e| parse_error(input, position, &e.to_string()))?;
+            let key = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+            let value = attribute
+                .unescape_value()
+                .map_err(|e| parse_error(input, position, &e.to_string()))?;
+            fields.insert(
+                format!("{}{}", ATTRIBUTE, key),
+                Value::String(value.into_owned()),
+            );
+        }
+        Ok(Frame {
+            name,
+            fields,
+            has_children: false,
+            text: String::new(),
+        })
+    }
+
+    fn close(self) -> Value {
+        if self.fields.is_empty() {
+            return Value::String(self.text);
+        }
+        let mut fields = self.fields;
+        if !self.text.is_empty() {
+            fields.insert(TEXT.to_string(), Value::String(self.text));
+        }
+        Value::Object(fields)
+    }
+
+    /// Adds a child element, turning repeats into an array
+    fn add(&mut self, name: String, value: Value) {
+        self.has_children = true;
+        match self.fields.get_mut(&name) {
+            Some(Value::Array(items)) => items.push(value),
+            Some(existing) => {
+                let first = existing.take();
+                *existing = Value::Array(vec![first, value]);
+            }
+            None => {
+                self.fields.insert(name, value);
+            }
+        }
+    }
+}
+
+fn parse_error(input: &str, position: usize, message: &str) -> String {
+    let (line, column) = line_column(input, position);
+    format!(
+        "XML Parse Error: {} at line {} column {}",
+        message, line, column
+    )
+}
+
+pub fn read(input: &str, diagnostics: &mut Vec<Diagnostic>) -> Result<Value, String> {
+    let mut reader = Reader::from_str(input);
+    reader.config_mut().trim_text(true);
+
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut root: Option<(String, Value)> = None;
+    let mut dropped = 0;
+    let mut typed_text = false;
+    let mut attributes = false;
+    let mut mixed = false;
+
+    loop {
+        let position = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| parse_error(input, reader.error_position() as usize, &e.to_string()))?;
+        let closed = match event {
+            Event::Start(start) => {
+                let frame = Frame::open(&start, input, position)?;
+                attributes |= !frame.fields.is_empty();
+                stack.push(frame);
+                None
+            }
+            Event::Empty(start) => {
+                let frame = Frame::open(&start, input, position)?;
+                attributes |= !frame.fields.is_empty();
+                Some(frame)
+            }
+            Event::End(_) => stack.pop(),
+            Event::Text(text) => {
+                let text = text
+                    .unescape()
+                    .map_err(|e| parse_error(input, position, &e.to_string()))?;
+                if let Some(frame) = stack.last_mut() {
+                    frame.text.push_str(&text);
+                }
+                None
+            }
+            Event::CData(data) => {
+                if let Some(frame) = stack.last_mut() {
+                    frame.text.push_str(&String::from_utf8_lossy(&data));
+                }
+                None
+            }
+            Event::Comment(_) | Event::PI(_) | Event::DocType(_) => {
+                dropped += 1;
+                None
+            }
+            Event::Decl(_) => None,
+            Event::Eof => break,
+        };
+
+        let Some(frame) = closed else {
+            continue;
+        };
+        if !frame.has_children && frame.fields.is_empty() {
+            let text = frame.text.trim();
+            typed_text |= text.parse::<f64>().is_ok() || text == "true" || text == "false";
+        }
+        mixed |= frame.has_children && !frame.text.is_empty();
+        let name = frame.name.clone();
+        let value = frame.close();
+        match stack.last_mut() {
+            Some(parent) => parent.add(name, value),
+            None if root.is_some() => {
+                return Err(parse_error(input, position, "more than one root element"))
+            }
+            None => root = Some((name, value)),
+        }
+    }
+
+    if let Some(open) = stack.last() {
+        return Err(parse_error(
+            input,
+            input.len(),
+            &format!("<{}> is never closed", open.name),
+        ));
+    }
+    let Some((name, value)) = root else {
+        return Err("XML Parse Error: no root element".to_string());
+    };
+
+    if dropped > 0 {
+        diagnostics.push(Diagnostic::new(
+            "",
+            format!(
+                "{} comment{}, processing instruction{} or doctype dropped",
+                dropped,
+                if dropped == 1 { "" } else { "s" },
+                if dropped == 1 { "" } else { "s" },
+            ),
+        ));
+    }
+    if attributes {
+        diagnostics.push(Diagnostic::new(
+            "",
+            format!("Attributes written as keys starting with \"{}\"", ATTRIBUTE),
+        ));
+    }
+    if mixed {
+        diagnostics.push(Diagnostic::new(
+            "",
+            format!(
+                "Text mixed with child elements is kept under \"{}\"; its position among the children is lost",
+                TEXT
+            ),
+        ));
+    }
+    if typed_text {
+        diagnostics.push(Diagnostic::new(
+            "",
+            "XML has no types, so numbers and booleans are read as strings",
+        ));
+    }
+    let mut document = Map::new();
+    document.insert(name, value);
+    Ok(Value::Object(document))
+}
+
+pub fn write(value: &Value, diagnostics: &mut Vec<Diagnostic>) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    match value {
+        Value::Object(map)
+            if map.len() == 1
+                && map.iter().all(|(key, value)| {
+                    !key.starts_with(ATTRIBUTE) && key != TEXT && !value.is_array()
+                }) =>
+        {
+            let (key, value) = map.iter().next().expect("one entry");
+            element(
+                key,
+                value,
+                0,
+                &child_pointer("", key),
+                &mut out,
+                diagnostics,
+            );
+        }
+        _ => {
+            diagnostics.push(Diagnostic::new(
+                "",
+                "Wrapped in <root>; XML needs exactly one root element",
+            ));
+            element("root", value, 0, "", &mut out, diagnostics);
+        }
+    }
+    out
+}
+
+fn element(
+    key: &str,
+    value: &Value,
+    depth: usize,
+    pointer: &str,
+    out: &mut String,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let indent = "  ".repeat(depth);
+    let name = xml_name(key);
+    if name != key {
+        diagnostics.push(Diagnostic::new(
+            pointer,
+            format!("\"{}\" is not a valid XML name; written as <{}>", key, name),
+        ));
+    }
+
+    match value {
+        Value::Array(items) => {
+            if items.is_empty() {
+                diagnostics.push(Diagnostic::new(
+                    pointer,
+                    "Empty array omitted; XML has no empty lists",
+                ));
+            }
+            for (i, item) in items.iter().enumerate() {
+                let item_pointer = format!("{}/{}", pointer, i);
+                if item.is_array() {
+                    diagnostics.push(Diagnostic::new(
+                        &item_pointer,
+                        "Nested array flattened into repeated elements",
+                    ));
+                }
+                element(key, item, depth, &item_pointer, out, diagnostics);
+            }
+        }
+        Value::Null => {
+            diagnostics.push(Diagnostic::new(
+                pointer,
+                "null written as an empty element, which reads back as \"\"",
+            ));
+            out.push_str(&format!("{}<{}/>\n", indent, name));
+        }
+        Value::Object(map) => {
+            let mut attributes = String::new();
+            let mut text = None;
+            let mut children = Vec::new();
+            for (child_key, child) in map {
+                let child_pointer = child_pointer(pointer, child_key);
+                if let Some(attribute) = child_key.strip_prefix(ATTRIBUTE) {
+                    match scalar_text(child) {
+                        Some(value) => attributes.push_str(&format!(
+                            " {}=\"{}\"",
+                            xml_name(attribute),
+                            escape(&value, true)
+                        )),
+                        None => {
+                            diagnostics.push(Diagnostic::new(
+                                &child_pointer,
+                                "Attributes hold text only; written as a child element instead",
+                            ));
+                            children.push((attribute, child, child_pointer));
+                        }
+                    }
+                } else if child_key == TEXT {
+                    text = scalar_text(child);
+                } else {
+                    children.push((child_key.as_str(), child, child_pointer));
+                }
+            }
+
+            if children.is_empty() {
+                match text {
+                    Some(text) => out.push_str(&format!(
+                        "{}<{}{}>{}</{}>\n",
+                        indent,
+                        name,
+                        attributes,
+                        escape(&text, false),
+                        name
+                    )),
+                    None => out.push_str(&format!("{}<{}{}/>\n", indent, name, attributes)),
+                }
+                return;
+            }
+            out.push_str(&format!("{}<{}{}>\n", indent, name, attributes));
+            if let Some(text) = text {
+                out.push_str(&format!("{}  {}\n", indent, escape(&text, false)));
+            }
+            for (child_key, child, child_pointer) in children {
+                element(
+                    child_key,
+                    child,
+                    depth + 1,
+                    &child_pointer,
+                    out,
+                    diagnostics,
+                );
+            }
+            out.push_str(&format!("{}</{}>\n", indent, name));
+        }
+        scalar => {
+            let text = scalar_text(scalar).unwrap_or_default();
+            out.push_str(&format!(
+                "{}<{}>{}</{}>\n",
+                indent,
+                name,
+                escape(&text, false),
+                name
+            ));
+        }
+    }
+}
+
+/// Text of a string, number or boolean
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// `key` made into a valid element or attribute name
+fn xml_name(key: &str) -> String {
+    let mut name: String = key
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let valid_start = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == ':');
+    if !valid_start {
+        name.insert(0, '_');
+    }
+    name
+}
+
+fn escape(text: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
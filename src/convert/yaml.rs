@@ -0,0 +1,147 @@
+//! YAML via `serde_yaml`, with merge keys applied and tags dropped.
+
+use super::{child_pointer, float_value, number_value, Diagnostic, NumberValue};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use serde_yaml::{Mapping, Value as Yaml};
+
+pub fn read(input: &str, diagnostics: &mut Vec<Diagnostic>) -> Result<Value, String> {
+    let mut documents = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(input) {
+        let mut yaml =
+            Yaml::deserialize(document).map_err(|e| format!("YAML Parse Error: {}", e))?;
+        yaml.apply_merge()
+            .map_err(|e| format!("YAML Parse Error: {}", e))?;
+        documents.push(yaml);
+    }
+
+    match documents.len() {
+        0 => Ok(Value::Null),
+        1 => Ok(to_json(documents.remove(0), "", diagnostics)),
+        count => {
+            diagnostics.push(Diagnostic::new(
+                "",
+                format!("{} YAML documents combined into one array", count),
+            ));
+            Ok(Value::Array(
+                documents
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, document)| to_json(document, &format!("/{}", i), diagnostics))
+                    .collect(),
+            ))
+        }
+    }
+}
+
+fn to_json(yaml: Yaml, pointer: &str, diagnostics: &mut Vec<Diagnostic>) -> Value {
+    match yaml {
+        Yaml::Null => Value::Null,
+        Yaml::Bool(b) => Value::Bool(b),
+        Yaml::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::from(u)
+            } else {
+                float_value(n.as_f64().unwrap_or(f64::NAN), pointer, diagnostics)
+            }
+        }
+        Yaml::String(s) => Value::String(s),
+        Yaml::Sequence(items) => Value::Array(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| to_json(item, &format!("{}/{}", pointer, i), diagnostics))
+                .collect(),
+        ),
+        Yaml::Mapping(mapping) => {
+            let mut map = Map::new();
+            for (key, value) in mapping {
+                let key = match key {
+                    Yaml::String(key) => key,
+                    other => {
+                        let text = scalar_key(&other);
+                        diagnostics.push(Diagnostic::new(
+                            pointer,
+                            format!(
+                                "Non-string key {} converted to the string \"{}\"",
+                                text, text
+                            ),
+                        ));
+                        text
+                    }
+                };
+                let value = to_json(value, &child_pointer(pointer, &key), diagnostics);
+                map.insert(key, value);
+            }
+            Value::Object(map)
+        }
+        Yaml::Tagged(tagged) => {
+            diagnostics.push(Diagnostic::new(
+                pointer,
+                format!("Tag {} dropped; JSON has no tags", tagged.tag),
+            ));
+            to_json(tagged.value, pointer, diagnostics)
+        }
+    }
+}
+
+/// Text of a non-string mapping key
+fn scalar_key(key: &Yaml) -> String {
+    match key {
+        Yaml::Null => "null".to_string(),
+        Yaml::Bool(b) => b.to_string(),
+        Yaml::Number(n) => n.to_string(),
+        Yaml::String(s) => s.clone(),
+        other => serde_yaml::to_string(other)
+            .unwrap_or_default()
+            .trim()
+            .replace('\n', " "),
+    }
+}
+
+pub fn write(value: &Value, diagnostics: &mut Vec<Diagnostic>) -> Result<String, String> {
+    serde_yaml::to_string(&from_json(value, "", diagnostics)).map_err(|e| e.to_string())
+}
+
+fn from_json(value: &Value, pointer: &str, diagnostics: &mut Vec<Diagnostic>) -> Yaml {
+    match value {
+        Value::Null => Yaml::Null,
+        Value::Bool(b) => Yaml::Bool(*b),
+        Value::Number(n) => match number_value(n) {
+            NumberValue::Signed(i) => Yaml::from(i),
+            NumberValue::Unsigned(u) => Yaml::from(u),
+            NumberValue::Float(f, exact) => {
+                if f.is_infinite() {
+                    diagnostics.push(Diagnostic::new(
+                        pointer,
+                        format!("{} is out of range for a 64-bit float; written as {}", n, f),
+                    ));
+                } else if !exact {
+                    diagnostics.push(Diagnostic::new(
+                        pointer,
+                        format!("{} rounded to {}; YAML readers use 64-bit floats", n, f),
+                    ));
+                }
+                Yaml::from(f)
+            }
+        },
+        Value::String(s) => Yaml::String(s.clone()),
+        Value::Array(items) => Yaml::Sequence(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| from_json(item, &format!("{}/{}", pointer, i), diagnostics))
+                .collect(),
+        ),
+        Value::Object(map) => {
+            let mut mapping = Mapping::new();
+            for (key, value) in map {
+                let value = from_json(value, &child_pointer(pointer, key), diagnostics);
+                mapping.insert(Yaml::String(key.clone()), value);
+            }
+            Yaml::Mapping(mapping)
+        }
+    }
+}
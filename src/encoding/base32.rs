@@ -0,0 +1,100 @@
+//! Base32 in the RFC 4648 and Crockford alphabets.
+
+const RFC4648: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+pub fn encode(bytes: &[u8], crockford: bool, padding: bool) -> String {
+    let alphabet = if crockford { CROCKFORD } else { RFC4648 };
+    let mut out = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer: u16 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u16;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(alphabet[(buffer >> bits) as usize & 31] as char);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        out.push(alphabet[(buffer << (5 - bits)) as usize & 31] as char);
+    }
+    // Crockford's encoding has no padding
+    if padding && !crockford {
+        while !out.len().is_multiple_of(8) {
+            out.push('=');
+        }
+    }
+    out
+}
+
+/// Value of an RFC 4648 character, ignoring case
+fn rfc4648_value(c: char) -> Option<u8> {
+    match c.to_ascii_uppercase() {
+        c @ 'A'..='Z' => Some(c as u8 - b'A'),
+        c @ '2'..='7' => Some(c as u8 - b'2' + 26),
+        _ => None,
+    }
+}
+
+/// Value of a Crockford character, ignoring case and reading the easily
+/// confused I, L and O as 1, 1 and 0
+fn crockford_value(c: char) -> Option<u8> {
+    let c = match c.to_ascii_uppercase() {
+        'I' | 'L' => '1',
+        'O' => '0',
+        c => c,
+    };
+    CROCKFORD
+        .iter()
+        .position(|&a| a as char == c)
+        .map(|i| i as u8)
+}
+
+/// Decodes ignoring whitespace, case and padding; Crockford input may also
+/// contain hyphens
+pub fn decode(text: &str, crockford: bool) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(text.len() * 5 / 8);
+    let mut buffer: u16 = 0;
+    let mut bits = 0;
+    let mut count = 0;
+    let mut padding = false;
+    for (i, c) in text.chars().enumerate() {
+        if c.is_whitespace() || (crockford && c == '-') {
+            continue;
+        }
+        if c == '=' && !crockford {
+            padding = true;
+            continue;
+        }
+        let value = if crockford {
+            crockford_value(c)
+        } else {
+            rfc4648_value(c)
+        };
+        let Some(value) = value.filter(|_| !padding) else {
+            return Err(format!(
+                "'{}' at position {} is not a Base32 character",
+                c.escape_default(),
+                i + 1
+            ));
+        };
+        buffer = (buffer << 5) | value as u16;
+        bits += 5;
+        count += 1;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    // 8 characters carry 5 bytes; these remainders cannot end a byte
+    if matches!(count % 8, 1 | 3 | 6) {
+        return Err(format!(
+            "{} characters cannot be Base32; one is missing or extra",
+            count
+        ));
+    }
+    Ok(out)
+}
@@ -0,0 +1,74 @@
+//! Base58 with the Bitcoin alphabet. Leading zero bytes become leading `1`s.
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Base58 conversion is quadratic, so inputs are capped
+pub const MAX_BYTES: usize = 4096;
+
+pub fn encode(bytes: &[u8]) -> Result<String, String> {
+    if bytes.len() > MAX_BYTES {
+        return Err(format!(
+            "Base58 is limited to {} bytes; the conversion slows down quadratically",
+            MAX_BYTES
+        ));
+    }
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    // Little-endian base 58 digits
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for &byte in &bytes[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    Ok(std::iter::repeat_n('1', zeros)
+        .chain(digits.iter().rev().map(|&d| ALPHABET[d as usize] as char))
+        .collect())
+}
+
+/// Decodes ignoring surrounding whitespace
+pub fn decode(text: &str) -> Result<Vec<u8>, String> {
+    let text = text.trim();
+    if text.len() > MAX_BYTES * 2 {
+        return Err(format!(
+            "Base58 is limited to {} characters; the conversion slows down quadratically",
+            MAX_BYTES * 2
+        ));
+    }
+    let zeros = text.chars().take_while(|&c| c == '1').count();
+    // Little-endian bytes
+    let mut bytes: Vec<u8> = Vec::with_capacity(text.len() * 733 / 1000 + 1);
+    for (i, c) in text.chars().enumerate().skip(zeros) {
+        let Some(value) = ALPHABET.iter().position(|&a| a as char == c) else {
+            return Err(format!(
+                "'{}' at position {} is not a Base58 character{}",
+                c.escape_default(),
+                i + 1,
+                if matches!(c, '0' | 'O' | 'I' | 'l') {
+                    "; 0, O, I and l are left out of the alphabet"
+                } else {
+                    ""
+                }
+            ));
+        };
+        let mut carry = value as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    Ok(std::iter::repeat_n(0, zeros)
+        .chain(bytes.into_iter().rev())
+        .collect())
+}
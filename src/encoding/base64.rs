@@ -0,0 +1,100 @@
+//! Base64 in the standard and URL-safe alphabets (RFC 4648), with optional
+//! padding and MIME line wrapping.
+
+use base64::alphabet::{self, Alphabet};
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::{DecodeError, Engine as _};
+
+/// Line length for MIME output (RFC 2045)
+const MIME_LINE: usize = 76;
+
+/// An engine for `alphabet` that accepts input with or without padding
+fn engine(alphabet: &Alphabet, padding: bool) -> GeneralPurpose {
+    GeneralPurpose::new(
+        alphabet,
+        GeneralPurposeConfig::new()
+            .with_encode_padding(padding)
+            .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+    )
+}
+
+pub fn encode(bytes: &[u8], url_safe: bool, padding: bool, wrap: bool) -> String {
+    let alphabet = if url_safe {
+        &alphabet::URL_SAFE
+    } else {
+        &alphabet::STANDARD
+    };
+    let encoded = engine(alphabet, padding).encode(bytes);
+    if !wrap {
+        return encoded;
+    }
+    encoded
+        .as_bytes()
+        .chunks(MIME_LINE)
+        .map(|line| String::from_utf8_lossy(line).into_owned())
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+/// Decodes either alphabet, ignoring whitespace and missing padding.
+/// Returns the bytes and a description of the variant found.
+pub fn decode(text: &str) -> Result<(Vec<u8>, String), String> {
+    let cleaned: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let url_safe = cleaned.contains(['-', '_']);
+    let standard = cleaned.contains(['+', '/']);
+    if url_safe && standard {
+        return Err("input mixes URL-safe (- _) and standard (+ /) characters".to_string());
+    }
+    let alphabet = if url_safe {
+        &alphabet::URL_SAFE
+    } else {
+        &alphabet::STANDARD
+    };
+    let bytes = engine(alphabet, true)
+        .decode(&cleaned)
+        .map_err(|e| describe_error(e, cleaned.len()))?;
+
+    let variant = format!(
+        "Detected {}, {}{}",
+        match (url_safe, standard) {
+            (true, _) => "URL-safe Base64",
+            (_, true) => "standard Base64",
+            _ => "Base64 valid in both the standard and URL-safe alphabets",
+        },
+        if cleaned.ends_with('=') {
+            "padded"
+        } else {
+            "unpadded"
+        },
+        if text.trim().contains('\n') {
+            ", line-wrapped"
+        } else {
+            ""
+        },
+    );
+    Ok((bytes, variant))
+}
+
+/// Explains a decode error; positions count from 1 and skip whitespace
+fn describe_error(error: DecodeError, length: usize) -> String {
+    match error {
+        DecodeError::InvalidByte(offset, b'=') => {
+            format!("unexpected padding at position {}", offset + 1)
+        }
+        DecodeError::InvalidByte(offset, byte) => format!(
+            "'{}' at position {} is not a Base64 character",
+            byte.escape_ascii(),
+            offset + 1
+        ),
+        DecodeError::InvalidLength => format!(
+            "{} characters cannot be Base64; one is missing or extra",
+            length
+        ),
+        DecodeError::InvalidLastSymbol(offset, byte) => format!(
+            "'{}' at position {} sets bits past the end of the data; the input may be truncated",
+            byte as char,
+            offset + 1
+        ),
+        DecodeError::InvalidPadding => "padding does not match the data length".to_string(),
+    }
+}
@@ -0,0 +1,154 @@
+//! Base85 variants: Ascii85 (Adobe), Z85 (ZeroMQ) and the RFC 1924 alphabet
+//! used by Git and Python's `b85encode`.
+//!
+//! All three turn 4 bytes into 5 characters. Ascii85 and RFC 1924 encode a
+//! short final group as one character more than its bytes; Z85 only takes
+//! whole groups.
+
+const Z85: &[u8; 85] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+const RFC1924: &[u8; 85] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+
+/// A Base85 alphabet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Ascii85,
+    Z85,
+    Rfc1924,
+}
+
+impl Variant {
+    fn digit(self, value: u32) -> char {
+        match self {
+            Variant::Ascii85 => (b'!' + value as u8) as char,
+            Variant::Z85 => Z85[value as usize] as char,
+            Variant::Rfc1924 => RFC1924[value as usize] as char,
+        }
+    }
+
+    fn value(self, c: char) -> Option<u32> {
+        let position = |alphabet: &[u8; 85]| {
+            alphabet
+                .iter()
+                .position(|&a| a as char == c)
+                .map(|i| i as u32)
+        };
+        match self {
+            Variant::Ascii85 => ('!'..='u').contains(&c).then(|| c as u32 - '!' as u32),
+            Variant::Z85 => position(Z85),
+            Variant::Rfc1924 => position(RFC1924),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Variant::Ascii85 => "Ascii85",
+            Variant::Z85 => "Z85",
+            Variant::Rfc1924 => "Base85",
+        }
+    }
+}
+
+/// Encodes `bytes`; Ascii85 output is wrapped in `<~` and `~>`
+pub fn encode(bytes: &[u8], variant: Variant) -> Result<String, String> {
+    if variant == Variant::Z85 && !bytes.len().is_multiple_of(4) {
+        return Err(format!(
+            "Z85 needs a multiple of 4 bytes, but the input is {} bytes",
+            bytes.len()
+        ));
+    }
+    let mut out = String::with_capacity(bytes.len().div_ceil(4) * 5 + 4);
+    if variant == Variant::Ascii85 {
+        out.push_str("<~");
+    }
+    for chunk in bytes.chunks(4) {
+        let mut group = [0u8; 4];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let mut value = u32::from_be_bytes(group);
+        // Ascii85 shortens a whole group of zeros to `z`
+        if variant == Variant::Ascii85 && value == 0 && chunk.len() == 4 {
+            out.push('z');
+            continue;
+        }
+        let mut digits = [' '; 5];
+        for digit in digits.iter_mut().rev() {
+            *digit = variant.digit(value % 85);
+            value /= 85;
+        }
+        out.extend(&digits[..chunk.len() + 1]);
+    }
+    if variant == Variant::Ascii85 {
+        out.push_str("~>");
+    }
+    Ok(out)
+}
+
+/// Decodes ignoring whitespace; Ascii85 input may omit its `<~ ~>` delimiters
+pub fn decode(text: &str, variant: Variant) -> Result<Vec<u8>, String> {
+    let mut text = text.trim();
+    if variant == Variant::Ascii85 {
+        text = text.strip_prefix("<~").unwrap_or(text);
+        text = text.strip_suffix("~>").unwrap_or(text);
+    }
+
+    let mut out = Vec::with_capacity(text.len() / 5 * 4 + 4);
+    let mut group = Vec::with_capacity(5);
+    let mut count = 0;
+    for (i, c) in text.chars().enumerate() {
+        if c.is_whitespace() {
+            continue;
+        }
+        count += 1;
+        if variant == Variant::Ascii85 && c == 'z' {
+            if !group.is_empty() {
+                return Err(format!(
+                    "'z' at position {} falls inside a group; it may only replace a whole one",
+                    i + 1
+                ));
+            }
+            out.extend([0; 4]);
+            continue;
+        }
+        let Some(value) = variant.value(c) else {
+            return Err(format!(
+                "'{}' at position {} is not a {} character",
+                c.escape_default(),
+                i + 1,
+                variant.label()
+            ));
+        };
+        group.push(value);
+        if group.len() == 5 {
+            out.extend(group_bytes(&group, i)?);
+            group.clear();
+        }
+    }
+
+    if !group.is_empty() {
+        if variant == Variant::Z85 {
+            return Err(format!(
+                "{} characters cannot be Z85; it needs a multiple of 5",
+                count
+            ));
+        }
+        if group.len() == 1 {
+            return Err("the last group has a single character, which holds no bytes".to_string());
+        }
+        // A short group was padded with the highest digit before encoding
+        let kept = group.len() - 1;
+        group.resize(5, 84);
+        out.extend(&group_bytes(&group, text.chars().count() - 1)?[..kept]);
+    }
+    Ok(out)
+}
+
+fn group_bytes(group: &[u32], position: usize) -> Result<[u8; 4], String> {
+    let value = group
+        .iter()
+        .try_fold(0u32, |value, &digit| {
+            value.checked_mul(85)?.checked_add(digit)
+        })
+        .ok_or_else(|| format!("the group ending at position {} is too large", position + 1))?;
+    Ok(value.to_be_bytes())
+}
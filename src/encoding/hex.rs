@@ -0,0 +1,92 @@
+//! Hexadecimal with optional separators between bytes.
+
+/// What goes between encoded bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Separator {
+    #[default]
+    None,
+    Space,
+    Colon,
+}
+
+impl Separator {
+    pub const ALL: [Separator; 3] = [Separator::None, Separator::Space, Separator::Colon];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Separator::None => "none",
+            Separator::Space => "space",
+            Separator::Colon => "colon",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Separator::None => "None",
+            Separator::Space => "Spaces",
+            Separator::Colon => "Colons",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|separator| separator.id() == id)
+    }
+
+    fn text(self) -> &'static str {
+        match self {
+            Separator::None => "",
+            Separator::Space => " ",
+            Separator::Colon => ":",
+        }
+    }
+}
+
+pub fn encode(bytes: &[u8], separator: Separator, uppercase: bool) -> String {
+    bytes
+        .iter()
+        .map(|byte| {
+            if uppercase {
+                format!("{:02X}", byte)
+            } else {
+                format!("{:02x}", byte)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(separator.text())
+}
+
+/// Decodes in either case, skipping whitespace, `:`, `-` and `,` separators
+/// and `0x` or `\x` prefixes
+pub fn decode(text: &str) -> Result<Vec<u8>, String> {
+    let mut digits = Vec::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            ':' | '-' | ',' => {}
+            '0' | '\\' if matches!(chars.peek(), Some((_, 'x' | 'X'))) => {
+                chars.next();
+            }
+            c => match c.to_digit(16) {
+                Some(digit) => digits.push(digit as u8),
+                None => {
+                    return Err(format!(
+                        "'{}' at position {} is not a hex digit",
+                        c.escape_default(),
+                        text[..i].chars().count() + 1
+                    ))
+                }
+            },
+        }
+    }
+    if digits.len() % 2 != 0 {
+        return Err(format!(
+            "{} hex digits cannot make whole bytes; one is missing or extra",
+            digits.len()
+        ));
+    }
+    Ok(digits
+        .chunks(2)
+        .map(|pair| (pair[0] << 4) | pair[1])
+        .collect())
+}
@@ -0,0 +1,361 @@
+//! Text and binary encodings for the encode/decode tool.
+//!
+//! Everything works on bytes: text is encoded as UTF-8 and decoders return
+//! bytes, so binary payloads survive a round trip.
+
+pub mod base32;
+pub mod base58;
+pub mod base64;
+pub mod base85;
+pub mod hex;
+pub mod html;
+pub mod percent;
+pub mod quoted_printable;
+
+use crate::binary::is_text;
+use base85::Variant;
+
+/// A supported encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    #[default]
+    Base64,
+    Base32,
+    Base32Crockford,
+    Base58,
+    Base85,
+    Ascii85,
+    Z85,
+    Hex,
+    Percent,
+    Html,
+    QuotedPrintable,
+}
+
+impl Encoding {
+    pub const ALL: [Encoding; 11] = [
+        Encoding::Base64,
+        Encoding::Base32,
+        Encoding::Base32Crockford,
+        Encoding::Base58,
+        Encoding::Base85,
+        Encoding::Ascii85,
+        Encoding::Z85,
+        Encoding::Hex,
+        Encoding::Percent,
+        Encoding::Html,
+        Encoding::QuotedPrintable,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Encoding::Base64 => "base64",
+            Encoding::Base32 => "base32",
+            Encoding::Base32Crockford => "crockford",
+            Encoding::Base58 => "base58",
+            Encoding::Base85 => "base85",
+            Encoding::Ascii85 => "ascii85",
+            Encoding::Z85 => "z85",
+            Encoding::Hex => "hex",
+            Encoding::Percent => "percent",
+            Encoding::Html => "html",
+            Encoding::QuotedPrintable => "qp",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Encoding::Base64 => "Base64",
+            Encoding::Base32 => "Base32",
+            Encoding::Base32Crockford => "Crockford Base32",
+            Encoding::Base58 => "Base58",
+            Encoding::Base85 => "Base85",
+            Encoding::Ascii85 => "Ascii85",
+            Encoding::Z85 => "Z85",
+            Encoding::Hex => "Hex",
+            Encoding::Percent => "URL",
+            Encoding::Html => "HTML Entities",
+            Encoding::QuotedPrintable => "Quoted-Printable",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|encoding| encoding.id() == id)
+    }
+}
+
+/// Settings that only some encodings use
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    /// Base64: URL-safe alphabet
+    pub url_safe: bool,
+    /// Base64 and RFC 4648 Base32: `=` padding
+    pub padding: bool,
+    /// Base64: MIME line wrapping
+    pub wrap: bool,
+    pub hex_separator: hex::Separator,
+    pub hex_uppercase: bool,
+    pub percent_mode: percent::Mode,
+    /// HTML: numeric references for every non-ASCII character
+    pub html_ascii_only: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            url_safe: false,
+            padding: true,
+            wrap: false,
+            hex_separator: hex::Separator::None,
+            hex_uppercase: false,
+            percent_mode: percent::Mode::Component,
+            html_ascii_only: false,
+        }
+    }
+}
+
+/// Decoded bytes and anything detected about the input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoded {
+    pub bytes: Vec<u8>,
+    /// Empty unless the decoder had something to report
+    pub note: String,
+}
+
+pub fn encode(encoding: Encoding, bytes: &[u8], options: &Options) -> Result<String, String> {
+    Ok(match encoding {
+        Encoding::Base64 => base64::encode(bytes, options.url_safe, options.padding, options.wrap),
+        Encoding::Base32 => base32::encode(bytes, false, options.padding),
+        Encoding::Base32Crockford => base32::encode(bytes, true, false),
+        Encoding::Base58 => base58::encode(bytes)?,
+        Encoding::Base85 => base85::encode(bytes, Variant::Rfc1924)?,
+        Encoding::Ascii85 => base85::encode(bytes, Variant::Ascii85)?,
+        Encoding::Z85 => base85::encode(bytes, Variant::Z85)?,
+        Encoding::Hex => hex::encode(bytes, options.hex_separator, options.hex_uppercase),
+        Encoding::Percent => percent::encode(bytes, options.percent_mode),
+        Encoding::Html => {
+            let text = std::str::from_utf8(bytes)
+                .map_err(|_| "HTML entities can only encode UTF-8 text".to_string())?;
+            html::encode(text, options.html_ascii_only)
+        }
+        Encoding::QuotedPrintable => quoted_printable::encode(bytes, is_text(bytes)),
+    })
+}
+
+pub fn decode(encoding: Encoding, text: &str) -> Result<Decoded, String> {
+    let decoded = match encoding {
+        Encoding::Base64 => {
+            let (bytes, note) = base64::decode(text).map_err(decode_error)?;
+            return Ok(Decoded { bytes, note });
+        }
+        Encoding::Base32 => base32::decode(text, false),
+        Encoding::Base32Crockford => base32::decode(text, true),
+        Encoding::Base58 => base58::decode(text),
+        Encoding::Base85 => base85::decode(text, Variant::Rfc1924),
+        Encoding::Ascii85 => base85::decode(text, Variant::Ascii85),
+        Encoding::Z85 => base85::decode(text, Variant::Z85),
+        Encoding::Hex => hex::decode(text),
+        Encoding::Percent => percent::decode(text),
+        Encoding::Html => Ok(html::decode(text).into_bytes()),
+        Encoding::QuotedPrintable => quoted_printable::decode(text),
+    };
+    Ok(Decoded {
+        bytes: decoded.map_err(decode_error)?,
+        note: String::new(),
+    })
+}
+
+fn decode_error(message: String) -> String {
+    format!("Decode error: {}", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples() -> Vec<Vec<u8>> {
+        let mut samples: Vec<Vec<u8>> = [
+            "",
+            "f",
+            "fo",
+            "foo",
+            "foob",
+            "fooba",
+            "foobar",
+            "Hello, World!",
+            "héllo wörld ✓ 🦀",
+            "a&b <c> \"d\" 'e'",
+            "line one\nline two \n\ttabbed\r\nwindows line",
+        ]
+        .iter()
+        .map(|s| s.as_bytes().to_vec())
+        .collect();
+        samples.push(vec![0, 0, 0, 0, 1, 2, 3]);
+        samples.push((0..=255).collect());
+        samples.push(b"=".repeat(100));
+        samples.push(b"x".repeat(300));
+        samples
+    }
+
+    fn round_trip(encoding: Encoding, options: &Options) {
+        for sample in samples() {
+            // Z85 only takes whole 4-byte groups, HTML only text
+            if (encoding == Encoding::Z85 && sample.len() % 4 != 0)
+                || (encoding == Encoding::Html && std::str::from_utf8(&sample).is_err())
+            {
+                assert!(encode(encoding, &sample, options).is_err());
+                continue;
+            }
+            let encoded = encode(encoding, &sample, options).unwrap();
+            let decoded = decode(encoding, &encoded).unwrap();
+            assert_eq!(
+                decoded.bytes,
+                sample,
+                "{} round trip via {:?}",
+                encoding.label(),
+                encoded
+            );
+        }
+    }
+
+    #[test]
+    fn every_encoding_round_trips() {
+        for encoding in Encoding::ALL {
+            round_trip(encoding, &Options::default());
+        }
+    }
+
+    #[test]
+    fn options_round_trip() {
+        let variants = [
+            Options {
+                url_safe: true,
+                padding: false,
+                ..Options::default()
+            },
+            Options {
+                wrap: true,
+                ..Options::default()
+            },
+            Options {
+                hex_separator: hex::Separator::Space,
+                hex_uppercase: true,
+                ..Options::default()
+            },
+            Options {
+                hex_separator: hex::Separator::Colon,
+                ..Options::default()
+            },
+            Options {
+                percent_mode: percent::Mode::Url,
+                html_ascii_only: true,
+                ..Options::default()
+            },
+        ];
+        for options in &variants {
+            for encoding in Encoding::ALL {
+                round_trip(encoding, options);
+            }
+        }
+    }
+
+    #[test]
+    fn known_vectors() {
+        let options = Options::default();
+        let cases: &[(Encoding, &[u8], &str)] = &[
+            (Encoding::Base64, b"foobar", "Zm9vYmFy"),
+            (Encoding::Base64, b"fo", "Zm8="),
+            (Encoding::Base32, b"fooba", "MZXW6YTB"),
+            (Encoding::Base32, b"foobar", "MZXW6YTBOI======"),
+            (Encoding::Base32Crockford, b"foobar", "CSQPYRK1E8"),
+            (Encoding::Base58, b"Hello World!", "2NEpo7TZRRrLZSi2U"),
+            (Encoding::Base58, &[0, 0, 1], "112"),
+            (Encoding::Base85, b"hello", "Xk~0{Zv"),
+            (Encoding::Ascii85, b"Man ", "<~9jqo^~>"),
+            (Encoding::Ascii85, &[0, 0, 0, 0], "<~z~>"),
+            (
+                Encoding::Z85,
+                &[0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B],
+                "HelloWorld",
+            ),
+            (Encoding::Hex, &[0xde, 0xad, 0xbe, 0xef], "deadbeef"),
+            (Encoding::Percent, "a b/ü".as_bytes(), "a%20b%2F%C3%BC"),
+            (
+                Encoding::Html,
+                b"<a href=\"x\">",
+                "&lt;a href=&quot;x&quot;&gt;",
+            ),
+            (
+                Encoding::QuotedPrintable,
+                "café=".as_bytes(),
+                "caf=C3=A9=3D",
+            ),
+        ];
+        for (encoding, bytes, expected) in cases {
+            assert_eq!(
+                encode(*encoding, bytes, &options).unwrap(),
+                *expected,
+                "{}",
+                encoding.label()
+            );
+        }
+    }
+
+    #[test]
+    fn lenient_decoding() {
+        let cases: &[(Encoding, &str, &[u8])] = &[
+            (Encoding::Base64, "Zm9v\nYmFy", b"foobar"),
+            (Encoding::Base64, "Zm8", b"fo"),
+            (Encoding::Base32, "mzxw6ytboi", b"foobar"),
+            (Encoding::Base32Crockford, "csqp-yrk1-e8", b"foobar"),
+            (Encoding::Base32Crockford, "CSQPYRKLE8", b"foobar"),
+            (Encoding::Ascii85, "9jqo^", b"Man "),
+            (Encoding::Hex, "0xDE 0xAD:be-ef", &[0xde, 0xad, 0xbe, 0xef]),
+            (Encoding::Hex, "\\xde\\xad", &[0xde, 0xad]),
+            (
+                Encoding::Html,
+                "&eacute;&#233;&#xE9;&copy;&bogus; & x",
+                "ééé©&bogus; & x".as_bytes(),
+            ),
+            (
+                Encoding::QuotedPrintable,
+                "soft=\r\nbreak  \r\nend",
+                b"softbreak\r\nend",
+            ),
+        ];
+        for (encoding, text, expected) in cases {
+            assert_eq!(
+                decode(*encoding, text).unwrap().bytes,
+                *expected,
+                "{}",
+                encoding.label()
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_input_is_rejected() {
+        let cases: &[(Encoding, &str)] = &[
+            (Encoding::Base64, "Zm9v+_"),
+            (Encoding::Base64, "Zm9vY"),
+            (Encoding::Base32, "MZXW6Y1B"),
+            (Encoding::Base32, "MZX"),
+            (Encoding::Base58, "0OIl"),
+            (Encoding::Ascii85, "9jzqo"),
+            (Encoding::Z85, "He~lo"),
+            (Encoding::Z85, "Hell"),
+            (Encoding::Hex, "abc"),
+            (Encoding::Hex, "zz"),
+            (Encoding::Percent, "100%"),
+            (Encoding::QuotedPrintable, "a=ZZ"),
+        ];
+        for (encoding, text) in cases {
+            assert!(
+                decode(*encoding, text).is_err(),
+                "{} accepted {:?}",
+                encoding.label(),
+                text
+            );
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! URL percent-encoding (RFC 3986).
+
+/// How much of the input is escaped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// A single query value or path segment: only unreserved characters stay
+    #[default]
+    Component,
+    /// A whole URL: reserved characters such as `/`, `?` and `&` stay too
+    Url,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 2] = [Mode::Component, Mode::Url];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Mode::Component => "component",
+            Mode::Url => "url",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Mode::Component => "Component",
+            Mode::Url => "Full URL",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.id() == id)
+    }
+}
+
+/// Reserved characters (RFC 3986 section 2.2) kept when encoding a full URL
+const RESERVED: &[u8] = b":/?#[]@!$&'()*+,;=";
+
+pub fn encode(bytes: &[u8], mode: Mode) -> String {
+    match mode {
+        Mode::Component => urlencoding::encode_binary(bytes).into_owned(),
+        Mode::Url => {
+            let mut out = String::with_capacity(bytes.len());
+            for &byte in bytes {
+                if byte.is_ascii_alphanumeric()
+                    || b"-._~".contains(&byte)
+                    || RESERVED.contains(&byte)
+                {
+                    out.push(byte as char);
+                } else {
+                    out.push_str(&format!("%{:02X}", byte));
+                }
+            }
+            out
+        }
+    }
+}
+
+/// Decodes `%XX` escapes, rejecting malformed ones. `+` is left alone since
+/// only form encoding uses it for spaces.
+pub fn decode(text: &str) -> Result<Vec<u8>, String> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'%' {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        let escape = bytes
+            .get(i + 1..i + 3)
+            .and_then(|pair| std::str::from_utf8(pair).ok())
+            .and_then(|pair| u8::from_str_radix(pair, 16).ok());
+        let Some(byte) = escape else {
+            return Err(format!(
+                "'%' at position {} is not followed by two hex digits",
+                text[..i].chars().count() + 1
+            ));
+        };
+        out.push(byte);
+        i += 3;
+    }
+    Ok(out)
+}
@@ -0,0 +1,98 @@
+//! Quoted-printable (RFC 2045).
+
+/// Longest encoded line, not counting the line break
+const MAX_LINE: usize = 76;
+
+/// Encodes `bytes`. Text keeps its line breaks as hard breaks; in binary data
+/// CR and LF are escaped like any other byte.
+pub fn encode(bytes: &[u8], text: bool) -> String {
+    // Soft breaks follow the input's line ending style
+    let crlf = !text || bytes.windows(2).any(|pair| pair == b"\r\n");
+    let soft_break = if crlf { "=\r\n" } else { "=\n" };
+
+    let mut out = String::with_capacity(bytes.len() * 3 / 2);
+    let mut line_length = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        if text && (byte == b'\n' || bytes[i..].starts_with(b"\r\n")) {
+            let hard_break = if byte == b'\n' { "\n" } else { "\r\n" };
+            out.push_str(hard_break);
+            line_length = 0;
+            i += hard_break.len();
+            continue;
+        }
+
+        // Spaces and tabs are only literal where they cannot end a line
+        let at_line_end = match bytes.get(i + 1) {
+            None => true,
+            Some(b'\n') | Some(b'\r') => text,
+            Some(_) => false,
+        };
+        let literal = (byte.is_ascii_graphic() && byte != b'=')
+            || (matches!(byte, b' ' | b'\t') && !at_line_end);
+        let token = if literal {
+            (byte as char).to_string()
+        } else {
+            format!("={:02X}", byte)
+        };
+
+        // Leave room for the `=` of a soft break
+        if line_length + token.len() > MAX_LINE - 1 {
+            out.push_str(soft_break);
+            line_length = 0;
+        }
+        out.push_str(&token);
+        line_length += token.len();
+        i += 1;
+    }
+    out
+}
+
+/// Decodes `=XX` escapes and removes soft line breaks, along with the
+/// trailing whitespace that transports may add before a line break
+pub fn decode(text: &str) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(text.len());
+    let mut position = 0;
+    for line in text.split_inclusive('\n') {
+        let (content, line_break) = match line.strip_suffix("\r\n") {
+            Some(content) => (content, "\r\n"),
+            None => match line.strip_suffix('\n') {
+                Some(content) => (content, "\n"),
+                None => (line, ""),
+            },
+        };
+        let content = content.trim_end_matches([' ', '\t']);
+        let (content, soft) = match content.strip_suffix('=') {
+            Some(content) => (content, true),
+            None => (content, false),
+        };
+
+        let bytes = content.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] != b'=' {
+                out.push(bytes[i]);
+                i += 1;
+                continue;
+            }
+            let escape = bytes
+                .get(i + 1..i + 3)
+                .and_then(|pair| std::str::from_utf8(pair).ok())
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok());
+            let Some(byte) = escape else {
+                return Err(format!(
+                    "'=' at position {} is not followed by two hex digits or a line break",
+                    position + content[..i].chars().count() + 1
+                ));
+            };
+            out.push(byte);
+            i += 3;
+        }
+        if !soft {
+            out.extend_from_slice(line_break.as_bytes());
+        }
+        position += line.chars().count();
+    }
+    Ok(out)
+}
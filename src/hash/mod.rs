@@ -0,0 +1,348 @@
+//! Message digests, checksums and HMACs, plus reading the expected values
+//! people paste next to them.
+
+use crate::encoding::{base64, hex};
+use blake2::{Blake2b512, Blake2s256};
+use hmac::digest::core_api::BlockSizeUser;
+use hmac::digest::{Digest, KeyInit};
+use hmac::{Mac, SimpleHmac};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Sha224, Sha256, Sha384, Sha512};
+use sha3::{Sha3_224, Sha3_256, Sha3_384, Sha3_512};
+
+/// A hash or checksum algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Md5,
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+    Sha3_224,
+    Sha3_256,
+    Sha3_384,
+    Sha3_512,
+    Blake2b,
+    Blake2s,
+    Blake3,
+    Crc32,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 14] = [
+        Algorithm::Md5,
+        Algorithm::Sha1,
+        Algorithm::Sha224,
+        Algorithm::Sha256,
+        Algorithm::Sha384,
+        Algorithm::Sha512,
+        Algorithm::Sha3_224,
+        Algorithm::Sha3_256,
+        Algorithm::Sha3_384,
+        Algorithm::Sha3_512,
+        Algorithm::Blake2b,
+        Algorithm::Blake2s,
+        Algorithm::Blake3,
+        Algorithm::Crc32,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Algorithm::Md5 => "MD5",
+            Algorithm::Sha1 => "SHA-1",
+            Algorithm::Sha224 => "SHA-224",
+            Algorithm::Sha256 => "SHA-256",
+            Algorithm::Sha384 => "SHA-384",
+            Algorithm::Sha512 => "SHA-512",
+            Algorithm::Sha3_224 => "SHA3-224",
+            Algorithm::Sha3_256 => "SHA3-256",
+            Algorithm::Sha3_384 => "SHA3-384",
+            Algorithm::Sha3_512 => "SHA3-512",
+            Algorithm::Blake2b => "BLAKE2b-512",
+            Algorithm::Blake2s => "BLAKE2s-256",
+            Algorithm::Blake3 => "BLAKE3",
+            Algorithm::Crc32 => "CRC32",
+        }
+    }
+}
+
+fn digest_with<D: Digest>(bytes: &[u8]) -> Vec<u8> {
+    D::digest(bytes).to_vec()
+}
+
+/// The digest of `bytes`; CRC32 is returned big-endian, as usually printed
+pub fn digest(algorithm: Algorithm, bytes: &[u8]) -> Vec<u8> {
+    match algorithm {
+        Algorithm::Md5 => digest_with::<Md5>(bytes),
+        Algorithm::Sha1 => digest_with::<Sha1>(bytes),
+        Algorithm::Sha224 => digest_with::<Sha224>(bytes),
+        Algorithm::Sha256 => digest_with::<Sha256>(bytes),
+        Algorithm::Sha384 => digest_with::<Sha384>(bytes),
+        Algorithm::Sha512 => digest_with::<Sha512>(bytes),
+        Algorithm::Sha3_224 => digest_with::<Sha3_224>(bytes),
+        Algorithm::Sha3_256 => digest_with::<Sha3_256>(bytes),
+        Algorithm::Sha3_384 => digest_with::<Sha3_384>(bytes),
+        Algorithm::Sha3_512 => digest_with::<Sha3_512>(bytes),
+        Algorithm::Blake2b => digest_with::<Blake2b512>(bytes),
+        Algorithm::Blake2s => digest_with::<Blake2s256>(bytes),
+        Algorithm::Blake3 => blake3::hash(bytes).as_bytes().to_vec(),
+        Algorithm::Crc32 => crc32fast::hash(bytes).to_be_bytes().to_vec(),
+    }
+}
+
+fn hmac_with<D: Digest + BlockSizeUser>(key: &[u8], bytes: &[u8]) -> Vec<u8> {
+    let mut mac =
+        <SimpleHmac<D> as KeyInit>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(bytes);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// The HMAC (RFC 2104) of `bytes` under `key`. BLAKE3 has its own keyed
+/// mode and CRC32 is no hash, so neither has one.
+pub fn hmac(algorithm: Algorithm, key: &[u8], bytes: &[u8]) -> Option<Vec<u8>> {
+    Some(match algorithm {
+        Algorithm::Md5 => hmac_with::<Md5>(key, bytes),
+        Algorithm::Sha1 => hmac_with::<Sha1>(key, bytes),
+        Algorithm::Sha224 => hmac_with::<Sha224>(key, bytes),
+        Algorithm::Sha256 => hmac_with::<Sha256>(key, bytes),
+        Algorithm::Sha384 => hmac_with::<Sha384>(key, bytes),
+        Algorithm::Sha512 => hmac_with::<Sha512>(key, bytes),
+        Algorithm::Sha3_224 => hmac_with::<Sha3_224>(key, bytes),
+        Algorithm::Sha3_256 => hmac_with::<Sha3_256>(key, bytes),
+        Algorithm::Sha3_384 => hmac_with::<Sha3_384>(key, bytes),
+        Algorithm::Sha3_512 => hmac_with::<Sha3_512>(key, bytes),
+        Algorithm::Blake2b => hmac_with::<Blake2b512>(key, bytes),
+        Algorithm::Blake2s => hmac_with::<Blake2s256>(key, bytes),
+        Algorithm::Blake3 | Algorithm::Crc32 => return None,
+    })
+}
+
+/// How digests are written out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Hex,
+    Base64,
+}
+
+impl OutputFormat {
+    pub const ALL: [OutputFormat; 2] = [OutputFormat::Hex, OutputFormat::Base64];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            OutputFormat::Hex => "hex",
+            OutputFormat::Base64 => "base64",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OutputFormat::Hex => "Hex",
+            OutputFormat::Base64 => "Base64",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.id() == id)
+    }
+
+    pub fn format(self, digest: &[u8]) -> String {
+        match self {
+            OutputFormat::Hex => hex::encode(digest, hex::Separator::None, false),
+            OutputFormat::Base64 => base64::encode(digest, false, true, false),
+        }
+    }
+}
+
+/// Drops a `sha256=` or `sha256:` style label, as webhook signature headers
+/// carry. Base64 padding is left alone.
+fn strip_label(text: &str) -> &str {
+    match text.split_once(['=', ':']) {
+        Some((label, rest))
+            if !label.is_empty()
+                && !rest.is_empty()
+                && !rest.starts_with('=')
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+        {
+            rest
+        }
+        _ => text,
+    }
+}
+
+/// The digest bytes of a pasted checksum, in hex or Base64. Accepts
+/// `sha256sum` lines (`<hash>  <file>`), BSD `shasum --tag` lines
+/// (`SHA256 (<file>) = <hash>`) and labelled values like `sha256=<hash>`.
+pub fn parse_expected(text: &str) -> Result<Vec<u8>, String> {
+    let text = text.trim();
+    let text = match text.rsplit_once(" = ") {
+        Some((_, hash)) => hash,
+        None => text.split_whitespace().next().unwrap_or_default(),
+    };
+    let text = strip_label(text.trim());
+    if text.len().is_multiple_of(2) && text.chars().all(|c| c.is_ascii_hexdigit()) {
+        return hex::decode(text);
+    }
+    base64::decode(text)
+        .map(|(bytes, _)| bytes)
+        .map_err(|_| "Expected hash is neither hex nor Base64".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        OutputFormat::Hex.format(bytes)
+    }
+
+    #[test]
+    fn known_answers() {
+        // Empty input and "abc": NIST FIPS 180 and 202 examples, RFC 1321,
+        // RFC 7693 and the BLAKE3 reference vectors
+        let vectors = [
+            (Algorithm::Md5, "d41d8cd98f00b204e9800998ecf8427e", "900150983cd24fb0d6963f7d28e17f72"),
+            (Algorithm::Sha1, "da39a3ee5e6b4b0d3255bfef95601890afd80709", "a9993e364706816aba3e25717850c26c9cd0d89d"),
+            (Algorithm::Sha224, "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f", "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7"),
+            (Algorithm::Sha256, "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855", "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+            (Algorithm::Sha384, "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b", "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7"),
+            (Algorithm::Sha512, "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e", "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"),
+            (Algorithm::Sha3_224, "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7", "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf"),
+            (Algorithm::Sha3_256, "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a", "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"),
+            (Algorithm::Sha3_384, "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2ac3713831264adb47fb6bd1e058d5f004", "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b298d88cea927ac7f539f1edf228376d25"),
+            (Algorithm::Sha3_512, "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a615b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26", "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"),
+            (Algorithm::Blake2b, "786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce", "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"),
+            (Algorithm::Blake2s, "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9", "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982"),
+            (Algorithm::Blake3, "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262", "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"),
+            (Algorithm::Crc32, "00000000", "352441c2"),
+        ];
+        assert_eq!(vectors.len(), Algorithm::ALL.len());
+        for (algorithm, empty, abc) in vectors {
+            assert_eq!(hex(&digest(algorithm, b"")), empty, "{}", algorithm.label());
+            assert_eq!(
+                hex(&digest(algorithm, b"abc")),
+                abc,
+                "{}",
+                algorithm.label()
+            );
+        }
+        assert_eq!(hex(&digest(Algorithm::Crc32, b"123456789")), "cbf43926");
+    }
+
+    #[test]
+    fn hmac_rfc4231() {
+        let long_key = [0xaa; 131];
+        let cases: [(&[u8], &[u8], [&str; 4]); 3] = [
+            (&[0x0b; 20], b"Hi There", [
+                "896fb1128abbdf196832107cd49df33f47b4b1169912ba4f53684b22",
+                "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+                "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59cfaea9ea9076ede7f4af152e8b2fa9cb6",
+                "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cdedaa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+            ]),
+            (b"Jefe", b"what do ya want for nothing?", [
+                "a30e01098bc6dbbf45690f3a7e9e6d0f8bbea2a39e6148008fd05e44",
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+                "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649",
+                "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+            ]),
+            // Keys longer than the block are hashed first
+            (&long_key, b"Test Using Larger Than Block-Size Key - Hash Key First", [
+                "95e9a0db962095adaebe9b2d6f0dbce2d499f112f2d2b7273fa6870e",
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+                "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c60c2ef6ab4030fe8296248df163f44952",
+                "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+            ]),
+        ];
+        let algorithms = [
+            Algorithm::Sha224,
+            Algorithm::Sha256,
+            Algorithm::Sha384,
+            Algorithm::Sha512,
+        ];
+        for (key, data, expected) in cases {
+            for (algorithm, mac) in algorithms.into_iter().zip(expected) {
+                assert_eq!(
+                    hex(&hmac(algorithm, key, data).unwrap()),
+                    mac,
+                    "{}",
+                    algorithm.label()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn hmac_rfc2202() {
+        let long_key = [0xaa; 80];
+        let long_data = b"Test Using Larger Than Block-Size Key - Hash Key First";
+        let md5 = |key: &[u8], data: &[u8]| hex(&hmac(Algorithm::Md5, key, data).unwrap());
+        let sha1 = |key: &[u8], data: &[u8]| hex(&hmac(Algorithm::Sha1, key, data).unwrap());
+        assert_eq!(
+            md5(&[0x0b; 16], b"Hi There"),
+            "9294727a3638bb1c13f48ef8158bfc9d"
+        );
+        assert_eq!(
+            md5(b"Jefe", b"what do ya want for nothing?"),
+            "750c783e6ab0b503eaa86e310a5db738"
+        );
+        assert_eq!(
+            md5(&long_key, long_data),
+            "6b1ab7fe4bd7bf8f0b62e6ce61b9d0cd"
+        );
+        assert_eq!(
+            sha1(&[0x0b; 20], b"Hi There"),
+            "b617318655057264e28bc0b6fb378c8ef146be00"
+        );
+        assert_eq!(
+            sha1(b"Jefe", b"what do ya want for nothing?"),
+            "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79"
+        );
+        assert_eq!(
+            sha1(&long_key, long_data),
+            "aa4ae5e15272d00e95705637ce8a3b55ed402112"
+        );
+        assert_eq!(hmac(Algorithm::Blake3, b"key", b""), None);
+        assert_eq!(hmac(Algorithm::Crc32, b"key", b""), None);
+    }
+
+    #[test]
+    fn expected_values() {
+        let sha256 = digest(Algorithm::Sha256, b"abc");
+        let hash = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        for text in [
+            hash.to_string(),
+            hash.to_uppercase(),
+            format!("  {}  abc.txt\n", hash),
+            format!("{} *abc.txt", hash),
+            format!("SHA256 (abc.txt) = {}", hash),
+            format!("SHA256 (a = b.txt) = {}", hash),
+            format!("sha256={}", hash),
+            format!("sha256:{}", hash),
+            "ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0=".to_string(),
+            "sha-256=ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0=".to_string(),
+        ] {
+            assert_eq!(parse_expected(&text).as_ref(), Ok(&sha256), "{}", text);
+        }
+        // Padding is not mistaken for a label
+        assert_eq!(
+            parse_expected("kAFQmDzST7DWlj99KOF/cg==").unwrap(),
+            digest(Algorithm::Md5, b"abc")
+        );
+        assert_eq!(
+            parse_expected("md5=kAFQmDzST7DWlj99KOF/cg==").unwrap(),
+            digest(Algorithm::Md5, b"abc")
+        );
+        assert_eq!(
+            parse_expected("not a hash!").unwrap_err(),
+            "Expected hash is neither hex nor Base64"
+        );
+        assert_eq!(
+            OutputFormat::Base64.format(&sha256),
+            "ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0="
+        );
+    }
+}
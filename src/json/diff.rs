@@ -0,0 +1,585 @@
+//! Structural comparison of two JSON documents.
+//!
+//! Objects are compared by key regardless of order and numbers by value, so
+//! only semantic differences are reported. Alongside the change list an
+//! RFC 6902 patch is produced whose operations apply cleanly in sequence.
+
+use super::tree::{escape_pointer, path_segment};
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+
+/// How elements of two arrays are paired up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArrayMatch {
+    /// Element `i` on the left is compared with element `i` on the right
+    Index,
+    /// Objects are paired by the value of this field. Arrays where that is
+    /// not possible (missing or duplicate keys) fall back to [`ArrayMatch::Index`].
+    Key(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+    /// An array element matched by key that sits at a different position
+    Moved,
+}
+
+impl ChangeKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Changed => "changed",
+            ChangeKind::Moved => "moved",
+        }
+    }
+}
+
+/// One difference between the documents
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// JSONPath of the value, using right-hand indices where it exists there
+    pub path: String,
+    /// JSON Pointer into the left document, unless the value was added
+    pub left_pointer: Option<String>,
+    /// JSON Pointer into the right document, unless the value was removed
+    pub right_pointer: Option<String>,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+/// Result of [`diff`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diff {
+    pub changes: Vec<Change>,
+    /// RFC 6902 operations turning the left document into the right one
+    pub patch: Vec<Value>,
+}
+
+impl Diff {
+    pub fn count(&self, kind: ChangeKind) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.kind == kind)
+            .count()
+    }
+}
+
+/// Compares `left` with `right`
+pub fn diff(left: &Value, right: &Value, arrays: &ArrayMatch) -> Diff {
+    let mut walker = Walker {
+        arrays,
+        diff: Diff::default(),
+    };
+    let root = Position {
+        left: String::new(),
+        right: String::new(),
+        path: "$".to_string(),
+    };
+    walker.node(left, right, &root);
+    walker.diff
+}
+
+/// Whether two values are equal ignoring key order and number formatting
+pub fn same(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => {
+            canonical_number(&a.to_string()) == canonical_number(&b.to_string())
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, value)| b.get(key).is_some_and(|other| same(value, other)))
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(x, y)| same(x, y))
+        }
+        _ => left == right,
+    }
+}
+
+/// Sign, significant digits and exponent of a decimal number literal
+fn canonical_number(text: &str) -> (bool, String, i64) {
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(i) => (&text[..i], text[i + 1..].parse::<i64>().unwrap_or(0)),
+        None => (text, 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{}{}", integer, fraction);
+    let leading = digits.len() - digits.trim_start_matches('0').len();
+    let digits = digits.trim_start_matches('0');
+    let trimmed = digits.trim_end_matches('0');
+    if trimmed.is_empty() {
+        return (false, String::new(), 0);
+    }
+    // Exponent of the first significant digit
+    let exponent = exponent + integer.len() as i64 - leading as i64;
+    (negative, trimmed.to_string(), exponent)
+}
+
+/// Where the walker currently is in both documents
+struct Position {
+    left: String,
+    /// Pointer into the right document, which is also where the patch has
+    /// placed this value by the time its children are visited
+    right: String,
+    path: String,
+}
+
+impl Position {
+    fn key(&self, key: &str) -> Position {
+        let escaped = escape_pointer(key);
+        Position {
+            left: format!("{}/{}", self.left, escaped),
+            right: format!("{}/{}", self.right, escaped),
+            path: format!("{}{}", self.path, path_segment(key)),
+        }
+    }
+
+    fn index(&self, left: usize, right: usize) -> Position {
+        Position {
+            left: format!("{}/{}", self.left, left),
+            right: format!("{}/{}", self.right, right),
+            path: format!("{}[{}]", self.path, right),
+        }
+    }
+}
+
+struct Walker<'a> {
+    arrays: &'a ArrayMatch,
+    diff: Diff,
+}
+
+impl Walker<'_> {
+    fn node(&mut self, left: &Value, right: &Value, at: &Position) {
+        if same(left, right) {
+            return;
+        }
+        match (left, right) {
+            (Value::Object(l), Value::Object(r)) => self.object(l, r, at),
+            (Value::Array(l), Value::Array(r)) => self.array(l, r, at),
+            _ => {
+                self.diff.changes.push(Change {
+                    kind: ChangeKind::Changed,
+                    path: at.path.clone(),
+                    left_pointer: Some(at.left.clone()),
+                    right_pointer: Some(at.right.clone()),
+                    old: Some(left.clone()),
+                    new: Some(right.clone()),
+                });
+                self.diff
+                    .patch
+                    .push(json!({ "op": "replace", "path": at.right, "value": right }));
+            }
+        }
+    }
+
+    fn object(&mut self, left: &Map<String, Value>, right: &Map<String, Value>, at: &Position) {
+        for (key, value) in left {
+            if !right.contains_key(key) {
+                self.removed(value, &at.key(key));
+            }
+        }
+        for (key, value) in right {
+            match left.get(key) {
+                Some(old) => self.node(old, value, &at.key(key)),
+                None => self.added(value, &at.key(key)),
+            }
+        }
+    }
+
+    fn array(&mut self, left: &[Value], right: &[Value], at: &Position) {
+        let keys = match self.arrays {
+            ArrayMatch::Key(field) => element_keys(left, field).zip(element_keys(right, field)),
+            ArrayMatch::Index => None,
+        };
+        let Some((left_keys, right_keys)) = keys else {
+            // Trailing removals first, from the end so indices stay valid
+            let common = left.len().min(right.len());
+            for i in (common..left.len()).rev() {
+                self.removed(&left[i], &at.index(i, i));
+            }
+            for (i, value) in right.iter().enumerate().skip(common) {
+                self.added(value, &at.index(i, i));
+            }
+            for (i, (old, new)) in left.iter().zip(right).enumerate() {
+                self.node(old, new, &at.index(i, i));
+            }
+            return;
+        };
+
+        let left_index: HashMap<&str, usize> = left_keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (key.as_str(), i))
+            .collect();
+        let right_index: HashMap<&str, usize> = right_keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (key.as_str(), i))
+            .collect();
+
+        for i in (0..left.len()).rev() {
+            if !right_index.contains_key(left_keys[i].as_str()) {
+                self.removed(&left[i], &at.index(i, i));
+            }
+        }
+
+        // Bring the surviving elements into right-hand order
+        let mut current: Vec<&str> = left_keys
+            .iter()
+            .map(String::as_str)
+            .filter(|key| right_index.contains_key(key))
+            .collect();
+        let target: Vec<&str> = right_keys
+            .iter()
+            .map(String::as_str)
+            .filter(|key| left_index.contains_key(key))
+            .collect();
+        for (i, key) in target.iter().enumerate() {
+            if current[i] == *key {
+                continue;
+            }
+            let from = current[i..]
+                .iter()
+                .position(|k| k == key)
+                .map_or(i, |offset| i + offset);
+            let moved = current.remove(from);
+            current.insert(i, moved);
+            self.diff.changes.push(Change {
+                kind: ChangeKind::Moved,
+                path: format!("{}[{}]", at.path, right_index[key]),
+                left_pointer: Some(format!("{}/{}", at.left, left_index[key])),
+                right_pointer: Some(format!("{}/{}", at.right, right_index[key])),
+                old: None,
+                new: None,
+            });
+            self.diff.patch.push(json!({
+                "op": "move",
+                "from": format!("{}/{}", at.right, from),
+                "path": format!("{}/{}", at.right, i),
+            }));
+        }
+
+        for (i, key) in right_keys.iter().enumerate() {
+            if !left_index.contains_key(key.as_str()) {
+                self.added(&right[i], &at.index(i, i));
+            }
+        }
+
+        for (r, key) in right_keys.iter().enumerate() {
+            if let Some(&l) = left_index.get(key.as_str()) {
+                self.node(&left[l], &right[r], &at.index(l, r));
+            }
+        }
+    }
+
+    fn added(&mut self, value: &Value, at: &Position) {
+        self.diff.changes.push(Change {
+            kind: ChangeKind::Added,
+            path: at.path.clone(),
+            left_pointer: None,
+            right_pointer: Some(at.right.clone()),
+            old: None,
+            new: Some(value.clone()),
+        });
+        self.diff
+            .patch
+            .push(json!({ "op": "add", "path": at.right, "value": value }));
+    }
+
+    fn removed(&mut self, value: &Value, at: &Position) {
+        self.diff.changes.push(Change {
+            kind: ChangeKind::Removed,
+            path: at.path.clone(),
+            left_pointer: Some(at.left.clone()),
+            right_pointer: None,
+            old: Some(value.clone()),
+            new: None,
+        });
+        self.diff
+            .patch
+            .push(json!({ "op": "remove", "path": at.right }));
+    }
+}
+
+/// Key values of every element, if all are objects with a distinct `field`
+fn element_keys(items: &[Value], field: &str) -> Option<Vec<String>> {
+    let mut seen = HashSet::new();
+    items
+        .iter()
+        .map(|item| {
+            let key = item.get(field)?;
+            let key = match key {
+                Value::Number(n) => format!("{:?}", canonical_number(&n.to_string())),
+                other => other.to_string(),
+            };
+            seen.insert(key.clone()).then_some(key)
+        })
+        .collect()
+}
+
+/// Pretty-printed lines of `value`, each tagged with the JSON Pointer of the
+/// value it belongs to
+pub fn annotated_lines(value: &Value) -> Vec<(String, String)> {
+    let mut lines = Vec::new();
+    write_lines(value, String::new(), 0, "", "", &mut lines);
+    lines
+}
+
+fn write_lines(
+    value: &Value,
+    pointer: String,
+    depth: usize,
+    key: &str,
+    comma: &str,
+    lines: &mut Vec<(String, String)>,
+) {
+    let indent = "  ".repeat(depth);
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            lines.push((pointer.clone(), format!("{}{}{{", indent, key)));
+            for (i, (child_key, child)) in map.iter().enumerate() {
+                write_lines(
+                    child,
+                    format!("{}/{}", pointer, escape_pointer(child_key)),
+                    depth + 1,
+                    &format!("{}: ", Value::String(child_key.clone())),
+                    if i + 1 < map.len() { "," } else { "" },
+                    lines,
+                );
+            }
+            lines.push((pointer, format!("{}}}{}", indent, comma)));
+        }
+        Value::Array(items) if !items.is_empty() => {
+            lines.push((pointer.clone(), format!("{}{}[", indent, key)));
+            for (i, child) in items.iter().enumerate() {
+                write_lines(
+                    child,
+                    format!("{}/{}", pointer, i),
+                    depth + 1,
+                    "",
+                    if i + 1 < items.len() { "," } else { "" },
+                    lines,
+                );
+            }
+            lines.push((pointer, format!("{}]{}", indent, comma)));
+        }
+        scalar => lines.push((pointer, format!("{}{}{}{}", indent, key, scalar, comma))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parent pointer and unescaped last token of `pointer`
+    fn split(pointer: &str) -> (&str, String) {
+        let (parent, last) = pointer.rsplit_once('/').unwrap();
+        (parent, last.replace("~1", "/").replace("~0", "~"))
+    }
+
+    fn take(doc: &mut Value, pointer: &str) -> Value {
+        let (parent, token) = split(pointer);
+        match doc.pointer_mut(parent).unwrap() {
+            Value::Object(map) => map.shift_remove(&token).unwrap(),
+            Value::Array(items) => items.remove(token.parse().unwrap()),
+            other => panic!("cannot remove from {}", other),
+        }
+    }
+
+    fn put(doc: &mut Value, pointer: &str, value: Value) {
+        if pointer.is_empty() {
+            *doc = value;
+            return;
+        }
+        let (parent, token) = split(pointer);
+        match doc.pointer_mut(parent).unwrap() {
+            Value::Object(map) => {
+                map.insert(token, value);
+            }
+            Value::Array(items) if token == "-" => items.push(value),
+            Value::Array(items) => items.insert(token.parse().unwrap(), value),
+            other => panic!("cannot add to {}", other),
+        }
+    }
+
+    /// Applies an RFC 6902 patch made of the operations [`diff`] emits
+    fn apply(doc: &Value, patch: &[Value]) -> Value {
+        let mut doc = doc.clone();
+        for op in patch {
+            let path = op["path"].as_str().unwrap();
+            match op["op"].as_str().unwrap() {
+                "add" => put(&mut doc, path, op["value"].clone()),
+                "remove" => {
+                    take(&mut doc, path);
+                }
+                "replace" => *doc.pointer_mut(path).unwrap() = op["value"].clone(),
+                "move" => {
+                    let value = take(&mut doc, op["from"].as_str().unwrap());
+                    put(&mut doc, path, value);
+                }
+                other => panic!("unexpected op {}", other),
+            }
+        }
+        doc
+    }
+
+    /// Diffs the documents and checks the patch turns one into the other
+    fn check(left: &str, right: &str, arrays: ArrayMatch) -> Diff {
+        let left: Value = serde_json::from_str(left).unwrap();
+        let right: Value = serde_json::from_str(right).unwrap();
+        let result = diff(&left, &right, &arrays);
+        let patched = apply(&left, &result.patch);
+        assert!(
+            same(&patched, &right),
+            "patch {:?} gave {}",
+            result.patch,
+            patched
+        );
+        result
+    }
+
+    fn kinds(diff: &Diff) -> Vec<(ChangeKind, &str)> {
+        diff.changes
+            .iter()
+            .map(|change| (change.kind, change.path.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn equal_documents() {
+        let result = check(
+            r#"{"a": 1.0, "b": [1, {"c": null}]}"#,
+            r#"{"b": [1e0, {"c": null}], "a": 10e-1}"#,
+            ArrayMatch::Index,
+        );
+        assert_eq!(result, Diff::default());
+    }
+
+    #[test]
+    fn objects() {
+        let result = check(
+            r#"{"keep": 1, "gone": true, "nested": {"x": "old"}}"#,
+            r#"{"keep": 1, "nested": {"x": "new", "y": []}, "extra": {}}"#,
+            ArrayMatch::Index,
+        );
+        assert_eq!(
+            kinds(&result),
+            [
+                (ChangeKind::Removed, "$.gone"),
+                (ChangeKind::Changed, "$.nested.x"),
+                (ChangeKind::Added, "$.nested.y"),
+                (ChangeKind::Added, "$.extra"),
+            ]
+        );
+        assert_eq!(result.changes[1].old, Some(json!("old")));
+        assert_eq!(result.changes[1].new, Some(json!("new")));
+    }
+
+    #[test]
+    fn type_changes() {
+        let result = check(
+            r#"{"a": [1], "b": {"c": 1}, "c": "1", "d": null}"#,
+            r#"{"a": {"0": 1}, "b": [1], "c": 1, "d": false}"#,
+            ArrayMatch::Index,
+        );
+        assert_eq!(result.count(ChangeKind::Changed), 4);
+        assert!(result.patch.iter().all(|op| op["op"] == "replace"));
+        check("[1, 2]", r#"{"a": 1}"#, ArrayMatch::Index);
+        check("1", "\"1\"", ArrayMatch::Index);
+    }
+
+    #[test]
+    fn escaped_keys() {
+        let result = check(
+            r#"{"a/b": 1, "m~n": {"~/": 1}, "": 1}"#,
+            r#"{"a/b": 2, "m~n": {"~/": 2}, "": 2, "~1": 3}"#,
+            ArrayMatch::Index,
+        );
+        let pointers: Vec<_> = result
+            .changes
+            .iter()
+            .map(|change| change.right_pointer.as_deref().unwrap())
+            .collect();
+        assert_eq!(pointers, ["/a~1b", "/m~0n/~0~1", "/", "/~01"]);
+        assert_eq!(result.changes[0].path, "$['a/b']");
+    }
+
+    #[test]
+    fn index_matching() {
+        let result = check("[1, 2, 3, 4]", "[1, 5]", ArrayMatch::Index);
+        assert_eq!(
+            kinds(&result),
+            [
+                (ChangeKind::Removed, "$[3]"),
+                (ChangeKind::Removed, "$[2]"),
+                (ChangeKind::Changed, "$[1]"),
+            ]
+        );
+        let result = check("[1]", "[2, 3, [4]]", ArrayMatch::Index);
+        assert_eq!(result.count(ChangeKind::Added), 2);
+        check("[]", "[[], {}]", ArrayMatch::Index);
+        // Without usable keys, keyed matching pairs by index
+        check(
+            r#"[{"id": 1}, {"id": 1}]"#,
+            r#"[{"id": 2}]"#,
+            ArrayMatch::Key("id".to_string()),
+        );
+    }
+
+    #[test]
+    fn key_matching() {
+        let id = || ArrayMatch::Key("id".to_string());
+        let left = r#"[{"id": 1, "v": "a"}, {"id": 2, "v": "b"}, {"id": 3, "v": "c"}]"#;
+        let right = r#"[{"id": 3, "v": "c"}, {"id": 1, "v": "z"}, {"id": 4}]"#;
+        let result = check(left, right, id());
+        assert_eq!(
+            kinds(&result),
+            [
+                (ChangeKind::Removed, "$[1]"),
+                (ChangeKind::Moved, "$[0]"),
+                (ChangeKind::Added, "$[2]"),
+                (ChangeKind::Changed, "$[1].v"),
+            ]
+        );
+        let moved = &result.changes[1];
+        assert_eq!(moved.left_pointer.as_deref(), Some("/2"));
+        assert_eq!(moved.right_pointer.as_deref(), Some("/0"));
+        // The same documents matched by index only change values
+        let by_index = check(left, right, ArrayMatch::Index);
+        assert_eq!(by_index.count(ChangeKind::Moved), 0);
+
+        let reversed = check(
+            r#"{"list": [{"id": 1}, {"id": 2}, {"id": 3}, {"id": 4}]}"#,
+            r#"{"list": [{"id": 4}, {"id": 3}, {"id": 2}, {"id": 1.0}]}"#,
+            id(),
+        );
+        assert_eq!(reversed.count(ChangeKind::Moved), 3);
+        assert_eq!(reversed.changes.len(), 3);
+    }
+
+    #[test]
+    fn number_equality() {
+        assert!(same(&json!(1), &serde_json::from_str("1.000").unwrap()));
+        assert!(same(
+            &serde_json::from_str("0.0").unwrap(),
+            &serde_json::from_str("-0e5").unwrap()
+        ));
+        assert!(same(
+            &serde_json::from_str("12345678901234567890123").unwrap(),
+            &serde_json::from_str("1.2345678901234567890123e22").unwrap()
+        ));
+        assert!(!same(&json!(1), &json!(10)));
+        assert!(!same(&json!(0.1), &json!(0.01)));
+        assert!(!same(&json!(1), &json!(-1)));
+    }
+}
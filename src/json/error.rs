@@ -0,0 +1,289 @@
+//! Pinpointing JSON syntax errors and suggesting fixes for common mistakes.
+
+use serde_json::error::Category;
+
+/// A failed parse, with its position when one is known
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub location: Option<ErrorLocation>,
+}
+
+impl ParseError {
+    /// Wraps a serde error raised while parsing `input` directly. serde
+    /// counts columns in bytes, so the message gives the character column
+    /// from [`ErrorLocation`] instead.
+    pub fn from_serde(input: &str, error: &serde_json::Error) -> Self {
+        let message = strip_position(error);
+        let location = ErrorLocation::find(input, error);
+        ParseError {
+            message: match &location {
+                Some(at) => format!(
+                    "JSON Parse Error: {} at line {} column {}",
+                    message, at.line, at.column
+                ),
+                None => format!("JSON Parse Error: {}", message),
+            },
+            location,
+        }
+    }
+}
+
+/// Where a parse failed, with the offending line for display
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorLocation {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, counted in characters
+    pub column: usize,
+    /// UTF-16 offset of the error within the whole input, as used by textareas
+    pub offset: usize,
+    /// Text of the offending line
+    pub excerpt: String,
+    /// Likely fixes, most specific first
+    pub suggestions: Vec<String>,
+}
+
+impl ErrorLocation {
+    /// Locates `error` within `input`; `None` when serde reports no position
+    pub fn find(input: &str, error: &serde_json::Error) -> Option<Self> {
+        let byte = error_byte_offset(input, error)?;
+        let eof = error.classify() == Category::Eof;
+        // serde points at the last byte it read; what is missing goes after it
+        let byte = if eof { input.trim_end().len() } else { byte };
+        Some(Self::at(input, byte, &error.to_string(), eof, true))
+    }
+
+    /// Describes the error at byte offset `byte` of `input`; `strict` inputs
+    /// may be pointed at the lenient dialects in the suggestions
+    pub fn at(input: &str, byte: usize, message: &str, eof: bool, strict: bool) -> Self {
+        let mut byte = byte.min(input.len());
+        while !input.is_char_boundary(byte) {
+            byte -= 1;
+        }
+
+        let line_start = input[..byte].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[byte..].find('\n').map_or(input.len(), |i| byte + i);
+        let excerpt = input[line_start..line_end].trim_end_matches('\r');
+        let at = input[byte..].chars().next();
+
+        ErrorLocation {
+            line: input[..byte].matches('\n').count() + 1,
+            column: input[line_start..byte].chars().count() + 1,
+            offset: input[..byte].encode_utf16().count(),
+            excerpt: excerpt.to_string(),
+            suggestions: suggest_fixes(input, message, eof, strict, at),
+        }
+    }
+
+    /// Spaces followed by a caret under the error column
+    pub fn caret(&self) -> String {
+        let padding: String = self
+            .excerpt
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        format!("{}^", padding)
+    }
+}
+
+/// Byte offset in `text` that a serde error points at
+pub fn error_byte_offset(text: &str, error: &serde_json::Error) -> Option<usize> {
+    if error.line() == 0 {
+        return None;
+    }
+    let line_start: usize = text
+        .split('\n')
+        .take(error.line() - 1)
+        .map(|line| line.len() + 1)
+        .sum();
+    // serde counts columns in bytes and points at the offending byte
+    Some(line_start + error.column().saturating_sub(1))
+}
+
+/// The serde error message without its "at line X column Y" suffix
+pub fn strip_position(error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    let suffix = format!(" at line {} column {}", error.line(), error.column());
+    message
+        .strip_suffix(&suffix)
+        .map_or(message.clone(), str::to_string)
+}
+
+fn suggest_fixes(
+    input: &str,
+    message: &str,
+    eof: bool,
+    strict: bool,
+    at: Option<char>,
+) -> Vec<String> {
+    let mut suggestions = Vec::new();
+    let or_read_as = |fix: &str, dialect: &str| {
+        if strict {
+            format!("{}, or read the input as {}", fix, dialect)
+        } else {
+            fix.to_string()
+        }
+    };
+
+    if input.starts_with('\u{feff}') {
+        suggestions
+            .push("Remove the byte order mark (U+FEFF) at the start of the input".to_string());
+    }
+    if message.starts_with("trailing comma") {
+        suggestions.push(or_read_as(
+            "Remove the trailing comma before the closing bracket",
+            "JSONC",
+        ));
+    }
+    match at {
+        Some('\'') => suggestions.push(or_read_as(
+            "Use double quotes instead of single quotes",
+            "JSON5",
+        )),
+        Some('/') if strict => {
+            suggestions.push("Remove comments, or read the input as JSONC or JSON5".to_string())
+        }
+        Some('#') => suggestions.push("Remove `#` comments; JSON does not allow them".to_string()),
+        Some(c)
+            if message.starts_with("key must be a string")
+                && (c.is_alphabetic() || c == '_' || c == '$') =>
+        {
+            suggestions.push(or_read_as("Wrap object keys in double quotes", "JSON5"))
+        }
+        _ => {}
+    }
+    if message.starts_with("expected `,`") {
+        suggestions.push("Add a comma between the previous value and this one".to_string());
+    }
+    if eof {
+        suggestions.push(
+            "The input ends early; check for an unclosed bracket, brace, string or comment"
+                .to_string(),
+        );
+    }
+
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn parse_error(input: &str) -> ParseError {
+        let error = serde_json::from_str::<Value>(input).unwrap_err();
+        ParseError::from_serde(input, &error)
+    }
+
+    fn location(input: &str) -> ErrorLocation {
+        parse_error(input).location.unwrap()
+    }
+
+    #[test]
+    fn multi_byte_characters() {
+        // Columns count characters, offsets count UTF-16 units
+        let at = location("{\"é\": x}");
+        assert_eq!((at.line, at.column, at.offset), (1, 7, 6));
+        let at = location("[\"😀\", x]");
+        assert_eq!((at.line, at.column, at.offset), (1, 7, 7));
+        assert_eq!(at.caret(), "      ^");
+        assert_eq!(
+            parse_error("[\"😀\", x]").message,
+            "JSON Parse Error: expected value at line 1 column 7"
+        );
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        let at = location("{\r\n  \"a\": 1,\r\n}");
+        assert_eq!((at.line, at.column, at.offset), (3, 1, 14));
+        assert_eq!(at.excerpt, "}");
+
+        let at = location("[\r\n  1\r\n  x\r\n]");
+        assert_eq!((at.line, at.column, at.offset), (3, 3, 10));
+        assert_eq!(at.excerpt, "  x");
+    }
+
+    #[test]
+    fn end_of_input() {
+        let error = parse_error("{\"a\": [1, 2\n\n");
+        assert_eq!(
+            error.message,
+            "JSON Parse Error: EOF while parsing a list at line 1 column 12"
+        );
+        let at = error.location.unwrap();
+        assert_eq!(at.offset, 11);
+        assert_eq!(at.caret(), "           ^");
+        assert_eq!(
+            at.suggestions,
+            ["The input ends early; check for an unclosed bracket, brace, string or comment"]
+        );
+
+        let at = location("");
+        assert_eq!((at.line, at.column, at.offset), (1, 1, 0));
+    }
+
+    #[test]
+    fn tabs_in_caret() {
+        let at = location("\t[1,\tx]");
+        assert_eq!(at.column, 6);
+        assert_eq!(at.caret(), "\t   \t^");
+    }
+
+    #[test]
+    fn suggestions() {
+        let suggest = |input: &str| location(input).suggestions;
+        assert_eq!(
+            suggest("{'a': 1}"),
+            ["Use double quotes instead of single quotes, or read the input as JSON5"]
+        );
+        assert_eq!(
+            suggest("{// note\n}"),
+            ["Remove comments, or read the input as JSONC or JSON5"]
+        );
+        assert_eq!(
+            suggest("{a: 1}"),
+            ["Wrap object keys in double quotes, or read the input as JSON5"]
+        );
+        assert_eq!(
+            suggest("[1, 2,]"),
+            ["Remove the trailing comma before the closing bracket, or read the input as JSONC"]
+        );
+        assert_eq!(
+            suggest("[1 2]"),
+            ["Add a comma between the previous value and this one"]
+        );
+        assert_eq!(
+            suggest("{\"a\": 1 # note\n}"),
+            [
+                "Remove `#` comments; JSON does not allow them",
+                "Add a comma between the previous value and this one",
+            ]
+        );
+        assert_eq!(
+            suggest("\u{feff}{}"),
+            ["Remove the byte order mark (U+FEFF) at the start of the input"]
+        );
+        assert!(suggest("[1, x]").is_empty());
+    }
+
+    #[test]
+    fn lenient_suggestions() {
+        // Input already read leniently is not pointed at the other dialects
+        let at = ErrorLocation::at("{'a' 1}", 5, "expected `:`", false, false);
+        assert_eq!((at.line, at.column), (1, 6));
+        let at = ErrorLocation::at("{'a': 1,}", 1, "trailing comma", false, false);
+        assert_eq!(
+            at.suggestions,
+            [
+                "Remove the trailing comma before the closing bracket",
+                "Use double quotes instead of single quotes",
+            ]
+        );
+        assert!(ErrorLocation::at("{/", 1, "", false, false)
+            .suggestions
+            .is_empty());
+    }
+}
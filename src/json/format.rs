@@ -0,0 +1,267 @@
+//! Serializing JSON with configurable layout, key order and escaping.
+
+use serde::Serialize;
+use serde_json::ser::{PrettyFormatter, Serializer};
+use serde_json::{Map, Number, Value};
+
+/// Overall output layout
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FormatMode {
+    #[default]
+    Pretty,
+    Minify,
+    /// Pretty-printed with object keys sorted recursively
+    SortKeys,
+}
+
+impl FormatMode {
+    pub const ALL: [FormatMode; 3] = [FormatMode::Pretty, FormatMode::Minify, FormatMode::SortKeys];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            FormatMode::Pretty => "pretty",
+            FormatMode::Minify => "minify",
+            FormatMode::SortKeys => "sort",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FormatMode::Pretty => "Pretty",
+            FormatMode::Minify => "Minify",
+            FormatMode::SortKeys => "Sort Keys",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.id() == id)
+    }
+}
+
+/// Indentation used by the pretty layouts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Indent {
+    #[default]
+    Two,
+    Four,
+    Tab,
+}
+
+impl Indent {
+    pub const ALL: [Indent; 3] = [Indent::Two, Indent::Four, Indent::Tab];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Indent::Two => "2",
+            Indent::Four => "4",
+            Indent::Tab => "tab",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Indent::Two => "2 Spaces",
+            Indent::Four => "4 Spaces",
+            Indent::Tab => "Tab",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|indent| indent.id() == id)
+    }
+
+    fn as_bytes(self) -> &'static [u8] {
+        match self {
+            Indent::Two => b"  ",
+            Indent::Four => b"    ",
+            Indent::Tab => b"\t",
+        }
+    }
+}
+
+/// How [`format_value`] lays out its output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    pub mode: FormatMode,
+    pub indent: Indent,
+    /// Write non-ASCII characters as `\uXXXX` escapes
+    pub escape_unicode: bool,
+    /// Keep numbers exactly as written instead of round-tripping through f64
+    pub exact_numbers: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            mode: FormatMode::Pretty,
+            indent: Indent::Two,
+            escape_unicode: false,
+            exact_numbers: true,
+        }
+    }
+}
+
+/// Serializes `value` according to `options`
+pub fn format_value(value: Value, options: &FormatOptions) -> Result<String, String> {
+    let mut value = value;
+    if !options.exact_numbers {
+        value = lossy_numbers(value);
+    }
+    if options.mode == FormatMode::SortKeys {
+        value = sort_keys(value);
+    }
+
+    let text = match options.mode {
+        FormatMode::Minify => serde_json::to_string(&value).map_err(|e| e.to_string())?,
+        FormatMode::Pretty | FormatMode::SortKeys => {
+            let mut buffer = Vec::new();
+            let formatter = PrettyFormatter::with_indent(options.indent.as_bytes());
+            let mut serializer = Serializer::with_formatter(&mut buffer, formatter);
+            value
+                .serialize(&mut serializer)
+                .map_err(|e| e.to_string())?;
+            String::from_utf8(buffer).map_err(|e| e.to_string())?
+        }
+    };
+
+    Ok(if options.escape_unicode {
+        escape_non_ascii(&text)
+    } else {
+        text
+    })
+}
+
+/// Recursively orders object keys
+pub fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, child)| (key, sort_keys(child)))
+                    .collect::<Map<String, Value>>(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+/// Converts every number that is not an exact 64-bit integer through f64
+fn lossy_numbers(value: Value) -> Value {
+    match value {
+        // Values outside the f64 range are left as written
+        Value::Number(n) if !n.is_i64() && !n.is_u64() => {
+            Value::Number(n.as_f64().and_then(Number::from_f64).unwrap_or(n))
+        }
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, child)| (key, lossy_numbers(child)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(lossy_numbers).collect()),
+        other => other,
+    }
+}
+
+/// Escapes every non-ASCII character as `\uXXXX`, using surrogate pairs where needed.
+///
+/// Serialized JSON only contains non-ASCII inside strings, so this is safe to
+/// apply to the whole document.
+fn escape_non_ascii(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii() {
+            escaped.push(c);
+        } else {
+            let mut units = [0u16; 2];
+            for unit in c.encode_utf16(&mut units) {
+                escaped.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(input: &str, options: FormatOptions) -> String {
+        format_value(serde_json::from_str(input).unwrap(), &options).unwrap()
+    }
+
+    fn with_mode(mode: FormatMode) -> FormatOptions {
+        FormatOptions {
+            mode,
+            ..FormatOptions::default()
+        }
+    }
+
+    #[test]
+    fn key_order() {
+        let input = r#"{"b": 1, "a": {"d": [{"z": 1, "y": 2}], "c": 3}}"#;
+        assert_eq!(
+            format(input, with_mode(FormatMode::Minify)),
+            r#"{"b":1,"a":{"d":[{"z":1,"y":2}],"c":3}}"#
+        );
+        assert_eq!(
+            format(input, with_mode(FormatMode::SortKeys)),
+            "{\n  \"a\": {\n    \"c\": 3,\n    \"d\": [\n      {\n        \"y\": 2,\n        \"z\": 1\n      }\n    ]\n  },\n  \"b\": 1\n}"
+        );
+    }
+
+    #[test]
+    fn indentation() {
+        let indented = |indent: Indent| {
+            format(
+                r#"{"a": [1]}"#,
+                FormatOptions {
+                    indent,
+                    ..FormatOptions::default()
+                },
+            )
+        };
+        assert_eq!(indented(Indent::Two), "{\n  \"a\": [\n    1\n  ]\n}");
+        assert_eq!(
+            indented(Indent::Four),
+            "{\n    \"a\": [\n        1\n    ]\n}"
+        );
+        assert_eq!(indented(Indent::Tab), "{\n\t\"a\": [\n\t\t1\n\t]\n}");
+        assert_eq!(format("[]", FormatOptions::default()), "[]");
+    }
+
+    #[test]
+    fn unicode_escapes() {
+        let options = FormatOptions {
+            mode: FormatMode::Minify,
+            escape_unicode: true,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format(r#"{"café": "😀 é \n"}"#, options),
+            r#"{"caf\u00e9":"\ud83d\ude00 \u00e9 \n"}"#
+        );
+        assert_eq!(format(r#""é""#, with_mode(FormatMode::Minify)), "\"é\"");
+    }
+
+    #[test]
+    fn numbers() {
+        let input = "[1.10, 1e2, -0.0, 1e400, 12345678901234567890123, 18446744073709551615]";
+        assert_eq!(
+            format(input, with_mode(FormatMode::Minify)),
+            "[1.10,1e2,-0.0,1e400,12345678901234567890123,18446744073709551615]"
+        );
+        let lossy = FormatOptions {
+            mode: FormatMode::Minify,
+            exact_numbers: false,
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            format(input, lossy),
+            "[1.1,100.0,-0.0,1e400,1.2345678901234568e22,18446744073709551615]"
+        );
+    }
+}
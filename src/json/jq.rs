@@ -0,0 +1,1559 @@
+//! A useful subset of jq: paths, iteration, pipes, construction, arithmetic,
+//! comparisons, `if`, variables and the common built-in functions.
+
+use super::diff::same;
+use super::query::{compare_values, syntax_error, truthy, type_name};
+use serde_json::{Map, Number, Value};
+use std::cmp::Ordering;
+
+/// Upper bound on values produced by `range`, to keep typos from hanging the page
+const MAX_RANGE: i64 = 100_000;
+
+/// Evaluates a jq program against `document`
+pub fn evaluate(query: &str, document: &Value) -> Result<Vec<Value>, String> {
+    let tokens = tokenize(query)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        end: query.chars().count(),
+    };
+    let program = parser.pipe()?;
+    if let Some((token, position)) = parser.tokens.get(parser.pos) {
+        return Err(syntax_error(
+            *position,
+            &format!("unexpected {}", token.describe()),
+        ));
+    }
+    eval(&program, document, &Vec::new())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot,
+    DotDot,
+    Field(String),
+    Ident(String),
+    Var(String),
+    Str(String),
+    Num(Value),
+    Op(&'static str),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Dot => "'.'".to_string(),
+            Token::DotDot => "'..'".to_string(),
+            Token::Field(name) => format!("'.{}'", name),
+            Token::Ident(name) => format!("'{}'", name),
+            Token::Var(name) => format!("'${}'", name),
+            Token::Str(_) => "string".to_string(),
+            Token::Num(n) => format!("'{}'", n),
+            Token::Op(op) => format!("'{}'", op),
+        }
+    }
+}
+
+const OPERATORS: &[&str] = &[
+    "//", "==", "!=", "<=", ">=", "|", ",", "<", ">", "+", "-", "*", "/", "%", "(", ")", "[", "]",
+    "{", "}", ":", ";", "?",
+];
+
+fn is_ident_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn tokenize(query: &str) -> Result<Vec<(Token, usize)>, String> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let ident = |start: usize| {
+        let mut end = start;
+        while end < chars.len() && is_ident_char(chars[end]) {
+            end += 1;
+        }
+        (chars[start..end].iter().collect::<String>(), end)
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '#' {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '.' {
+            if chars.get(i + 1) == Some(&'.') {
+                tokens.push((Token::DotDot, start));
+                i += 2;
+            } else if chars.get(i + 1).is_some_and(|&c| is_ident_start(c)) {
+                let (name, end) = ident(i + 1);
+                tokens.push((Token::Field(name), start));
+                i = end;
+            } else if chars.get(i + 1).is_some_and(char::is_ascii_digit) {
+                i = number(&chars, i, &mut tokens)?;
+            } else {
+                tokens.push((Token::Dot, start));
+                i += 1;
+            }
+        } else if c == '$' {
+            if !chars.get(i + 1).is_some_and(|&c| is_ident_start(c)) {
+                return Err(syntax_error(start, "expected a variable name after '$'"));
+            }
+            let (name, end) = ident(i + 1);
+            tokens.push((Token::Var(name), start));
+            i = end;
+        } else if is_ident_start(c) {
+            let (name, end) = ident(i);
+            tokens.push((Token::Ident(name), start));
+            i = end;
+        } else if c.is_ascii_digit() {
+            i = number(&chars, i, &mut tokens)?;
+        } else if c == '"' {
+            let (text, end) = string(&chars, i)?;
+            tokens.push((Token::Str(text), start));
+            i = end;
+        } else if let Some(op) = OPERATORS.iter().find(|op| {
+            op.chars()
+                .enumerate()
+                .all(|(offset, c)| chars.get(i + offset) == Some(&c))
+        }) {
+            tokens.push((Token::Op(op), start));
+            i += op.len();
+        } else {
+            return Err(syntax_error(start, &format!("unexpected '{}'", c)));
+        }
+    }
+    Ok(tokens)
+}
+
+fn number(chars: &[char], start: usize, tokens: &mut Vec<(Token, usize)>) -> Result<usize, String> {
+    let mut end = start;
+    while end < chars.len() && (chars[end].is_ascii_digit() || chars[end] == '.') {
+        end += 1;
+    }
+    if end < chars.len() && matches!(chars[end], 'e' | 'E') {
+        end += 1;
+        if end < chars.len() && matches!(chars[end], '+' | '-') {
+            end += 1;
+        }
+        while end < chars.len() && chars[end].is_ascii_digit() {
+            end += 1;
+        }
+    }
+    let text: String = chars[start..end].iter().collect();
+    let normalized = if text.starts_with('.') {
+        format!("0{}", text)
+    } else {
+        text
+    };
+    let value = serde_json::from_str::<Value>(&normalized)
+        .ok()
+        .filter(Value::is_number)
+        .ok_or_else(|| syntax_error(start, "invalid number"))?;
+    tokens.push((Token::Num(value), start));
+    Ok(end)
+}
+
+fn string(chars: &[char], start: usize) -> Result<(String, usize), String> {
+    // Find the closing quote, then let serde decode the escapes
+    let mut end = start + 1;
+    while end < chars.len() && chars[end] != '"' {
+        if chars[end] == '\\' {
+            end += 1;
+        }
+        end += 1;
+    }
+    if end >= chars.len() {
+        return Err(syntax_error(start, "unterminated string"));
+    }
+    let literal: String = chars[start..=end].iter().collect();
+    if literal.contains("\\(") {
+        return Err(syntax_error(start, "string interpolation is not supported"));
+    }
+    serde_json::from_str::<String>(&literal)
+        .map(|text| (text, end + 1))
+        .map_err(|_| syntax_error(start, "invalid string escape"))
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Identity,
+    RecurseAll,
+    Literal(Value),
+    Var(String),
+    Index(Box<Expr>, Box<Expr>),
+    Slice(Box<Expr>, Option<Box<Expr>>, Option<Box<Expr>>),
+    Iterate(Box<Expr>),
+    /// `expr?`: errors produce no output
+    Try(Box<Expr>),
+    Pipe(Box<Expr>, Box<Expr>),
+    Comma(Box<Expr>, Box<Expr>),
+    Alternative(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+    Negate(Box<Expr>),
+    Array(Option<Box<Expr>>),
+    Object(Vec<(Expr, Expr)>),
+    If(Box<Expr>, Box<Expr>, Option<Box<Expr>>),
+    Bind(Box<Expr>, String, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    /// Character length of the query, for errors at the end
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |(_, p)| *p)
+    }
+
+    fn error(&self, message: &str) -> String {
+        syntax_error(self.position(), message)
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Op(o)) if *o == op) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(name)) if name == keyword) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_op(&mut self, op: &str) -> Result<(), String> {
+        if self.eat_op(op) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", op)))
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), String> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", keyword)))
+        }
+    }
+
+    fn pipe(&mut self) -> Result<Expr, String> {
+        let left = self.comma()?;
+        if self.eat_keyword("as") {
+            let name = match self.peek() {
+                Some(Token::Var(name)) => name.clone(),
+                _ => return Err(self.error("expected a variable after 'as'")),
+            };
+            self.pos += 1;
+            self.expect_op("|")?;
+            let body = self.pipe()?;
+            return Ok(Expr::Bind(Box::new(left), name, Box::new(body)));
+        }
+        if self.eat_op("|") {
+            return Ok(Expr::Pipe(Box::new(left), Box::new(self.pipe()?)));
+        }
+        Ok(left)
+    }
+
+    fn comma(&mut self) -> Result<Expr, String> {
+        let mut left = self.alternative()?;
+        while self.eat_op(",") {
+            left = Expr::Comma(Box::new(left), Box::new(self.alternative()?));
+        }
+        Ok(left)
+    }
+
+    fn alternative(&mut self) -> Result<Expr, String> {
+        let left = self.or()?;
+        if self.eat_op("//") {
+            return Ok(Expr::Alternative(
+                Box::new(left),
+                Box::new(self.alternative()?),
+            ));
+        }
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.eat_keyword("or") {
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.comparison()?;
+        while self.eat_keyword("and") {
+            left = Expr::And(Box::new(left), Box::new(self.comparison()?));
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let left = self.additive()?;
+        for op in ["==", "!=", "<=", ">=", "<", ">"] {
+            if self.eat_op(op) {
+                return Ok(Expr::Binary(op, Box::new(left), Box::new(self.additive()?)));
+            }
+        }
+        Ok(left)
+    }
+
+    fn additive(&mut self) -> Result<Expr, String> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let Some(op) = ["+", "-"].into_iter().find(|op| self.eat_op(op)) else {
+                return Ok(left);
+            };
+            left = Expr::Binary(op, Box::new(left), Box::new(self.multiplicative()?));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        loop {
+            let Some(op) = ["*", "/", "%"].into_iter().find(|op| self.eat_op(op)) else {
+                return Ok(left);
+            };
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat_op("-") {
+            return Ok(Expr::Negate(Box::new(self.postfix()?)));
+        }
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> Result<Expr, String> {
+        let mut expr = self.primary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Field(name)) => {
+                    let key = Expr::Literal(Value::String(name.clone()));
+                    self.pos += 1;
+                    expr = Expr::Index(Box::new(expr), Box::new(key));
+                }
+                Some(Token::Dot) => match self.tokens.get(self.pos + 1).map(|(token, _)| token) {
+                    Some(Token::Str(key)) => {
+                        let key = Expr::Literal(Value::String(key.clone()));
+                        self.pos += 2;
+                        expr = Expr::Index(Box::new(expr), Box::new(key));
+                    }
+                    Some(Token::Op("[")) => self.pos += 1,
+                    _ => return Err(self.error("expected a field name after '.'")),
+                },
+                Some(Token::Op("[")) => {
+                    self.pos += 1;
+                    expr = self.bracket(expr)?;
+                }
+                Some(Token::Op("?")) => {
+                    self.pos += 1;
+                    expr = Expr::Try(Box::new(expr));
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
+    /// Suffix after `[`: iteration, an index or a slice
+    fn bracket(&mut self, target: Expr) -> Result<Expr, String> {
+        let target = Box::new(target);
+        if self.eat_op("]") {
+            return Ok(Expr::Iterate(target));
+        }
+        let start = if matches!(self.peek(), Some(Token::Op(":"))) {
+            None
+        } else {
+            Some(Box::new(self.pipe()?))
+        };
+        if self.eat_op(":") {
+            let end = if matches!(self.peek(), Some(Token::Op("]"))) {
+                None
+            } else {
+                Some(Box::new(self.pipe()?))
+            };
+            self.expect_op("]")?;
+            return Ok(Expr::Slice(target, start, end));
+        }
+        self.expect_op("]")?;
+        match start {
+            Some(index) => Ok(Expr::Index(target, index)),
+            None => Err(self.error("expected an index")),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let Some(token) = self.peek().cloned() else {
+            return Err(self.error("unexpected end of query"));
+        };
+        self.pos += 1;
+        match token {
+            Token::Dot => match self.peek() {
+                Some(Token::Str(key)) => {
+                    let key = Expr::Literal(Value::String(key.clone()));
+                    self.pos += 1;
+                    Ok(Expr::Index(Box::new(Expr::Identity), Box::new(key)))
+                }
+                _ => Ok(Expr::Identity),
+            },
+            Token::DotDot => Ok(Expr::RecurseAll),
+            Token::Field(name) => Ok(Expr::Index(
+                Box::new(Expr::Identity),
+                Box::new(Expr::Literal(Value::String(name))),
+            )),
+            Token::Num(value) => Ok(Expr::Literal(value)),
+            Token::Str(text) => Ok(Expr::Literal(Value::String(text))),
+            Token::Var(name) => Ok(Expr::Var(name)),
+            Token::Op("(") => {
+                let inner = self.pipe()?;
+                self.expect_op(")")?;
+                Ok(inner)
+            }
+            Token::Op("[") => {
+                if self.eat_op("]") {
+                    return Ok(Expr::Array(None));
+                }
+                let inner = self.pipe()?;
+                self.expect_op("]")?;
+                Ok(Expr::Array(Some(Box::new(inner))))
+            }
+            Token::Op("{") => self.object(),
+            Token::Ident(name) => match name.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                "if" => self.conditional(),
+                "then" | "elif" | "else" | "end" | "as" | "and" | "or" => {
+                    self.pos -= 1;
+                    Err(self.error(&format!("unexpected '{}'", name)))
+                }
+                "reduce" | "foreach" | "def" | "try" | "catch" | "label" | "import" | "include" => {
+                    self.pos -= 1;
+                    Err(self.error(&format!("'{}' is not supported", name)))
+                }
+                _ => {
+                    let mut args = Vec::new();
+                    if self.eat_op("(") {
+                        loop {
+                            args.push(self.pipe()?);
+                            if !self.eat_op(";") {
+                                break;
+                            }
+                        }
+                        self.expect_op(")")?;
+                    }
+                    Ok(Expr::Call(name, args))
+                }
+            },
+            other => {
+                self.pos -= 1;
+                Err(self.error(&format!("unexpected {}", other.describe())))
+            }
+        }
+    }
+
+    /// After `if`: condition, branches and the closing `end`
+    fn conditional(&mut self) -> Result<Expr, String> {
+        let condition = self.pipe()?;
+        self.expect_keyword("then")?;
+        let then = self.pipe()?;
+        let otherwise = if self.eat_keyword("elif") {
+            Some(Box::new(self.conditional()?))
+        } else if self.eat_keyword("else") {
+            let otherwise = self.pipe()?;
+            self.expect_keyword("end")?;
+            Some(Box::new(otherwise))
+        } else {
+            self.expect_keyword("end")?;
+            None
+        };
+        Ok(Expr::If(Box::new(condition), Box::new(then), otherwise))
+    }
+
+    /// After `{`: `key`, `key: value`, `"key": value`, `$var` or `(expr): value`
+    fn object(&mut self) -> Result<Expr, String> {
+        let mut entries = Vec::new();
+        if self.eat_op("}") {
+            return Ok(Expr::Object(entries));
+        }
+        loop {
+            let Some(token) = self.peek().cloned() else {
+                return Err(self.error("unterminated object"));
+            };
+            self.pos += 1;
+            let (key, shorthand) = match token {
+                Token::Ident(name) | Token::Str(name) => {
+                    let shorthand = Expr::Index(
+                        Box::new(Expr::Identity),
+                        Box::new(Expr::Literal(Value::String(name.clone()))),
+                    );
+                    (Expr::Literal(Value::String(name)), Some(shorthand))
+                }
+                Token::Var(name) => (
+                    Expr::Literal(Value::String(name.clone())),
+                    Some(Expr::Var(name)),
+                ),
+                Token::Op("(") => {
+                    let key = self.pipe()?;
+                    self.expect_op(")")?;
+                    (key, None)
+                }
+                other => {
+                    self.pos -= 1;
+                    return Err(self.error(&format!("unexpected {} in object", other.describe())));
+                }
+            };
+            let value = if self.eat_op(":") {
+                self.alternative()?
+            } else {
+                shorthand.ok_or_else(|| self.error("expected ':'"))?
+            };
+            entries.push((key, value));
+            if self.eat_op("}") {
+                return Ok(Expr::Object(entries));
+            }
+            self.expect_op(",")?;
+        }
+    }
+}
+
+type Env = Vec<(String, Value)>;
+
+fn eval(expr: &Expr, input: &Value, env: &Env) -> Result<Vec<Value>, String> {
+    match expr {
+        Expr::Identity => Ok(vec![input.clone()]),
+        Expr::RecurseAll => Ok(recurse(input)),
+        Expr::Literal(value) => Ok(vec![value.clone()]),
+        Expr::Var(name) => env
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| vec![value.clone()])
+            .ok_or_else(|| format!("${} is not defined", name)),
+        Expr::Index(target, key) => {
+            let mut out = Vec::new();
+            for key in eval(key, input, env)? {
+                for value in eval(target, input, env)? {
+                    out.push(index(&value, &key)?);
+                }
+            }
+            Ok(out)
+        }
+        Expr::Slice(target, start, end) => {
+            let bound = |bound: &Option<Box<Expr>>| -> Result<Vec<Value>, String> {
+                match bound {
+                    Some(expr) => eval(expr, input, env),
+                    None => Ok(vec![Value::Null]),
+                }
+            };
+            let mut out = Vec::new();
+            for end in bound(end)? {
+                for start in bound(start)? {
+                    for value in eval(target, input, env)? {
+                        out.push(slice(&value, &start, &end)?);
+                    }
+                }
+            }
+            Ok(out)
+        }
+        Expr::Iterate(target) => {
+            let mut out = Vec::new();
+            for value in eval(target, input, env)? {
+                match value {
+                    Value::Array(items) => out.extend(items),
+                    Value::Object(map) => out.extend(map.into_iter().map(|(_, v)| v)),
+                    other => return Err(format!("Cannot iterate over {}", describe(&other))),
+                }
+            }
+            Ok(out)
+        }
+        Expr::Try(inner) => Ok(eval(inner, input, env).unwrap_or_default()),
+        Expr::Pipe(left, right) => {
+            let mut out = Vec::new();
+            for value in eval(left, input, env)? {
+                out.extend(eval(right, &value, env)?);
+            }
+            Ok(out)
+        }
+        Expr::Comma(left, right) => {
+            let mut out = eval(left, input, env)?;
+            out.extend(eval(right, input, env)?);
+            Ok(out)
+        }
+        Expr::Alternative(left, right) => {
+            let found: Vec<Value> = eval(left, input, env)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(truthy)
+                .collect();
+            if found.is_empty() {
+                eval(right, input, env)
+            } else {
+                Ok(found)
+            }
+        }
+        Expr::And(left, right) | Expr::Or(left, right) => {
+            let is_and = matches!(expr, Expr::And(..));
+            let mut out = Vec::new();
+            for value in eval(left, input, env)? {
+                if truthy(&value) != is_and {
+                    out.push(Value::Bool(!is_and));
+                    continue;
+                }
+                for value in eval(right, input, env)? {
+                    out.push(Value::Bool(truthy(&value)));
+                }
+            }
+            Ok(out)
+        }
+        Expr::Binary(op, left, right) => {
+            let mut out = Vec::new();
+            for r in eval(right, input, env)? {
+                for l in eval(left, input, env)? {
+                    out.push(binary(op, &l, &r)?);
+                }
+            }
+            Ok(out)
+        }
+        Expr::Negate(inner) => eval(inner, input, env)?
+            .iter()
+            .map(|value| binary("-", &Value::from(0), value))
+            .collect(),
+        Expr::Array(inner) => Ok(vec![Value::Array(match inner {
+            Some(inner) => eval(inner, input, env)?,
+            None => Vec::new(),
+        })]),
+        Expr::Object(entries) => {
+            let mut objects = vec![Map::new()];
+            for (key, value) in entries {
+                let mut next = Vec::new();
+                for key in eval(key, input, env)? {
+                    let Value::String(key) = key else {
+                        return Err(format!(
+                            "Object keys must be strings, not {}",
+                            type_name(&key)
+                        ));
+                    };
+                    for value in eval(value, input, env)? {
+                        for object in &objects {
+                            let mut object = object.clone();
+                            object.insert(key.clone(), value.clone());
+                            next.push(object);
+                        }
+                    }
+                }
+                objects = next;
+            }
+            Ok(objects.into_iter().map(Value::Object).collect())
+        }
+        Expr::If(condition, then, otherwise) => {
+            let mut out = Vec::new();
+            for value in eval(condition, input, env)? {
+                if truthy(&value) {
+                    out.extend(eval(then, input, env)?);
+                } else if let Some(otherwise) = otherwise {
+                    out.extend(eval(otherwise, input, env)?);
+                } else {
+                    out.push(input.clone());
+                }
+            }
+            Ok(out)
+        }
+        Expr::Bind(source, name, body) => {
+            let mut out = Vec::new();
+            for value in eval(source, input, env)? {
+                let mut scope = env.clone();
+                scope.push((name.clone(), value));
+                out.extend(eval(body, input, &scope)?);
+            }
+            Ok(out)
+        }
+        Expr::Call(name, args) => call(name, args, input, env),
+    }
+}
+
+/// `value` followed by everything below it, in document order
+fn recurse(value: &Value) -> Vec<Value> {
+    let mut out = Vec::new();
+    let mut stack = vec![value];
+    while let Some(node) = stack.pop() {
+        out.push(node.clone());
+        match node {
+            Value::Array(items) => stack.extend(items.iter().rev()),
+            Value::Object(map) => stack.extend(map.values().rev()),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Short description of a value for error messages, as jq prints them
+fn describe(value: &Value) -> String {
+    let text = value.to_string();
+    let text = if text.chars().count() > 30 {
+        format!("{}...", text.chars().take(27).collect::<String>())
+    } else {
+        text
+    };
+    format!("{} ({})", type_name(value), text)
+}
+
+fn index(value: &Value, key: &Value) -> Result<Value, String> {
+    match (value, key) {
+        (Value::Null, Value::String(_) | Value::Number(_)) => Ok(Value::Null),
+        (Value::Object(map), Value::String(key)) => {
+            Ok(map.get(key).cloned().unwrap_or(Value::Null))
+        }
+        (Value::Array(items), Value::Number(n)) => {
+            let Some(i) = n.as_f64().map(|f| f.floor() as i64) else {
+                return Ok(Value::Null);
+            };
+            let i = if i < 0 { items.len() as i64 + i } else { i };
+            Ok(usize::try_from(i)
+                .ok()
+                .and_then(|i| items.get(i))
+                .cloned()
+                .unwrap_or(Value::Null))
+        }
+        _ => Err(format!(
+            "Cannot index {} with {}",
+            type_name(value),
+            match key {
+                Value::String(s) => format!("\"{}\"", s),
+                other => type_name(other).to_string(),
+            }
+        )),
+    }
+}
+
+fn slice(value: &Value, start: &Value, end: &Value) -> Result<Value, String> {
+    let len = match value {
+        Value::Null => return Ok(Value::Null),
+        Value::Array(items) => items.len(),
+        Value::String(text) => text.chars().count(),
+        other => return Err(format!("Cannot slice {}", type_name(other))),
+    } as i64;
+    let bound = |bound: &Value, default: i64| -> Result<usize, String> {
+        let i = match bound {
+            Value::Null => default,
+            Value::Number(n) => n.as_f64().unwrap_or(0.0).floor() as i64,
+            other => {
+                return Err(format!(
+                    "Slice bounds must be numbers, not {}",
+                    type_name(other)
+                ))
+            }
+        };
+        let i = if i < 0 { len + i } else { i };
+        Ok(i.clamp(0, len) as usize)
+    };
+    let start = bound(start, 0)?;
+    let end = bound(end, len)?.max(start);
+    Ok(match value {
+        Value::Array(items) => Value::Array(items[start..end].to_vec()),
+        Value::String(text) => Value::String(text.chars().skip(start).take(end - start).collect()),
+        _ => Value::Null,
+    })
+}
+
+/// Builds a number, keeping integers integral
+fn number_value(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 9_007_199_254_740_992.0 {
+        Value::from(n as i64)
+    } else {
+        Number::from_f64(n).map_or(Value::Null, Value::Number)
+    }
+}
+
+fn as_f64(value: &Value) -> f64 {
+    value.as_f64().unwrap_or(f64::NAN)
+}
+
+fn binary(op: &str, left: &Value, right: &Value) -> Result<Value, String> {
+    let fail = || {
+        Err(format!(
+            "{} and {} cannot be {}",
+            describe(left),
+            describe(right),
+            match op {
+                "+" => "added",
+                "-" => "subtracted",
+                "*" => "multiplied",
+                "/" => "divided",
+                _ => "divided (remainder)",
+            }
+        ))
+    };
+
+    match op {
+        "==" => return Ok(Value::Bool(same(left, right))),
+        "!=" => return Ok(Value::Bool(!same(left, right))),
+        "<" | "<=" | ">" | ">=" => {
+            let order = compare_values(left, right);
+            return Ok(Value::Bool(match op {
+                "<" => order == Ordering::Less,
+                "<=" => order != Ordering::Greater,
+                ">" => order == Ordering::Greater,
+                _ => order != Ordering::Less,
+            }));
+        }
+        _ => {}
+    }
+
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => {
+            if let (Some(a), Some(b)) = (a.as_i64(), b.as_i64()) {
+                let exact = match op {
+                    "+" => a.checked_add(b),
+                    "-" => a.checked_sub(b),
+                    "*" => a.checked_mul(b),
+                    "/" if b != 0 && a % b == 0 => a.checked_div(b),
+                    _ => None,
+                };
+                if let Some(result) = exact {
+                    return Ok(Value::from(result));
+                }
+            }
+            let (a, b) = (as_f64(left), as_f64(right));
+            match op {
+                "+" => Ok(number_value(a + b)),
+                "-" => Ok(number_value(a - b)),
+                "*" => Ok(number_value(a * b)),
+                "/" if b == 0.0 => Err(format!(
+                    "{} and {} cannot be divided because the divisor is zero",
+                    describe(left),
+                    describe(right)
+                )),
+                "/" => Ok(number_value(a / b)),
+                _ => {
+                    let (a, b) = (a.trunc() as i64, b.trunc() as i64);
+                    if b == 0 {
+                        Err(format!(
+                            "{} and {} cannot be divided because the divisor is zero",
+                            describe(left),
+                            describe(right)
+                        ))
+                    } else {
+                        Ok(Value::from(a.wrapping_rem(b.abs())))
+                    }
+                }
+            }
+        }
+        (Value::Null, other) | (other, Value::Null) if op == "+" => Ok(other.clone()),
+        (Value::String(a), Value::String(b)) => match op {
+            "+" => Ok(Value::String(format!("{}{}", a, b))),
+            "/" => Ok(split(a, b)),
+            _ => fail(),
+        },
+        (Value::String(text), Value::Number(_)) | (Value::Number(_), Value::String(text))
+            if op == "*" =>
+        {
+            let times = as_f64(if left.is_number() { left } else { right });
+            if times <= 0.0 {
+                Ok(Value::Null)
+            } else {
+                Ok(Value::String(text.repeat(times.ceil() as usize)))
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => match op {
+            "+" => Ok(Value::Array(a.iter().chain(b).cloned().collect())),
+            "-" => Ok(Value::Array(
+                a.iter()
+                    .filter(|item| !b.iter().any(|other| same(item, other)))
+                    .cloned()
+                    .collect(),
+            )),
+            _ => fail(),
+        },
+        (Value::Object(a), Value::Object(b)) => match op {
+            "+" => {
+                let mut merged = a.clone();
+                merged.extend(b.clone());
+                Ok(Value::Object(merged))
+            }
+            "*" => Ok(deep_merge(left, right)),
+            _ => fail(),
+        },
+        _ => fail(),
+    }
+}
+
+fn deep_merge(left: &Value, right: &Value) -> Value {
+    match (left, right) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut merged = a.clone();
+            for (key, value) in b {
+                let value = match merged.get(key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => value.clone(),
+                };
+                merged.insert(key.clone(), value);
+            }
+            Value::Object(merged)
+        }
+        _ => right.clone(),
+    }
+}
+
+fn split(text: &str, separator: &str) -> Value {
+    if text.is_empty() {
+        return Value::Array(Vec::new());
+    }
+    let parts: Vec<Value> = if separator.is_empty() {
+        text.chars().map(|c| Value::String(c.to_string())).collect()
+    } else {
+        text.split(separator)
+            .map(|part| Value::String(part.to_string()))
+            .collect()
+    };
+    Value::Array(parts)
+}
+
+fn items<'a>(value: &'a Value, function: &str) -> Result<&'a Vec<Value>, String> {
+    match value {
+        Value::Array(items) => Ok(items),
+        other => Err(format!(
+            "{} cannot be used with {}",
+            function,
+            describe(other)
+        )),
+    }
+}
+
+fn string_arg<'a>(value: &'a Value, function: &str) -> Result<&'a str, String> {
+    value
+        .as_str()
+        .ok_or_else(|| format!("{} expects a string, not {}", function, describe(value)))
+}
+
+/// Pairs each element with the outputs of `f` on it, sorted by those outputs
+fn keyed(
+    value: &Value,
+    f: &Expr,
+    env: &Env,
+    function: &str,
+) -> Result<Vec<(Value, Value)>, String> {
+    let mut pairs = items(value, function)?
+        .iter()
+        .map(|item| Ok((Value::Array(eval(f, item, env)?), item.clone())))
+        .collect::<Result<Vec<_>, String>>()?;
+    pairs.sort_by(|a, b| compare_values(&a.0, &b.0));
+    Ok(pairs)
+}
+
+fn call(name: &str, args: &[Expr], input: &Value, env: &Env) -> Result<Vec<Value>, String> {
+    let one = |value: Value| Ok(vec![value]);
+    // Evaluates argument `i` against the input
+    let arg = |i: usize| eval(&args[i], input, env);
+
+    match (name, args.len()) {
+        ("empty", 0) => Ok(Vec::new()),
+        ("error", 0) => Err(match input {
+            Value::String(message) => message.clone(),
+            other => format!("{} (not a string)", other),
+        }),
+        ("error", 1) => Err(arg(0)?
+            .into_iter()
+            .next()
+            .map(|value| value.as_str().map_or(value.to_string(), str::to_string))
+            .unwrap_or_default()),
+        ("not", 0) => one(Value::Bool(!truthy(input))),
+        ("length", 0) => one(match input {
+            Value::Null => Value::from(0),
+            Value::Bool(_) => return Err("boolean has no length".to_string()),
+            Value::Number(_) => number_value(as_f64(input).abs()),
+            Value::String(text) => Value::from(text.chars().count()),
+            Value::Array(items) => Value::from(items.len()),
+            Value::Object(map) => Value::from(map.len()),
+        }),
+        ("utf8bytelength", 0) => one(Value::from(string_arg(input, name)?.len())),
+        ("keys", 0) | ("keys_unsorted", 0) => one(match input {
+            Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                if name == "keys" {
+                    keys.sort();
+                }
+                Value::Array(keys.into_iter().map(|k| Value::String(k.clone())).collect())
+            }
+            Value::Array(items) => Value::Array((0..items.len()).map(Value::from).collect()),
+            other => return Err(format!("{} has no keys", describe(other))),
+        }),
+        ("has", 1) => arg(0)?
+            .iter()
+            .map(|key| match (input, key) {
+                (Value::Object(map), Value::String(key)) => Ok(Value::Bool(map.contains_key(key))),
+                (Value::Array(items), Value::Number(n)) => Ok(Value::Bool(
+                    n.as_f64()
+                        .is_some_and(|i| i >= 0.0 && (i as usize) < items.len()),
+                )),
+                _ => Err(format!(
+                    "Cannot check whether {} has a {} key",
+                    type_name(input),
+                    type_name(key)
+                )),
+            })
+            .collect(),
+        ("type", 0) => one(Value::String(type_name(input).to_string())),
+        ("values", 0) => Ok(if input.is_null() {
+            vec![]
+        } else {
+            vec![input.clone()]
+        }),
+        (
+            "nulls" | "booleans" | "numbers" | "strings" | "arrays" | "objects" | "iterables"
+            | "scalars",
+            0,
+        ) => {
+            let keep = match name {
+                "nulls" => input.is_null(),
+                "booleans" => input.is_boolean(),
+                "numbers" => input.is_number(),
+                "strings" => input.is_string(),
+                "arrays" => input.is_array(),
+                "objects" => input.is_object(),
+                "iterables" => input.is_array() || input.is_object(),
+                _ => !(input.is_array() || input.is_object()),
+            };
+            Ok(if keep { vec![input.clone()] } else { vec![] })
+        }
+        ("select", 1) => Ok(if arg(0)?.iter().any(truthy) {
+            vec![input.clone()]
+        } else {
+            Vec::new()
+        }),
+        ("map", 1) => {
+            let mut out = Vec::new();
+            for item in items(input, name)? {
+                out.extend(eval(&args[0], item, env)?);
+            }
+            one(Value::Array(out))
+        }
+        ("map_values", 1) => match input {
+            Value::Object(map) => {
+                let mut out = Map::new();
+                for (key, value) in map {
+                    if let Some(first) = eval(&args[0], value, env)?.into_iter().next() {
+                        out.insert(key.clone(), first);
+                    }
+                }
+                one(Value::Object(out))
+            }
+            Value::Array(items) => {
+                let mut out = Vec::new();
+                for item in items {
+                    out.extend(eval(&args[0], item, env)?.into_iter().next());
+                }
+                one(Value::Array(out))
+            }
+            other => Err(format!("Cannot iterate over {}", describe(other))),
+        },
+        ("recurse", 0) => Ok(recurse(input)),
+        ("recurse", 1) => {
+            let mut out = Vec::new();
+            let mut stack = vec![input.clone()];
+            while let Some(value) = stack.pop() {
+                let children = eval(&args[0], &value, env).unwrap_or_default();
+                out.push(value);
+                stack.extend(children.into_iter().rev());
+                if out.len() > MAX_RANGE as usize {
+                    return Err("recurse produced too many values".to_string());
+                }
+            }
+            Ok(out)
+        }
+        ("range", 1) | ("range", 2) => {
+            let bounds: Vec<Value> = (0..args.len())
+                .map(arg)
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .filter_map(|outputs| outputs.into_iter().next())
+                .collect();
+            let (from, to) = match bounds.as_slice() {
+                [to] => (0.0, as_f64(to)),
+                [from, to] => (as_f64(from), as_f64(to)),
+                _ => return Ok(Vec::new()),
+            };
+            if to - from > MAX_RANGE as f64 {
+                return Err(format!("range is limited to {} values", MAX_RANGE));
+            }
+            let mut out = Vec::new();
+            let mut i = from;
+            while i < to {
+                out.push(number_value(i));
+                i += 1.0;
+            }
+            Ok(out)
+        }
+        ("first", 0) => one(index(input, &Value::from(0))?),
+        ("last", 0) => one(index(input, &Value::from(-1))?),
+        ("nth", 1) => arg(0)?.iter().map(|n| index(input, n)).collect(),
+        ("first", 1) => Ok(arg(0)?.into_iter().take(1).collect()),
+        ("last", 1) => Ok(arg(0)?.into_iter().last().into_iter().collect()),
+        ("limit", 2) => {
+            let mut out = Vec::new();
+            for n in arg(0)? {
+                out.extend(arg(1)?.into_iter().take(as_f64(&n).max(0.0) as usize));
+            }
+            Ok(out)
+        }
+        ("reverse", 0) => one(match input {
+            Value::Null => Value::Array(Vec::new()),
+            Value::String(text) => Value::String(text.chars().rev().collect()),
+            other => Value::Array(items(other, name)?.iter().rev().cloned().collect()),
+        }),
+        ("sort", 0) => {
+            let mut sorted = items(input, name)?.clone();
+            sorted.sort_by(compare_values);
+            one(Value::Array(sorted))
+        }
+        ("sort_by", 1) => one(Value::Array(
+            keyed(input, &args[0], env, name)?
+                .into_iter()
+                .map(|(_, item)| item)
+                .collect(),
+        )),
+        ("group_by", 1) | ("unique_by", 1) => {
+            let mut groups: Vec<(Value, Vec<Value>)> = Vec::new();
+            for (key, item) in keyed(input, &args[0], env, name)? {
+                match groups.last_mut() {
+                    Some((last, group)) if same(last, &key) => group.push(item),
+                    _ => groups.push((key, vec![item])),
+                }
+            }
+            one(Value::Array(
+                groups
+                    .into_iter()
+                    .map(|(_, group)| {
+                        if name == "group_by" {
+                            Value::Array(group)
+                        } else {
+                            group.into_iter().next().unwrap_or(Value::Null)
+                        }
+                    })
+                    .collect(),
+            ))
+        }
+        ("unique", 0) => {
+            let mut sorted = items(input, name)?.clone();
+            sorted.sort_by(compare_values);
+            sorted.dedup_by(|a, b| same(a, b));
+            one(Value::Array(sorted))
+        }
+        ("min", 0) | ("max", 0) => {
+            let list = items(input, name)?;
+            let found = if name == "min" {
+                list.iter().min_by(|a, b| compare_values(a, b))
+            } else {
+                list.iter().max_by(|a, b| compare_values(a, b))
+            };
+            one(found.cloned().unwrap_or(Value::Null))
+        }
+        ("min_by", 1) | ("max_by", 1) => {
+            let pairs = keyed(input, &args[0], env, name)?;
+            let found = if name == "min_by" {
+                pairs.into_iter().next()
+            } else {
+                pairs.into_iter().last()
+            };
+            one(found.map_or(Value::Null, |(_, item)| item))
+        }
+        ("add", 0) => {
+            let values: Vec<&Value> = match input {
+                Value::Object(map) => map.values().collect(),
+                other => items(other, name)?.iter().collect(),
+            };
+            let mut total = Value::Null;
+            for value in values {
+                total = binary("+", &total, value)?;
+            }
+            one(total)
+        }
+        ("any", 0) => one(Value::Bool(items(input, name)?.iter().any(truthy))),
+        ("all", 0) => one(Value::Bool(items(input, name)?.iter().all(truthy))),
+        ("any", 1) | ("all", 1) => {
+            let mut results = Vec::new();
+            for item in items(input, name)? {
+                results.push(eval(&args[0], item, env)?.iter().any(truthy));
+            }
+            one(Value::Bool(if name == "any" {
+                results.into_iter().any(|r| r)
+            } else {
+                results.into_iter().all(|r| r)
+            }))
+        }
+        ("flatten", 0) | ("flatten", 1) => {
+            let depth = if args.is_empty() {
+                f64::INFINITY
+            } else {
+                arg(0)?.first().map_or(1.0, as_f64)
+            };
+            if depth < 0.0 {
+                return Err("flatten depth must not be negative".to_string());
+            }
+            fn flatten(items: &[Value], depth: f64, out: &mut Vec<Value>) {
+                for item in items {
+                    match item {
+                        Value::Array(inner) if depth > 0.0 => flatten(inner, depth - 1.0, out),
+                        other => out.push(other.clone()),
+                    }
+                }
+            }
+            let mut out = Vec::new();
+            flatten(items(input, name)?, depth, &mut out);
+            one(Value::Array(out))
+        }
+        ("to_entries", 0) => match input {
+            Value::Object(map) => one(Value::Array(
+                map.iter()
+                    .map(|(key, value)| {
+                        let mut entry = Map::new();
+                        entry.insert("key".to_string(), Value::String(key.clone()));
+                        entry.insert("value".to_string(), value.clone());
+                        Value::Object(entry)
+                    })
+                    .collect(),
+            )),
+            other => Err(format!("{} has no keys", describe(other))),
+        },
+        ("from_entries", 0) => {
+            let mut map = Map::new();
+            for entry in items(input, name)? {
+                let field = |names: &[&str]| {
+                    names
+                        .iter()
+                        .find_map(|n| entry.get(n).filter(|v| !v.is_null()))
+                        .cloned()
+                };
+                let key = match field(&["key", "k", "name", "Name", "Key", "K"]) {
+                    Some(Value::String(key)) => key,
+                    Some(Value::Number(n)) => n.to_string(),
+                    Some(Value::Bool(b)) => b.to_string(),
+                    _ => return Err(format!("Cannot use {} as an entry key", describe(entry))),
+                };
+                map.insert(
+                    key,
+                    field(&["value", "v", "Value", "V"]).unwrap_or(Value::Null),
+                );
+            }
+            one(Value::Object(map))
+        }
+        ("with_entries", 1) => {
+            let entries = call("to_entries", &[], input, env)?;
+            let mapped = call("map", args, &entries[0], env)?;
+            call("from_entries", &[], &mapped[0], env)
+        }
+        ("tostring", 0) => one(Value::String(match input {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        })),
+        ("tojson", 0) => one(Value::String(input.to_string())),
+        ("fromjson", 0) => serde_json::from_str(string_arg(input, name)?)
+            .map(|value| vec![value])
+            .map_err(|e| format!("fromjson: {}", e)),
+        ("tonumber", 0) => match input {
+            Value::Number(_) => one(input.clone()),
+            Value::String(text) => serde_json::from_str::<Value>(text.trim())
+                .ok()
+                .filter(Value::is_number)
+                .map(|value| vec![value])
+                .ok_or_else(|| format!("Cannot parse '{}' as a number", text)),
+            other => Err(format!("{} cannot be parsed as a number", describe(other))),
+        },
+        ("ascii_downcase", 0) => one(Value::String(string_arg(input, name)?.to_ascii_lowercase())),
+        ("ascii_upcase", 0) => one(Value::String(string_arg(input, name)?.to_ascii_uppercase())),
+        ("floor" | "ceil" | "round" | "fabs" | "sqrt", 0) => {
+            if !input.is_number() {
+                return Err(format!("{} has no {}", describe(input), name));
+            }
+            let n = as_f64(input);
+            one(number_value(match name {
+                "floor" => n.floor(),
+                "ceil" => n.ceil(),
+                "round" => n.round(),
+                "fabs" => n.abs(),
+                _ => n.sqrt(),
+            }))
+        }
+        ("startswith" | "endswith" | "ltrimstr" | "rtrimstr" | "split" | "join", 1) => {
+            let mut out = Vec::new();
+            for affix in arg(0)? {
+                out.push(match name {
+                    "join" => {
+                        let separator = string_arg(&affix, name)?;
+                        let parts = items(input, name)?
+                            .iter()
+                            .map(|item| match item {
+                                Value::Null => Ok(String::new()),
+                                Value::String(text) => Ok(text.clone()),
+                                Value::Number(_) | Value::Bool(_) => Ok(item.to_string()),
+                                other => Err(format!("Cannot join with {}", describe(other))),
+                            })
+                            .collect::<Result<Vec<_>, _>>()?;
+                        Value::String(parts.join(separator))
+                    }
+                    "split" => split(string_arg(input, name)?, string_arg(&affix, name)?),
+                    "startswith" => {
+                        Value::Bool(string_arg(input, name)?.starts_with(string_arg(&affix, name)?))
+                    }
+                    "endswith" => {
+                        Value::Bool(string_arg(input, name)?.ends_with(string_arg(&affix, name)?))
+                    }
+                    _ => match (input, &affix) {
+                        (Value::String(text), Value::String(affix)) => {
+                            let trimmed = if name == "ltrimstr" {
+                                text.strip_prefix(affix.as_str())
+                            } else {
+                                text.strip_suffix(affix.as_str())
+                            };
+                            Value::String(trimmed.unwrap_or(text).to_string())
+                        }
+                        _ => input.clone(),
+                    },
+                });
+            }
+            Ok(out)
+        }
+        ("contains", 1) => arg(0)?
+            .iter()
+            .map(|needle| contains(input, needle).map(Value::Bool))
+            .collect(),
+        ("getpath", 1) => {
+            let mut out = Vec::new();
+            for path in arg(0)? {
+                let mut value = input.clone();
+                for key in items(&path, name)? {
+                    value = index(&value, key)?;
+                }
+                out.push(value);
+            }
+            Ok(out)
+        }
+        ("explode", 0) => one(Value::Array(
+            string_arg(input, name)?
+                .chars()
+                .map(|c| Value::from(c as u32))
+                .collect(),
+        )),
+        ("implode", 0) => items(input, name)?
+            .iter()
+            .map(|code| {
+                code.as_u64()
+                    .and_then(|code| char::from_u32(code as u32))
+                    .ok_or_else(|| format!("Cannot implode {}", describe(code)))
+            })
+            .collect::<Result<String, _>>()
+            .map(|text| vec![Value::String(text)]),
+        _ => Err(format!("{}/{} is not defined", name, args.len())),
+    }
+}
+
+/// jq's `contains`: substring for strings, recursive subset for containers
+fn contains(haystack: &Value, needle: &Value) -> Result<bool, String> {
+    match (haystack, needle) {
+        (Value::String(a), Value::String(b)) => Ok(a.contains(b.as_str())),
+        (Value::Array(a), Value::Array(b)) => {
+            for wanted in b {
+                let mut found = false;
+                for item in a {
+                    if contains(item, wanted)? {
+                        found = true;
+                        break;
+                    }
+                }
+                if !found {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, wanted) in b {
+                match a.get(key) {
+                    Some(item) if contains(item, wanted)? => {}
+                    _ => return Ok(false),
+                }
+            }
+            Ok(true)
+        }
+        (a, b) if type_name(a) == type_name(b) => Ok(same(a, b)),
+        _ => Err(format!(
+            "{} and {} cannot have their containment checked",
+            describe(haystack),
+            describe(needle)
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn document() -> Value {
+        json!({
+            "users": [
+                {"name": "ada", "age": 36, "tags": ["math", "engines"]},
+                {"name": "grace", "age": 85, "tags": ["navy"]},
+                {"name": "linus", "age": 28, "tags": []}
+            ],
+            "meta": {"count": 3, "next": null}
+        })
+    }
+
+    fn run(query: &str, document: &Value) -> Vec<Value> {
+        evaluate(query, document).unwrap_or_else(|e| panic!("{}: {}", query, e))
+    }
+
+    #[test]
+    fn paths() {
+        let doc = document();
+        assert_eq!(run(".", &doc), vec![doc.clone()]);
+        assert_eq!(run(".meta.count", &doc), [json!(3)]);
+        assert_eq!(run(".users[1].name", &doc), [json!("grace")]);
+        assert_eq!(run(".users[-1].name", &doc), [json!("linus")]);
+        assert_eq!(run(".[\"meta\"].next", &doc), [json!(null)]);
+        assert_eq!(run(".missing.deeper", &doc), [json!(null)]);
+        assert_eq!(run(".users[1:].[].age", &doc), [json!(85), json!(28)]);
+        assert_eq!(run(".meta[]", &doc), [json!(3), json!(null)]);
+        // `?` suppresses errors instead of failing
+        assert_eq!(run(".meta.count[]?", &doc), Vec::<Value>::new());
+        assert!(evaluate(".meta.count[]", &doc).is_err());
+    }
+
+    #[test]
+    fn recursive_descent() {
+        let doc = json!({"a": [1, {"b": 2}]});
+        assert_eq!(
+            run("..", &doc),
+            [
+                doc.clone(),
+                json!([1, {"b": 2}]),
+                json!(1),
+                json!({"b": 2}),
+                json!(2),
+            ]
+        );
+        assert_eq!(run("[.. | numbers]", &doc), [json!([1, 2])]);
+        assert_eq!(run("[.. | .b? // empty]", &doc), [json!([2])]);
+    }
+
+    #[test]
+    fn pipes_and_construction() {
+        let doc = document();
+        assert_eq!(
+            run(".users[] | .name", &doc),
+            [json!("ada"), json!("grace"), json!("linus")]
+        );
+        assert_eq!(
+            run(".users[0] | {name, first: .tags[0]}", &doc),
+            [json!({"name": "ada", "first": "math"})]
+        );
+        assert_eq!(
+            run("[.users[] | .age] | add / length", &doc),
+            [json!(49.666666666666664)]
+        );
+        assert_eq!(
+            run(".meta.count, .meta.next", &doc),
+            [json!(3), json!(null)]
+        );
+        assert_eq!(
+            run(".users[] as $u | $u.tags | length", &doc),
+            [json!(2), json!(1), json!(0)]
+        );
+        assert_eq!(
+            run("if .meta.next then \"more\" else \"done\" end", &doc),
+            [json!("done")]
+        );
+    }
+
+    #[test]
+    fn select_and_map() {
+        let doc = document();
+        assert_eq!(
+            run(".users[] | select(.age > 30) | .name", &doc),
+            [json!("ada"), json!("grace")]
+        );
+        assert_eq!(
+            run("[.users[] | select(.tags | length == 0)] | length", &doc),
+            [json!(1)]
+        );
+        assert_eq!(
+            run(".users | map(.name)", &doc),
+            [json!(["ada", "grace", "linus"])]
+        );
+        assert_eq!(
+            run(
+                ".users | map(select(.name | startswith(\"g\")) | .age)",
+                &doc
+            ),
+            [json!([85])]
+        );
+        assert_eq!(run("[1, 2, 3] | map(. * 10)", &doc), [json!([10, 20, 30])]);
+        assert_eq!(
+            run(".users | sort_by(.age) | map(.name) | first", &doc),
+            [json!("linus")]
+        );
+    }
+
+    #[test]
+    fn runtime_errors() {
+        let doc = document();
+        assert_eq!(
+            evaluate(".meta.count | keys", &doc),
+            Err("number (3) has no keys".to_string())
+        );
+        assert_eq!(
+            evaluate("nosuchfn(1)", &doc),
+            Err("nosuchfn/1 is not defined".to_string())
+        );
+    }
+
+    #[test]
+    fn unsupported_syntax() {
+        let doc = document();
+        assert_eq!(
+            evaluate("reduce .users[] as $u (0; . + $u.age)", &doc),
+            Err("Query error at position 1: 'reduce' is not supported".to_string())
+        );
+        assert_eq!(
+            evaluate(".users | foreach .[] as $u (0; . + 1)", &doc),
+            Err("Query error at position 10: 'foreach' is not supported".to_string())
+        );
+        assert_eq!(
+            evaluate("def f: 1; f", &doc),
+            Err("Query error at position 1: 'def' is not supported".to_string())
+        );
+        assert_eq!(
+            evaluate("\"\\(.meta.count)\"", &doc),
+            Err("Query error at position 1: string interpolation is not supported".to_string())
+        );
+    }
+}
@@ -0,0 +1,667 @@
+//! JSONPath evaluation following RFC 9535: child and descendant segments,
+//! wildcards, indices, slices, unions and filter expressions.
+
+use super::diff::same;
+use super::query::{compare_values, syntax_error};
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// Evaluates a JSONPath query (starting with `$`) against `document`
+pub fn evaluate(query: &str, document: &Value) -> Result<Vec<Value>, String> {
+    let mut parser = Parser::new(query);
+    parser.skip_whitespace();
+    parser.expect('$')?;
+    let segments = parser.segments()?;
+    parser.skip_whitespace();
+    if let Some(c) = parser.peek() {
+        return Err(parser.error(&format!("unexpected '{}'", c)));
+    }
+
+    Ok(select(&segments, document, document)
+        .into_iter()
+        .cloned()
+        .collect())
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Debug, Clone)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    Filter(Filter),
+}
+
+#[derive(Debug, Clone)]
+enum Filter {
+    Or(Box<Filter>, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Compare(Operand, CompareOp, Operand),
+    Exists(Operand),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    Literal(Value),
+    /// `$...` when `absolute`, otherwise `@...`
+    Path {
+        absolute: bool,
+        segments: Vec<Segment>,
+    },
+}
+
+fn select<'a>(segments: &[Segment], root: &'a Value, node: &'a Value) -> Vec<&'a Value> {
+    let mut current = vec![node];
+    for segment in segments {
+        let mut next = Vec::new();
+        for value in current {
+            match segment {
+                Segment::Child(selectors) => apply(selectors, root, value, &mut next),
+                Segment::Descendant(selectors) => {
+                    for descendant in descendants(value) {
+                        apply(selectors, root, descendant, &mut next);
+                    }
+                }
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+/// `value` and everything below it, in document order
+fn descendants(value: &Value) -> Vec<&Value> {
+    let mut found = Vec::new();
+    let mut stack = vec![value];
+    while let Some(node) = stack.pop() {
+        found.push(node);
+        match node {
+            Value::Array(items) => stack.extend(items.iter().rev()),
+            Value::Object(map) => stack.extend(map.values().rev()),
+            _ => {}
+        }
+    }
+    found
+}
+
+fn children(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => map.values().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn apply<'a>(selectors: &[Selector], root: &'a Value, value: &'a Value, out: &mut Vec<&'a Value>) {
+    for selector in selectors {
+        match (selector, value) {
+            (Selector::Name(name), Value::Object(map)) => out.extend(map.get(name)),
+            (Selector::Wildcard, _) => out.extend(children(value)),
+            (Selector::Index(index), Value::Array(items)) => {
+                let len = items.len() as i64;
+                let index = if *index < 0 { len + index } else { *index };
+                if (0..len).contains(&index) {
+                    out.push(&items[index as usize]);
+                }
+            }
+            (Selector::Slice(start, end, step), Value::Array(items)) => {
+                out.extend(slice_indices(items.len(), *start, *end, *step).map(|i| &items[i]));
+            }
+            (Selector::Filter(filter), _) => {
+                out.extend(
+                    children(value)
+                        .into_iter()
+                        .filter(|child| test(filter, root, child)),
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Indices selected by a `[start:end:step]` slice, per RFC 9535
+fn slice_indices(
+    len: usize,
+    start: Option<i64>,
+    end: Option<i64>,
+    step: Option<i64>,
+) -> Box<dyn Iterator<Item = usize>> {
+    let len = len as i64;
+    let step = step.unwrap_or(1);
+    let normalize = |i: i64| if i < 0 { len + i } else { i };
+
+    if step > 0 {
+        let lower = normalize(start.unwrap_or(0)).clamp(0, len);
+        let upper = normalize(end.unwrap_or(len)).clamp(0, len);
+        Box::new((lower..upper).step_by(step as usize).map(|i| i as usize))
+    } else if step < 0 {
+        let upper = normalize(start.unwrap_or(len - 1)).clamp(-1, len - 1);
+        let lower = end.map_or(-1, |e| normalize(e).clamp(-1, len - 1));
+        Box::new(
+            ((lower + 1)..=upper)
+                .rev()
+                .step_by(step.unsigned_abs() as usize)
+                .map(|i| i as usize),
+        )
+    } else {
+        Box::new(std::iter::empty())
+    }
+}
+
+fn test(filter: &Filter, root: &Value, current: &Value) -> bool {
+    match filter {
+        Filter::Or(a, b) => test(a, root, current) || test(b, root, current),
+        Filter::And(a, b) => test(a, root, current) && test(b, root, current),
+        Filter::Not(inner) => !test(inner, root, current),
+        Filter::Exists(operand) => !resolve(operand, root, current).is_empty(),
+        Filter::Compare(left, op, right) => {
+            // Comparisons use singular queries: the first match, if any
+            let left = resolve(left, root, current).into_iter().next();
+            let right = resolve(right, root, current).into_iter().next();
+            compare(left, *op, right)
+        }
+    }
+}
+
+fn resolve<'a>(operand: &'a Operand, root: &'a Value, current: &'a Value) -> Vec<&'a Value> {
+    match operand {
+        Operand::Literal(value) => vec![value],
+        Operand::Path { absolute, segments } => {
+            select(segments, root, if *absolute { root } else { current })
+        }
+    }
+}
+
+fn compare(left: Option<&Value>, op: CompareOp, right: Option<&Value>) -> bool {
+    let equal = match (left, right) {
+        (Some(a), Some(b)) => same(a, b),
+        (None, None) => true,
+        _ => false,
+    };
+    // Ordering only applies between two numbers or two strings
+    let ordering = match (left, right) {
+        (Some(a @ Value::Number(_)), Some(b @ Value::Number(_)))
+        | (Some(a @ Value::String(_)), Some(b @ Value::String(_))) => Some(compare_values(a, b)),
+        _ => None,
+    };
+    match op {
+        CompareOp::Eq => equal,
+        CompareOp::Ne => !equal,
+        CompareOp::Lt => ordering == Some(Ordering::Less),
+        CompareOp::Gt => ordering == Some(Ordering::Greater),
+        CompareOp::Le => equal || ordering == Some(Ordering::Less),
+        CompareOp::Ge => equal || ordering == Some(Ordering::Greater),
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(query: &str) -> Self {
+        Parser {
+            chars: query.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn error(&self, message: &str) -> String {
+        syntax_error(self.pos, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, expected: &str) -> bool {
+        let matches = expected
+            .chars()
+            .enumerate()
+            .all(|(i, c)| self.peek_at(i) == Some(c));
+        if matches {
+            self.pos += expected.chars().count();
+        }
+        matches
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", expected)))
+        }
+    }
+
+    fn segments(&mut self) -> Result<Vec<Segment>, String> {
+        let mut segments = Vec::new();
+        loop {
+            // Whitespace may separate segments, but only if one follows
+            let start = self.pos;
+            self.skip_whitespace();
+            if self.eat("..") {
+                let selectors = match self.peek() {
+                    Some('[') => self.bracket()?,
+                    Some('*') => {
+                        self.pos += 1;
+                        vec![Selector::Wildcard]
+                    }
+                    _ => vec![Selector::Name(self.member_name()?)],
+                };
+                segments.push(Segment::Descendant(selectors));
+            } else if self.eat(".") {
+                if self.eat("*") {
+                    segments.push(Segment::Child(vec![Selector::Wildcard]));
+                } else {
+                    segments.push(Segment::Child(vec![Selector::Name(self.member_name()?)]));
+                }
+            } else if self.peek() == Some('[') {
+                segments.push(Segment::Child(self.bracket()?));
+            } else {
+                self.pos = start;
+                return Ok(segments);
+            }
+        }
+    }
+
+    fn member_name(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '-' || !c.is_ascii())
+        {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(self.error("expected a member name"));
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn bracket(&mut self) -> Result<Vec<Selector>, String> {
+        self.expect('[')?;
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_whitespace();
+            selectors.push(self.selector()?);
+            self.skip_whitespace();
+            if self.eat(",") {
+                continue;
+            }
+            self.expect(']')?;
+            return Ok(selectors);
+        }
+    }
+
+    fn selector(&mut self) -> Result<Selector, String> {
+        match self.peek() {
+            Some('\'') | Some('"') => Ok(Selector::Name(self.string()?)),
+            Some('*') => {
+                self.pos += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some('?') => {
+                self.pos += 1;
+                Ok(Selector::Filter(self.or()?))
+            }
+            Some(c) if c == '-' || c == ':' || c.is_ascii_digit() => {
+                let start = self.integer()?;
+                self.skip_whitespace();
+                if !self.eat(":") {
+                    return start
+                        .map(Selector::Index)
+                        .ok_or_else(|| self.error("expected an index"));
+                }
+                self.skip_whitespace();
+                let end = self.integer()?;
+                self.skip_whitespace();
+                let step = if self.eat(":") {
+                    self.skip_whitespace();
+                    self.integer()?
+                } else {
+                    None
+                };
+                Ok(Selector::Slice(start, end, step))
+            }
+            _ => Err(self.error("expected a selector")),
+        }
+    }
+
+    /// An optional integer; `None` when no digits follow
+    fn integer(&mut self) -> Result<Option<i64>, String> {
+        let start = self.pos;
+        self.eat("-");
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        text.parse()
+            .map(Some)
+            .map_err(|_| syntax_error(start, "invalid integer"))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        let quote = self.peek().unwrap_or('"');
+        self.pos += 1;
+        let mut text = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(syntax_error(start, "unterminated string")),
+                Some(c) if c == quote => {
+                    self.pos += 1;
+                    return Ok(text);
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    let escaped = self
+                        .peek()
+                        .ok_or_else(|| syntax_error(start, "unterminated string"))?;
+                    self.pos += 1;
+                    text.push(match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'u' => {
+                            let hex: String = self
+                                .chars
+                                .get(self.pos..self.pos + 4)
+                                .unwrap_or_default()
+                                .iter()
+                                .collect();
+                            self.pos += 4;
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("invalid \\u escape"))?
+                        }
+                        other => other,
+                    });
+                }
+                Some(c) => {
+                    self.pos += 1;
+                    text.push(c);
+                }
+            }
+        }
+    }
+
+    fn or(&mut self) -> Result<Filter, String> {
+        let mut left = self.and()?;
+        loop {
+            self.skip_whitespace();
+            if !self.eat("||") {
+                return Ok(left);
+            }
+            left = Filter::Or(Box::new(left), Box::new(self.and()?));
+        }
+    }
+
+    fn and(&mut self) -> Result<Filter, String> {
+        let mut left = self.unary()?;
+        loop {
+            self.skip_whitespace();
+            if !self.eat("&&") {
+                return Ok(left);
+            }
+            left = Filter::And(Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Filter, String> {
+        self.skip_whitespace();
+        if self.peek() == Some('!') && self.peek_at(1) != Some('=') {
+            self.pos += 1;
+            return Ok(Filter::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let inner = self.or()?;
+            self.skip_whitespace();
+            self.expect(')')?;
+            return Ok(inner);
+        }
+
+        let left = self.operand()?;
+        self.skip_whitespace();
+        let op = [
+            ("==", CompareOp::Eq),
+            ("!=", CompareOp::Ne),
+            ("<=", CompareOp::Le),
+            (">=", CompareOp::Ge),
+            ("<", CompareOp::Lt),
+            (">", CompareOp::Gt),
+        ]
+        .into_iter()
+        .find(|(token, _)| self.eat(token))
+        .map(|(_, op)| op);
+
+        match op {
+            Some(op) => {
+                self.skip_whitespace();
+                Ok(Filter::Compare(left, op, self.operand()?))
+            }
+            None if matches!(left, Operand::Path { .. }) => Ok(Filter::Exists(left)),
+            None => Err(self.error("expected a comparison operator")),
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand, String> {
+        match self.peek() {
+            Some(c @ ('@' | '$')) => {
+                self.pos += 1;
+                Ok(Operand::Path {
+                    absolute: c == '$',
+                    segments: self.segments()?,
+                })
+            }
+            Some('\'') | Some('"') => Ok(Operand::Literal(Value::String(self.string()?))),
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let start = self.pos;
+                while self
+                    .peek()
+                    .is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+                {
+                    self.pos += 1;
+                }
+                let text: String = self.chars[start..self.pos].iter().collect();
+                serde_json::from_str::<Value>(&text)
+                    .ok()
+                    .filter(Value::is_number)
+                    .map(Operand::Literal)
+                    .ok_or_else(|| syntax_error(start, "invalid number"))
+            }
+            _ => {
+                for (word, value) in [
+                    ("true", Value::Bool(true)),
+                    ("false", Value::Bool(false)),
+                    ("null", Value::Null),
+                ] {
+                    if self.eat(word) {
+                        return Ok(Operand::Literal(value));
+                    }
+                }
+                Err(self.error("expected a value, '@' or '$'"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// The bookstore example from RFC 9535
+    fn store() -> Value {
+        json!({
+            "store": {
+                "book": [
+                    {"category": "reference", "author": "Nigel Rees",
+                     "title": "Sayings of the Century", "price": 8.95},
+                    {"category": "fiction", "author": "Evelyn Waugh",
+                     "title": "Sword of Honour", "price": 12.99},
+                    {"category": "fiction", "author": "Herman Melville",
+                     "title": "Moby Dick", "isbn": "0-553-21311-3", "price": 8.99},
+                    {"category": "fiction", "author": "J. R. R. Tolkien",
+                     "title": "The Lord of the Rings", "isbn": "0-395-19395-8",
+                     "price": 22.99}
+                ],
+                "bicycle": {"color": "red", "price": 399}
+            }
+        })
+    }
+
+    fn query(path: &str, document: &Value) -> Vec<Value> {
+        evaluate(path, document).unwrap_or_else(|e| panic!("{}: {}", path, e))
+    }
+
+    #[test]
+    fn child_segments() {
+        let store = store();
+        assert_eq!(query("$.store.bicycle.color", &store), [json!("red")]);
+        assert_eq!(
+            query("$['store']['book'][0]['title']", &store),
+            [json!("Sayings of the Century")]
+        );
+        assert_eq!(query("$.store.book[-1].price", &store), [json!(22.99)]);
+        assert_eq!(query("$.store.*", &store).len(), 2);
+        assert_eq!(
+            query("$.store.book[0,2].author", &store),
+            [json!("Nigel Rees"), json!("Herman Melville")]
+        );
+        assert!(query("$.store.missing", &store).is_empty());
+        assert!(query("$.store.book[9]", &store).is_empty());
+    }
+
+    #[test]
+    fn recursive_descent() {
+        let store = store();
+        assert_eq!(
+            query("$..author", &store),
+            [
+                json!("Nigel Rees"),
+                json!("Evelyn Waugh"),
+                json!("Herman Melville"),
+                json!("J. R. R. Tolkien"),
+            ]
+        );
+        assert_eq!(query("$.store..price", &store).len(), 5);
+        assert_eq!(query("$..book[2].title", &store), [json!("Moby Dick")]);
+        assert_eq!(
+            query("$..book[-1:].title", &store),
+            [json!("The Lord of the Rings")]
+        );
+        // Every member and element below the root
+        assert_eq!(query("$..*", &store).len(), 27);
+    }
+
+    #[test]
+    fn slices() {
+        let array = json!(["a", "b", "c", "d", "e", "f", "g"]);
+        assert_eq!(query("$[1:3]", &array), [json!("b"), json!("c")]);
+        assert_eq!(query("$[5:]", &array), [json!("f"), json!("g")]);
+        assert_eq!(query("$[:2]", &array), [json!("a"), json!("b")]);
+        assert_eq!(query("$[1:5:2]", &array), [json!("b"), json!("d")]);
+        assert_eq!(query("$[-2:]", &array), [json!("f"), json!("g")]);
+        assert_eq!(
+            query("$[::3]", &array),
+            [json!("a"), json!("d"), json!("g")]
+        );
+
+        // Negative steps walk backwards from the end
+        assert_eq!(query("$[5:1:-2]", &array), [json!("f"), json!("d")]);
+        assert_eq!(
+            query("$[::-1]", &array),
+            ["g", "f", "e", "d", "c", "b", "a"].map(|s| json!(s))
+        );
+        assert_eq!(
+            query("$[-1:-4:-1]", &array),
+            [json!("g"), json!("f"), json!("e")]
+        );
+
+        // A zero step selects nothing; bounds are clamped
+        assert!(query("$[::0]", &array).is_empty());
+        assert_eq!(query("$[-100:2]", &array), [json!("a"), json!("b")]);
+        assert!(query("$[1:3]", &json!({"a": 1})).is_empty());
+    }
+
+    #[test]
+    fn filters() {
+        let store = store();
+        assert_eq!(
+            query("$..book[?@.price < 10].title", &store),
+            [json!("Sayings of the Century"), json!("Moby Dick")]
+        );
+        assert_eq!(
+            query("$..book[?@.isbn].title", &store),
+            [json!("Moby Dick"), json!("The Lord of the Rings")]
+        );
+        assert_eq!(
+            query("$..book[?!@.isbn].title", &store),
+            [json!("Sayings of the Century"), json!("Sword of Honour")]
+        );
+        assert_eq!(
+            query(
+                "$.store.book[?@.category == 'fiction' && @.price > 10].author",
+                &store
+            ),
+            [json!("Evelyn Waugh"), json!("J. R. R. Tolkien")]
+        );
+        assert_eq!(
+            query("$.store.book[?@.price < 9 || @.price > 20].price", &store),
+            [json!(8.95), json!(8.99), json!(22.99)]
+        );
+        // Comparing against the root
+        assert_eq!(
+            query("$.store.book[?@.price > $.store.bicycle.price]", &store),
+            Vec::<Value>::new()
+        );
+        assert_eq!(
+            query("$[?@ > 1]", &json!([1, 2, 3, "4"])),
+            [json!(2), json!(3)]
+        );
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let store = store();
+        for path in ["store", "$.", "$[", "$[1:2:3:4]", "$[?@.a ==]", "$.a b"] {
+            let error = evaluate(path, &store).expect_err(path);
+            assert!(error.starts_with("Query error at position"), "{}", error);
+        }
+    }
+}
@@ -0,0 +1,615 @@
+//! Reading JSONC and JSON5 by rewriting them into strict JSON.
+//!
+//! The rewrite keeps a map from output to input positions so that errors
+//! reported on the strict text still point at the right place in the input.
+
+use super::error::{error_byte_offset, strip_position, ErrorLocation, ParseError};
+use serde_json::error::Category;
+use serde_json::Value;
+
+/// Syntax accepted when reading JSON input
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Dialect {
+    #[default]
+    Strict,
+    /// JSON with `//` and `/* */` comments and trailing commas
+    Jsonc,
+    /// JSONC plus single quotes, unquoted keys and relaxed numbers
+    Json5,
+}
+
+impl Dialect {
+    pub const ALL: [Dialect; 3] = [Dialect::Strict, Dialect::Jsonc, Dialect::Json5];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Dialect::Strict => "strict",
+            Dialect::Jsonc => "jsonc",
+            Dialect::Json5 => "json5",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Dialect::Strict => "Strict JSON",
+            Dialect::Jsonc => "JSONC",
+            Dialect::Json5 => "JSON5",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|dialect| dialect.id() == id)
+    }
+}
+
+/// Counts of the lenient constructs rewritten into strict JSON
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Normalizations {
+    pub comments: usize,
+    pub trailing_commas: usize,
+    pub single_quoted: usize,
+    pub unquoted_keys: usize,
+    pub numbers: usize,
+    pub non_finite: usize,
+    pub escapes: usize,
+    pub line_continuations: usize,
+}
+
+impl Normalizations {
+    /// Human readable list of what was changed, e.g. "3 comments removed"
+    pub fn summary(&self) -> Vec<String> {
+        [
+            (self.comments, "comment", "comments", "removed"),
+            (
+                self.trailing_commas,
+                "trailing comma",
+                "trailing commas",
+                "fixed",
+            ),
+            (
+                self.single_quoted,
+                "single-quoted string",
+                "single-quoted strings",
+                "converted",
+            ),
+            (
+                self.unquoted_keys,
+                "unquoted key",
+                "unquoted keys",
+                "quoted",
+            ),
+            (self.numbers, "number", "numbers", "rewritten"),
+            (
+                self.non_finite,
+                "Infinity/NaN value",
+                "Infinity/NaN values",
+                "replaced with null",
+            ),
+            (
+                self.escapes,
+                "escape sequence",
+                "escape sequences",
+                "converted",
+            ),
+            (
+                self.line_continuations,
+                "line continuation",
+                "line continuations",
+                "joined",
+            ),
+        ]
+        .into_iter()
+        .filter(|(count, ..)| *count > 0)
+        .map(|(count, one, many, verb)| {
+            format!("{} {} {}", count, if count == 1 { one } else { many }, verb)
+        })
+        .collect()
+    }
+}
+
+/// Parses `input` in `dialect`, reporting what had to be normalized
+pub fn parse(input: &str, dialect: Dialect) -> Result<(Value, Normalizations), ParseError> {
+    if dialect == Dialect::Strict {
+        return serde_json::from_str(input)
+            .map(|value| (value, Normalizations::default()))
+            .map_err(|e| ParseError::from_serde(input, &e));
+    }
+
+    let normalizer = Normalizer::new(input, dialect == Dialect::Json5);
+    let (text, map, counts) = normalizer.run().map_err(|(byte, message)| ParseError {
+        message: format!("{} Parse Error: {}", dialect.label(), message),
+        location: Some(ErrorLocation::at(
+            input,
+            byte,
+            &message,
+            message.starts_with("unterminated"),
+            false,
+        )),
+    })?;
+
+    match serde_json::from_str(&text) {
+        Ok(value) => Ok((value, counts)),
+        Err(e) => {
+            let message = strip_position(&e);
+            let location = error_byte_offset(&text, &e).map(|byte| {
+                ErrorLocation::at(
+                    input,
+                    map_to_input(&map, byte),
+                    &message,
+                    e.classify() == Category::Eof,
+                    false,
+                )
+            });
+            Err(ParseError {
+                message: match &location {
+                    Some(at) => format!(
+                        "{} Parse Error: {} at line {} column {}",
+                        dialect.label(),
+                        message,
+                        at.line,
+                        at.column
+                    ),
+                    None => format!("{} Parse Error: {}", dialect.label(), message),
+                },
+                location,
+            })
+        }
+    }
+}
+
+/// Translates a byte offset in the rewritten text back to the input
+fn map_to_input(map: &[(usize, usize)], output_byte: usize) -> usize {
+    let index = map.partition_point(|&(out, _)| out <= output_byte);
+    if index == 0 {
+        return output_byte;
+    }
+    let (out, input) = map[index - 1];
+    let offset = input + (output_byte - out);
+    // Never run past where the next token started in the input
+    map.get(index).map_or(offset, |&(_, next)| offset.min(next))
+}
+
+fn is_json5_whitespace(c: char) -> bool {
+    matches!(
+        c,
+        '\u{a0}' | '\u{feff}' | '\u{2028}' | '\u{2029}' | '\u{b}' | '\u{c}'
+    ) || (c.is_whitespace() && !c.is_ascii())
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+fn is_identifier_part(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+type Failure = (usize, String);
+
+/// (output byte, input byte) pairs marking where each token started
+type PositionMap = Vec<(usize, usize)>;
+
+struct Normalizer<'a> {
+    src: &'a str,
+    pos: usize,
+    out: String,
+    map: PositionMap,
+    json5: bool,
+    counts: Normalizations,
+}
+
+impl<'a> Normalizer<'a> {
+    fn new(src: &'a str, json5: bool) -> Self {
+        Normalizer {
+            src,
+            pos: 0,
+            out: String::with_capacity(src.len()),
+            map: Vec::new(),
+            json5,
+            counts: Normalizations::default(),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn run(mut self) -> Result<(String, PositionMap, Normalizations), Failure> {
+        while let Some(c) = self.peek() {
+            self.map.push((self.out.len(), self.pos));
+            match c {
+                '/' if self.rest().starts_with("//") || self.rest().starts_with("/*") => {
+                    self.skip_comment()?;
+                    self.counts.comments += 1;
+                    // Keep tokens on either side of the comment apart
+                    self.out.push(' ');
+                }
+                '"' => self.string('"')?,
+                '\'' if self.json5 => {
+                    self.string('\'')?;
+                    self.counts.single_quoted += 1;
+                }
+                ',' => {
+                    self.pos += 1;
+                    if matches!(self.next_significant(), Some(']') | Some('}')) {
+                        self.counts.trailing_commas += 1;
+                    } else {
+                        self.out.push(',');
+                    }
+                }
+                '0'..='9' | '-' | '+' | '.' if self.json5 => self.number()?,
+                c if self.json5 && is_identifier_start(c) => self.identifier(),
+                c if self.json5 && is_json5_whitespace(c) => {
+                    self.out.push(' ');
+                    self.pos += c.len_utf8();
+                }
+                c => {
+                    self.out.push(c);
+                    self.pos += c.len_utf8();
+                }
+            }
+        }
+        Ok((self.out, self.map, self.counts))
+    }
+
+    fn skip_comment(&mut self) -> Result<(), Failure> {
+        let start = self.pos;
+        if self.rest().starts_with("//") {
+            self.pos += self.rest().find('\n').unwrap_or(self.rest().len());
+            Ok(())
+        } else {
+            match self.rest()[2..].find("*/") {
+                Some(end) => {
+                    self.pos += end + 4;
+                    Ok(())
+                }
+                None => Err((start, "unterminated block comment".to_string())),
+            }
+        }
+    }
+
+    /// The next character that is not whitespace or part of a comment
+    fn next_significant(&self) -> Option<char> {
+        let mut rest = self.rest();
+        loop {
+            rest = rest.trim_start_matches(|c: char| {
+                c.is_ascii_whitespace() || (self.json5 && is_json5_whitespace(c))
+            });
+            if let Some(after) = rest.strip_prefix("//") {
+                rest = after.find('\n').map_or("", |i| &after[i..]);
+            } else if let Some(after) = rest.strip_prefix("/*") {
+                rest = &after[after.find("*/")? + 2..];
+            } else {
+                return rest.chars().next();
+            }
+        }
+    }
+
+    fn string(&mut self, quote: char) -> Result<(), Failure> {
+        let start = self.pos;
+        self.pos += 1;
+        self.out.push('"');
+
+        loop {
+            let Some(c) = self.peek() else {
+                return Err((start, "unterminated string".to_string()));
+            };
+            self.pos += c.len_utf8();
+            match c {
+                c if c == quote => {
+                    self.out.push('"');
+                    return Ok(());
+                }
+                '"' => self.out.push_str("\\\""),
+                '\\' => self.escape(start)?,
+                c => self.out.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self, string_start: usize) -> Result<(), Failure> {
+        let Some(next) = self.peek() else {
+            return Err((string_start, "unterminated string".to_string()));
+        };
+        self.pos += next.len_utf8();
+
+        if !self.json5 {
+            self.out.push('\\');
+            self.out.push(next);
+            return Ok(());
+        }
+
+        match next {
+            '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' | 'u' => {
+                self.out.push('\\');
+                self.out.push(next);
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => self.counts.line_continuations += 1,
+            '\r' => {
+                if self.peek() == Some('\n') {
+                    self.pos += 1;
+                }
+                self.counts.line_continuations += 1;
+            }
+            'x' => {
+                let hex = self.rest().get(..2).unwrap_or_default();
+                let code = u8::from_str_radix(hex, 16)
+                    .map_err(|_| (self.pos - 2, "invalid \\x escape".to_string()))?;
+                self.pos += 2;
+                self.out.push_str(&format!("\\u{:04x}", code));
+                self.counts.escapes += 1;
+            }
+            '0' if !self.peek().is_some_and(|c| c.is_ascii_digit()) => {
+                self.out.push_str("\\u0000");
+                self.counts.escapes += 1;
+            }
+            'v' => {
+                self.out.push_str("\\u000b");
+                self.counts.escapes += 1;
+            }
+            '1'..='9' => return Err((self.pos - 2, "invalid escape sequence".to_string())),
+            // Any other character escapes to itself
+            other => {
+                self.out.push(other);
+                self.counts.escapes += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<(), Failure> {
+        let start = self.pos;
+        let mut rewritten = false;
+        let negative = match self.peek() {
+            Some('+') => {
+                self.pos += 1;
+                rewritten = true;
+                false
+            }
+            Some('-') => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        };
+
+        for word in ["Infinity", "NaN"] {
+            if self.rest().starts_with(word) {
+                self.pos += word.len();
+                self.out.push_str("null");
+                self.counts.non_finite += 1;
+                return Ok(());
+            }
+        }
+
+        let sign = if negative { "-" } else { "" };
+        let rest = self.rest();
+        if let Some(hex) = rest.strip_prefix("0x").or_else(|| rest.strip_prefix("0X")) {
+            let digits = hex.len()
+                - hex
+                    .trim_start_matches(|c: char| c.is_ascii_hexdigit())
+                    .len();
+            let value = u128::from_str_radix(&hex[..digits], 16)
+                .map_err(|_| (start, "invalid hexadecimal number".to_string()))?;
+            self.pos += 2 + digits;
+            self.out.push_str(&format!("{}{}", sign, value));
+            self.counts.numbers += 1;
+            return Ok(());
+        }
+
+        let digits = |s: &str| s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let integer_len = digits(rest);
+        let integer = &rest[..integer_len];
+        self.pos += integer_len;
+
+        let mut fraction = None;
+        if self.peek() == Some('.') {
+            self.pos += 1;
+            let len = digits(self.rest());
+            fraction = Some(&self.src[self.pos..self.pos + len]);
+            self.pos += len;
+        }
+
+        if integer.is_empty() && fraction.is_none_or(str::is_empty) {
+            // Not a number after all; let the strict parser report it
+            self.out.push_str(&self.src[start..self.pos]);
+            return Ok(());
+        }
+
+        self.out.push_str(sign);
+        if integer.is_empty() {
+            self.out.push('0');
+            rewritten = true;
+        } else {
+            self.out.push_str(integer);
+        }
+        match fraction {
+            Some("") => {
+                self.out.push_str(".0");
+                rewritten = true;
+            }
+            Some(fraction) => {
+                self.out.push('.');
+                self.out.push_str(fraction);
+            }
+            None => {}
+        }
+
+        if let Some(e @ ('e' | 'E')) = self.peek() {
+            self.pos += 1;
+            self.out.push(e);
+            if let Some(s @ ('+' | '-')) = self.peek() {
+                self.pos += 1;
+                self.out.push(s);
+            }
+            let len = digits(self.rest());
+            self.out.push_str(&self.src[self.pos..self.pos + len]);
+            self.pos += len;
+        }
+
+        if rewritten {
+            self.counts.numbers += 1;
+        }
+        Ok(())
+    }
+
+    fn identifier(&mut self) {
+        let len = self
+            .rest()
+            .find(|c: char| !is_identifier_part(c))
+            .unwrap_or(self.rest().len());
+        let word = &self.src[self.pos..self.pos + len];
+        self.pos += len;
+
+        match word {
+            // Reserved words are valid keys too, so check for a key first
+            _ if self.next_significant() == Some(':') => {
+                self.out.push('"');
+                self.out.push_str(word);
+                self.out.push('"');
+                self.counts.unquoted_keys += 1;
+            }
+            "true" | "false" | "null" => self.out.push_str(word),
+            "Infinity" | "NaN" => {
+                self.out.push_str("null");
+                self.counts.non_finite += 1;
+            }
+            // Let the strict parser report the stray word
+            _ => self.out.push_str(word),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parsed(input: &str, dialect: Dialect) -> (Value, Normalizations) {
+        parse(input, dialect).unwrap_or_else(|e| panic!("{:?} failed: {}", input, e.message))
+    }
+
+    /// Line and column of the error `input` raises in `dialect`
+    fn error_at(input: &str, dialect: Dialect) -> (usize, usize) {
+        let error = parse(input, dialect).expect_err(input);
+        let location = error.location.expect("error has a location");
+        (location.line, location.column)
+    }
+
+    #[test]
+    fn comments() {
+        let input = "// header\n{\n  \"a\": 1, // trailing\n  /* block\n  comment */ \"b\": [2 /* inline */]\n}";
+        let (value, counts) = parsed(input, Dialect::Jsonc);
+        assert_eq!(value, json!({"a": 1, "b": [2]}));
+        assert_eq!(counts.comments, 4);
+
+        // Comment markers inside strings are text
+        let (value, counts) = parsed(r#"{"url": "http://x/*y*/"}"#, Dialect::Jsonc);
+        assert_eq!(value, json!({"url": "http://x/*y*/"}));
+        assert_eq!(counts.comments, 0);
+
+        assert!(parse(input, Dialect::Strict).is_err());
+    }
+
+    #[test]
+    fn trailing_commas() {
+        let (value, counts) = parsed("{\"a\": [1, 2,], \"b\": {\"c\": 3,},}", Dialect::Jsonc);
+        assert_eq!(value, json!({"a": [1, 2], "b": {"c": 3}}));
+        assert_eq!(counts.trailing_commas, 3);
+
+        // A comment between the comma and the bracket still makes it trailing
+        let (value, _) = parsed("[1, // last\n]", Dialect::Jsonc);
+        assert_eq!(value, json!([1]));
+
+        // Only one trailing comma is forgiven
+        assert!(parse("[1,,]", Dialect::Jsonc).is_err());
+    }
+
+    #[test]
+    fn unquoted_keys() {
+        let (value, counts) = parsed("{a: 1, $b_2: 2, null: 3, true: [true]}", Dialect::Json5);
+        assert_eq!(value, json!({"a": 1, "$b_2": 2, "null": 3, "true": [true]}));
+        assert_eq!(counts.unquoted_keys, 4);
+
+        assert!(parse("{a: 1}", Dialect::Jsonc).is_err());
+    }
+
+    #[test]
+    fn single_quoted_strings() {
+        let (value, counts) = parsed(r#"{'key': 'say "hi"', "it's": 'it\'s'}"#, Dialect::Json5);
+        assert_eq!(value, json!({"key": "say \"hi\"", "it's": "it's"}));
+        assert_eq!(counts.single_quoted, 3);
+
+        let (value, _) = parsed("'a\\x41\\v\\0 \\\nb'", Dialect::Json5);
+        assert_eq!(value, json!("aA\u{b}\u{0} b"));
+
+        assert!(parse("'a'", Dialect::Jsonc).is_err());
+    }
+
+    #[test]
+    fn relaxed_numbers() {
+        let (value, counts) = parsed("[0xFF, -0x10, +1, .5, 5., -.25]", Dialect::Json5);
+        assert_eq!(value, json!([255, -16, 1, 0.5, 5.0, -0.25]));
+        assert_eq!(counts.numbers, 6);
+
+        let (value, counts) = parsed("[Infinity, -Infinity, NaN, +NaN]", Dialect::Json5);
+        assert_eq!(value, json!([null, null, null, null]));
+        assert_eq!(counts.non_finite, 4);
+
+        assert!(parse("0x", Dialect::Json5).is_err());
+        assert!(parse("[0xFF]", Dialect::Jsonc).is_err());
+    }
+
+    #[test]
+    fn error_positions() {
+        // Errors raised while rewriting point at their own position
+        assert_eq!(error_at("{\n  /* open", Dialect::Jsonc), (2, 3));
+        assert_eq!(error_at("[1,\n 'abc", Dialect::Json5), (2, 2));
+
+        // Errors from the strict parser are mapped back through the rewrite
+        assert_eq!(error_at("/* c */ {\"a\" 1}", Dialect::Jsonc), (1, 14));
+        assert_eq!(error_at("{a: 1,\n b: oops}", Dialect::Json5), (2, 5));
+
+        // Columns count characters, not bytes
+        assert_eq!(error_at("[\"é\", \"ü\" 1]", Dialect::Strict), (1, 11));
+        assert_eq!(error_at("{ä: 1 2}", Dialect::Json5), (1, 7));
+    }
+
+    #[test]
+    fn error_messages() {
+        let error = parse("{\"a\": 1 \"b\": 2}", Dialect::Strict).unwrap_err();
+        assert_eq!(
+            error.message,
+            "JSON Parse Error: expected `,` or `}` at line 1 column 9"
+        );
+
+        let error = parse("[1, /* open", Dialect::Jsonc).unwrap_err();
+        assert_eq!(
+            error.message,
+            "JSONC Parse Error: unterminated block comment"
+        );
+
+        // Strict input with comments is pointed at the lenient dialects
+        let error = parse("[1] // done", Dialect::Strict).unwrap_err();
+        let suggestions = error.location.unwrap().suggestions;
+        assert!(suggestions.iter().any(|s| s.contains("JSONC")));
+    }
+
+    #[test]
+    fn summary() {
+        let (_, counts) = parsed("{a: 'x', /* c */ b: [1,],}", Dialect::Json5);
+        assert_eq!(
+            counts.summary(),
+            [
+                "1 comment removed",
+                "2 trailing commas fixed",
+                "1 single-quoted string converted",
+                "2 unquoted keys quoted",
+            ]
+        );
+    }
+}
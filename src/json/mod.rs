@@ -0,0 +1,15 @@
+//! JSON inspection helpers shared by the JSON tools.
+//!
+//! These are plain functions over `serde_json::Value` so the components stay
+//! focused on rendering.
+
+pub mod diff;
+pub mod error;
+pub mod format;
+pub mod jq;
+pub mod jsonpath;
+pub mod lenient;
+pub mod query;
+pub mod schema;
+pub mod tree;
+pub mod types;
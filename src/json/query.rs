@@ -0,0 +1,127 @@
+//! Querying JSON documents with JSONPath or a subset of jq.
+//!
+//! Queries starting with `$` are JSONPath; anything else is read as jq.
+
+use super::diff::same;
+use super::{jq, jsonpath};
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// Query language, detected from the query text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    JsonPath,
+    Jq,
+}
+
+impl Language {
+    pub fn detect(query: &str) -> Self {
+        if query.trim_start().starts_with('$') {
+            Language::JsonPath
+        } else {
+            Language::Jq
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Language::JsonPath => "JSONPath",
+            Language::Jq => "jq",
+        }
+    }
+}
+
+/// Runs `query` against `document`, returning every result in order
+pub fn evaluate(query: &str, document: &Value) -> Result<Vec<Value>, String> {
+    match Language::detect(query) {
+        Language::JsonPath => jsonpath::evaluate(query, document),
+        Language::Jq => jq::evaluate(query, document),
+    }
+}
+
+/// Query error pointing at a character position in the query
+pub(crate) fn syntax_error(position: usize, message: &str) -> String {
+    format!("Query error at position {}: {}", position + 1, message)
+}
+
+/// Name of the value's type as jq reports it
+pub(crate) fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Everything except `false` and `null` counts as true
+pub(crate) fn truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+/// Total order over JSON values as used by jq: null, false, true, numbers,
+/// strings, arrays, objects
+pub(crate) fn compare_values(left: &Value, right: &Value) -> Ordering {
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Null => 0,
+            Value::Bool(false) => 1,
+            Value::Bool(true) => 2,
+            Value::Number(_) => 3,
+            Value::String(_) => 4,
+            Value::Array(_) => 5,
+            Value::Object(_) => 6,
+        }
+    }
+
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => {
+            if same(left, right) {
+                Ordering::Equal
+            } else {
+                let (a, b) = (
+                    a.as_f64().unwrap_or(f64::NAN),
+                    b.as_f64().unwrap_or(f64::NAN),
+                );
+                a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+            }
+        }
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Array(a), Value::Array(b)) => a
+            .iter()
+            .zip(b)
+            .map(|(x, y)| compare_values(x, y))
+            .find(|order| order.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        (Value::Object(a), Value::Object(b)) => {
+            let mut a_keys: Vec<&String> = a.keys().collect();
+            let mut b_keys: Vec<&String> = b.keys().collect();
+            a_keys.sort();
+            b_keys.sort();
+            a_keys.cmp(&b_keys).then_with(|| {
+                a_keys
+                    .iter()
+                    .map(|key| compare_values(&a[key.as_str()], &b[key.as_str()]))
+                    .find(|order| order.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            })
+        }
+        _ => rank(left).cmp(&rank(right)),
+    }
+}
+
+/// Pretty-prints results: a JSON array for JSONPath, one value per result for jq
+pub fn format_results(language: Language, results: Vec<Value>) -> String {
+    match language {
+        Language::JsonPath => {
+            serde_json::to_string_pretty(&Value::Array(results)).unwrap_or_default()
+        }
+        Language::Jq => results
+            .iter()
+            .map(|value| serde_json::to_string_pretty(value).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
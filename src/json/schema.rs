@@ -0,0 +1,1357 @@
+//! JSON Schema validation for Draft 7 and Draft 2020-12.
+//!
+//! Every violation is reported with the JSON Pointer of the offending value
+//! and of the schema keyword that rejected it. References are resolved within
+//! the schema document only; remote schemas are never fetched.
+
+use super::diff::same;
+use super::tree::escape_pointer;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+/// Deepest chain of nested schemas followed before giving up, which stops
+/// self-referencing schemas from recursing forever
+const MAX_DEPTH: usize = 128;
+
+/// Schema dialect, which decides how a few keywords behave
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Draft {
+    Draft7,
+    #[default]
+    Draft2020,
+}
+
+impl Draft {
+    pub const ALL: [Draft; 2] = [Draft::Draft7, Draft::Draft2020];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Draft::Draft7 => "draft7",
+            Draft::Draft2020 => "2020-12",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Draft::Draft7 => "Draft 7",
+            Draft::Draft2020 => "Draft 2020-12",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|draft| draft.id() == id)
+    }
+
+    /// Reads the draft from the schema's `$schema`, defaulting to 2020-12
+    pub fn detect(schema: &Value) -> Self {
+        match schema.get("$schema").and_then(Value::as_str) {
+            Some(uri) if uri.contains("draft-07") || uri.contains("draft-06") => Draft::Draft7,
+            _ => Draft::Draft2020,
+        }
+    }
+}
+
+/// One way in which the instance fails the schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// JSON Pointer of the offending value in the instance
+    pub instance_path: String,
+    /// JSON Pointer of the keyword that rejected it, through any `$ref`s
+    pub schema_path: String,
+    pub message: String,
+}
+
+/// Validates `instance` against `schema`; `check_formats` makes `format`
+/// an assertion rather than an annotation
+pub fn validate(
+    schema: &Value,
+    instance: &Value,
+    draft: Draft,
+    check_formats: bool,
+) -> Vec<Violation> {
+    let mut validator = Validator {
+        draft,
+        check_formats,
+        resources: HashMap::new(),
+        patterns: HashMap::new(),
+        depth: 0,
+    };
+    let base = schema
+        .get("$id")
+        .and_then(Value::as_str)
+        .map(|id| without_fragment(id).to_string())
+        .unwrap_or_default();
+    validator.index(schema, &base);
+    validator
+        .node(schema, instance, &base, &Location::default())
+        .0
+}
+
+/// Instance and schema pointers for the value being validated
+#[derive(Default, Clone)]
+struct Location {
+    instance: String,
+    schema: String,
+}
+
+impl Location {
+    fn keyword(&self, keyword: &str) -> Location {
+        Location {
+            instance: self.instance.clone(),
+            schema: format!("{}/{}", self.schema, escape_pointer(keyword)),
+        }
+    }
+
+    fn child(&self, segment: &str) -> Location {
+        Location {
+            instance: format!("{}/{}", self.instance, escape_pointer(segment)),
+            schema: self.schema.clone(),
+        }
+    }
+
+    fn violation(&self, message: String) -> Violation {
+        Violation {
+            instance_path: self.instance.clone(),
+            schema_path: self.schema.clone(),
+            message,
+        }
+    }
+}
+
+/// Properties and items that some subschema has already looked at, which
+/// `unevaluatedProperties` and `unevaluatedItems` skip
+#[derive(Default)]
+struct Evaluated {
+    properties: HashSet<String>,
+    items: HashSet<usize>,
+}
+
+impl Evaluated {
+    fn merge(&mut self, other: Evaluated) {
+        self.properties.extend(other.properties);
+        self.items.extend(other.items);
+    }
+}
+
+type Outcome = (Vec<Violation>, Evaluated);
+
+struct Validator<'a> {
+    draft: Draft,
+    check_formats: bool,
+    /// Schemas by absolute URI, including `#anchor` forms
+    resources: HashMap<String, &'a Value>,
+    patterns: HashMap<String, Result<Regex, String>>,
+    depth: usize,
+}
+
+impl<'a> Validator<'a> {
+    /// Registers every `$id` and anchor below `schema`
+    fn index(&mut self, schema: &'a Value, base: &str) {
+        match schema {
+            Value::Object(map) => {
+                let mut base = base.to_string();
+                if let Some(id) = map.get("$id").and_then(Value::as_str) {
+                    let resolved = resolve_uri(&base, id);
+                    match resolved.split_once('#') {
+                        // Draft 7 allows plain-name fragments in `$id`
+                        Some((uri, anchor)) if !anchor.is_empty() => {
+                            self.resources.insert(format!("{}#{}", uri, anchor), schema);
+                        }
+                        _ => {
+                            base = without_fragment(&resolved).to_string();
+                            self.resources.insert(base.clone(), schema);
+                        }
+                    }
+                }
+                for keyword in ["$anchor", "$dynamicAnchor"] {
+                    if let Some(anchor) = map.get(keyword).and_then(Value::as_str) {
+                        self.resources
+                            .insert(format!("{}#{}", base, anchor), schema);
+                    }
+                }
+                self.resources.entry(base.clone()).or_insert(schema);
+                for (key, value) in map {
+                    if !matches!(key.as_str(), "enum" | "const" | "default" | "examples") {
+                        self.index(value, &base);
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.index(item, base);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Finds the schema a reference points at, with its base URI
+    fn resolve(&self, base: &str, reference: &str) -> Option<(&'a Value, String)> {
+        let target = resolve_uri(base, reference);
+        let (uri, fragment) = target.split_once('#').unwrap_or((&target, ""));
+        if fragment.is_empty() {
+            return self
+                .resources
+                .get(uri)
+                .map(|schema| (*schema, uri.to_string()));
+        }
+        if fragment.starts_with('/') {
+            let resource = self.resources.get(uri)?;
+            let pointer = percent_decode(fragment);
+            return resource
+                .pointer(&pointer)
+                .map(|schema| (schema, uri.to_string()));
+        }
+        self.resources
+            .get(&target)
+            .map(|schema| (*schema, uri.to_string()))
+    }
+
+    fn regex(&mut self, pattern: &str) -> Result<Regex, String> {
+        self.patterns
+            .entry(pattern.to_string())
+            .or_insert_with(|| {
+                // Syntax errors span several lines with a caret; keep the reason
+                Regex::new(pattern).map_err(|e| {
+                    let message = e.to_string();
+                    message
+                        .lines()
+                        .last()
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string()
+                })
+            })
+            .clone()
+    }
+
+    fn node(&mut self, schema: &'a Value, instance: &Value, base: &str, at: &Location) -> Outcome {
+        let map = match schema {
+            Value::Bool(true) => return (Vec::new(), Evaluated::default()),
+            Value::Bool(false) => {
+                return (
+                    vec![at.violation("No value is allowed here".to_string())],
+                    Evaluated::default(),
+                )
+            }
+            Value::Object(map) => map,
+            _ => {
+                return (
+                    vec![at.violation("Schema must be an object or a boolean".to_string())],
+                    Evaluated::default(),
+                )
+            }
+        };
+
+        if self.depth >= MAX_DEPTH {
+            return (
+                vec![at.violation("Schema nesting is too deep; check for a $ref cycle".to_string())],
+                Evaluated::default(),
+            );
+        }
+        self.depth += 1;
+
+        let base = match map.get("$id").and_then(Value::as_str) {
+            Some(id) if !id.starts_with('#') => {
+                without_fragment(&resolve_uri(base, id)).to_string()
+            }
+            _ => base.to_string(),
+        };
+        let mut errors = Vec::new();
+        let mut evaluated = Evaluated::default();
+
+        let references: &[&str] = match self.draft {
+            Draft::Draft7 => &["$ref"],
+            Draft::Draft2020 => &["$ref", "$dynamicRef"],
+        };
+        for keyword in references {
+            let Some(reference) = map.get(*keyword).and_then(Value::as_str) else {
+                continue;
+            };
+            let at = at.keyword(keyword);
+            match self.resolve(&base, reference) {
+                Some((target, target_base)) => {
+                    let (e, found) = self.node(target, instance, &target_base, &at);
+                    if e.is_empty() {
+                        evaluated.merge(found);
+                    }
+                    errors.extend(e);
+                }
+                None => {
+                    errors.push(at.violation(format!("Cannot resolve reference \"{}\"", reference)))
+                }
+            }
+        }
+        // Before 2019-09, `$ref` replaces every keyword beside it
+        if self.draft == Draft::Draft7 && map.contains_key("$ref") {
+            self.depth -= 1;
+            return (errors, evaluated);
+        }
+
+        self.generic(map, instance, at, &mut errors);
+        match instance {
+            Value::Number(_) => self.numeric(map, instance, at, &mut errors),
+            Value::String(text) => self.string(map, text, at, &mut errors),
+            Value::Array(items) => self.array(map, items, &base, at, &mut errors, &mut evaluated),
+            Value::Object(object) => {
+                self.object(map, object, &base, at, &mut errors, &mut evaluated)
+            }
+            _ => {}
+        }
+        self.combinators(map, instance, &base, at, &mut errors, &mut evaluated);
+
+        // Unevaluated keywords depend on everything above, so they go last
+        if self.draft == Draft::Draft2020 {
+            match instance {
+                Value::Array(items) => {
+                    if let Some(rest) = map.get("unevaluatedItems") {
+                        for (i, item) in items.iter().enumerate() {
+                            if evaluated.items.contains(&i) {
+                                continue;
+                            }
+                            let item_at = at.keyword("unevaluatedItems").child(&i.to_string());
+                            if rest == &Value::Bool(false) {
+                                errors
+                                    .push(item_at.violation(format!("Item {} is not allowed", i)));
+                            } else {
+                                errors.extend(self.node(rest, item, &base, &item_at).0);
+                            }
+                        }
+                        evaluated.items.extend(0..items.len());
+                    }
+                }
+                Value::Object(object) => {
+                    if let Some(rest) = map.get("unevaluatedProperties") {
+                        for (key, value) in object {
+                            if evaluated.properties.contains(key) {
+                                continue;
+                            }
+                            let key_at = at.keyword("unevaluatedProperties").child(key);
+                            if rest == &Value::Bool(false) {
+                                errors.push(
+                                    key_at
+                                        .violation(format!("Property \"{}\" is not allowed", key)),
+                                );
+                            } else {
+                                errors.extend(self.node(rest, value, &base, &key_at).0);
+                            }
+                        }
+                        evaluated.properties.extend(object.keys().cloned());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.depth -= 1;
+        (errors, evaluated)
+    }
+
+    /// `type`, `enum` and `const`
+    fn generic(
+        &mut self,
+        map: &Map<String, Value>,
+        instance: &Value,
+        at: &Location,
+        errors: &mut Vec<Violation>,
+    ) {
+        if let Some(expected) = map.get("type") {
+            let types: Vec<&str> = match expected {
+                Value::String(name) => vec![name.as_str()],
+                Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !types.is_empty() && !types.iter().any(|name| is_type(instance, name)) {
+                errors.push(at.keyword("type").violation(format!(
+                    "Expected {}, got {}",
+                    types.join(" or "),
+                    type_of(instance)
+                )));
+            }
+        }
+        if let Some(Value::Array(options)) = map.get("enum") {
+            if !options.iter().any(|option| same(option, instance)) {
+                let options: Vec<String> = options.iter().map(Value::to_string).collect();
+                errors.push(
+                    at.keyword("enum")
+                        .violation(format!("Value must be one of: {}", options.join(", "))),
+                );
+            }
+        }
+        if let Some(expected) = map.get("const") {
+            if !same(expected, instance) {
+                errors.push(
+                    at.keyword("const")
+                        .violation(format!("Value must equal {}", expected)),
+                );
+            }
+        }
+    }
+
+    fn numeric(
+        &mut self,
+        map: &Map<String, Value>,
+        instance: &Value,
+        at: &Location,
+        errors: &mut Vec<Violation>,
+    ) {
+        let value = instance.as_f64().unwrap_or(f64::NAN);
+        let limit = |keyword: &str| map.get(keyword).and_then(Value::as_f64);
+
+        if let Some(divisor) = limit("multipleOf").filter(|d| *d > 0.0) {
+            let exact = instance
+                .as_i64()
+                .zip(map["multipleOf"].as_i64())
+                .map(|(n, d)| n % d == 0);
+            let multiple = exact.unwrap_or_else(|| {
+                let quotient = value / divisor;
+                quotient.is_finite() && (quotient - quotient.round()).abs() < 1e-9
+            });
+            if !multiple {
+                errors.push(at.keyword("multipleOf").violation(format!(
+                    "{} is not a multiple of {}",
+                    instance, map["multipleOf"]
+                )));
+            }
+        }
+
+        for keyword in ["minimum", "maximum", "exclusiveMinimum", "exclusiveMaximum"] {
+            let Some(bound) = limit(keyword) else {
+                continue;
+            };
+            let (passes, description) = match keyword {
+                "minimum" => (value >= bound, "less than the minimum of"),
+                "maximum" => (value <= bound, "greater than the maximum of"),
+                "exclusiveMinimum" => (value > bound, "not greater than"),
+                _ => (value < bound, "not less than"),
+            };
+            if !passes {
+                errors.push(
+                    at.keyword(keyword)
+                        .violation(format!("{} is {} {}", instance, description, map[keyword])),
+                );
+            }
+        }
+    }
+
+    fn string(
+        &mut self,
+        map: &Map<String, Value>,
+        text: &str,
+        at: &Location,
+        errors: &mut Vec<Violation>,
+    ) {
+        let length = text.chars().count() as u64;
+        if let Some(min) = map.get("minLength").and_then(Value::as_u64) {
+            if length < min {
+                errors.push(at.keyword("minLength").violation(format!(
+                    "String is {} characters, shorter than the minimum of {}",
+                    length, min
+                )));
+            }
+        }
+        if let Some(max) = map.get("maxLength").and_then(Value::as_u64) {
+            if length > max {
+                errors.push(at.keyword("maxLength").violation(format!(
+                    "String is {} characters, longer than the maximum of {}",
+                    length, max
+                )));
+            }
+        }
+        if let Some(pattern) = map.get("pattern").and_then(Value::as_str) {
+            match self.regex(pattern) {
+                Ok(regex) if !regex.is_match(text) => errors.push(
+                    at.keyword("pattern")
+                        .violation(format!("String does not match the pattern \"{}\"", pattern)),
+                ),
+                Ok(_) => {}
+                Err(e) => errors.push(
+                    at.keyword("pattern")
+                        .violation(format!("Invalid pattern in schema: {}", e)),
+                ),
+            }
+        }
+        if self.check_formats {
+            if let Some(format) = map.get("format").and_then(Value::as_str) {
+                if format_matches(format, text) == Some(false) {
+                    errors.push(
+                        at.keyword("format")
+                            .violation(format!("\"{}\" is not a valid {}", text, format)),
+                    );
+                }
+            }
+        }
+    }
+
+    fn array(
+        &mut self,
+        map: &'a Map<String, Value>,
+        items: &[Value],
+        base: &str,
+        at: &Location,
+        errors: &mut Vec<Violation>,
+        evaluated: &mut Evaluated,
+    ) {
+        // Schemas for leading positions, then one for the remaining items
+        let (prefix_keyword, prefix, rest_keyword) = match self.draft {
+            Draft::Draft7 => match map.get("items") {
+                Some(Value::Array(prefix)) => ("items", prefix.as_slice(), "additionalItems"),
+                _ => ("items", &[][..], "items"),
+            },
+            Draft::Draft2020 => (
+                "prefixItems",
+                map.get("prefixItems")
+                    .and_then(Value::as_array)
+                    .map_or(&[][..], Vec::as_slice),
+                "items",
+            ),
+        };
+        for (i, (schema, item)) in prefix.iter().zip(items).enumerate() {
+            let item_at = at
+                .keyword(prefix_keyword)
+                .keyword(&i.to_string())
+                .child(&i.to_string());
+            errors.extend(self.node(schema, item, base, &item_at).0);
+            evaluated.items.insert(i);
+        }
+        if let Some(rest) = map.get(rest_keyword).filter(|rest| !rest.is_array()) {
+            if rest == &Value::Bool(false) && items.len() > prefix.len() {
+                errors.push(at.keyword(rest_keyword).violation(format!(
+                    "Array has {} items; at most {} are allowed",
+                    items.len(),
+                    prefix.len()
+                )));
+            } else {
+                for (i, item) in items.iter().enumerate().skip(prefix.len()) {
+                    let item_at = at.keyword(rest_keyword).child(&i.to_string());
+                    errors.extend(self.node(rest, item, base, &item_at).0);
+                }
+            }
+            evaluated.items.extend(0..items.len());
+        }
+
+        if let Some(contains) = map.get("contains") {
+            let matching: Vec<usize> = items
+                .iter()
+                .enumerate()
+                .filter(|(i, item)| {
+                    let item_at = at.keyword("contains").child(&i.to_string());
+                    self.node(contains, item, base, &item_at).0.is_empty()
+                })
+                .map(|(i, _)| i)
+                .collect();
+            let (min, max) = match self.draft {
+                Draft::Draft7 => (1, None),
+                Draft::Draft2020 => (
+                    map.get("minContains").and_then(Value::as_u64).unwrap_or(1),
+                    map.get("maxContains").and_then(Value::as_u64),
+                ),
+            };
+            let found = matching.len() as u64;
+            if found < min {
+                let keyword = if min == 1 { "contains" } else { "minContains" };
+                errors.push(at.keyword(keyword).violation(if min == 1 {
+                    "Array does not contain a matching item".to_string()
+                } else {
+                    format!(
+                        "Array contains {} matching items, fewer than {}",
+                        found, min
+                    )
+                }));
+            }
+            if let Some(max) = max.filter(|max| found > *max) {
+                errors.push(at.keyword("maxContains").violation(format!(
+                    "Array contains {} matching items, more than {}",
+                    found, max
+                )));
+            }
+            evaluated.items.extend(matching);
+        }
+
+        let count = items.len() as u64;
+        if let Some(min) = map.get("minItems").and_then(Value::as_u64) {
+            if count < min {
+                errors.push(at.keyword("minItems").violation(format!(
+                    "Array has {} items, fewer than the minimum of {}",
+                    count, min
+                )));
+            }
+        }
+        if let Some(max) = map.get("maxItems").and_then(Value::as_u64) {
+            if count > max {
+                errors.push(at.keyword("maxItems").violation(format!(
+                    "Array has {} items, more than the maximum of {}",
+                    count, max
+                )));
+            }
+        }
+        if map.get("uniqueItems") == Some(&Value::Bool(true)) {
+            let duplicate = items.iter().enumerate().find_map(|(i, item)| {
+                items[..i]
+                    .iter()
+                    .position(|earlier| same(earlier, item))
+                    .map(|j| (j, i))
+            });
+            if let Some((first, second)) = duplicate {
+                errors.push(
+                    at.keyword("uniqueItems")
+                        .violation(format!("Items {} and {} are equal", first, second)),
+                );
+            }
+        }
+    }
+
+    fn object(
+        &mut self,
+        map: &'a Map<String, Value>,
+        object: &Map<String, Value>,
+        base: &str,
+        at: &Location,
+        errors: &mut Vec<Violation>,
+        evaluated: &mut Evaluated,
+    ) {
+        if let Some(Value::Array(required)) = map.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(name) {
+                    errors.push(
+                        at.keyword("required")
+                            .violation(format!("Missing required property \"{}\"", name)),
+                    );
+                }
+            }
+        }
+
+        let count = object.len() as u64;
+        if let Some(min) = map.get("minProperties").and_then(Value::as_u64) {
+            if count < min {
+                errors.push(at.keyword("minProperties").violation(format!(
+                    "Object has {} properties, fewer than the minimum of {}",
+                    count, min
+                )));
+            }
+        }
+        if let Some(max) = map.get("maxProperties").and_then(Value::as_u64) {
+            if count > max {
+                errors.push(at.keyword("maxProperties").violation(format!(
+                    "Object has {} properties, more than the maximum of {}",
+                    count, max
+                )));
+            }
+        }
+
+        let mut matched = HashSet::new();
+        if let Some(Value::Object(properties)) = map.get("properties") {
+            for (key, schema) in properties {
+                if let Some(value) = object.get(key) {
+                    let key_at = at.keyword("properties").keyword(key).child(key);
+                    errors.extend(self.node(schema, value, base, &key_at).0);
+                    matched.insert(key.clone());
+                }
+            }
+        }
+        if let Some(Value::Object(patterns)) = map.get("patternProperties") {
+            for (pattern, schema) in patterns {
+                let regex = match self.regex(pattern) {
+                    Ok(regex) => regex,
+                    Err(e) => {
+                        errors.push(
+                            at.keyword("patternProperties")
+                                .keyword(pattern)
+                                .violation(format!("Invalid pattern in schema: {}", e)),
+                        );
+                        continue;
+                    }
+                };
+                for (key, value) in object.iter().filter(|(key, _)| regex.is_match(key)) {
+                    let key_at = at.keyword("patternProperties").keyword(pattern).child(key);
+                    errors.extend(self.node(schema, value, base, &key_at).0);
+                    matched.insert(key.clone());
+                }
+            }
+        }
+        if let Some(rest) = map.get("additionalProperties") {
+            for (key, value) in object.iter().filter(|(key, _)| !matched.contains(*key)) {
+                let key_at = at.keyword("additionalProperties").child(key);
+                if rest == &Value::Bool(false) {
+                    errors.push(key_at.violation(format!("Property \"{}\" is not allowed", key)));
+                } else {
+                    errors.extend(self.node(rest, value, base, &key_at).0);
+                }
+            }
+            matched.extend(object.keys().cloned());
+        }
+        evaluated.properties.extend(matched);
+
+        if let Some(names) = map.get("propertyNames") {
+            for key in object.keys() {
+                let name = Value::String(key.clone());
+                for violation in self
+                    .node(names, &name, base, &at.keyword("propertyNames"))
+                    .0
+                {
+                    errors.push(Violation {
+                        message: format!("Property name \"{}\": {}", key, violation.message),
+                        ..violation
+                    });
+                }
+            }
+        }
+
+        // Draft 7 `dependencies` mixes both forms that 2019-09 split apart
+        let dependencies = match self.draft {
+            Draft::Draft7 => vec![("dependencies", map.get("dependencies"))],
+            Draft::Draft2020 => vec![
+                ("dependentRequired", map.get("dependentRequired")),
+                ("dependentSchemas", map.get("dependentSchemas")),
+            ],
+        };
+        for (keyword, dependency) in dependencies {
+            let Some(Value::Object(dependency)) = dependency else {
+                continue;
+            };
+            for (trigger, requirement) in dependency
+                .iter()
+                .filter(|(key, _)| object.contains_key(*key))
+            {
+                let rule_at = at.keyword(keyword).keyword(trigger);
+                match requirement {
+                    Value::Array(names) if keyword != "dependentSchemas" => {
+                        for name in names.iter().filter_map(Value::as_str) {
+                            if !object.contains_key(name) {
+                                errors.push(rule_at.violation(format!(
+                                    "Property \"{}\" is required when \"{}\" is present",
+                                    name, trigger
+                                )));
+                            }
+                        }
+                    }
+                    schema if keyword != "dependentRequired" => {
+                        let (e, found) =
+                            self.node(schema, &Value::Object(object.clone()), base, &rule_at);
+                        if e.is_empty() {
+                            evaluated.merge(found);
+                        }
+                        errors.extend(e);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// `allOf`, `anyOf`, `oneOf`, `not` and `if`/`then`/`else`
+    fn combinators(
+        &mut self,
+        map: &'a Map<String, Value>,
+        instance: &Value,
+        base: &str,
+        at: &Location,
+        errors: &mut Vec<Violation>,
+        evaluated: &mut Evaluated,
+    ) {
+        if let Some(Value::Array(schemas)) = map.get("allOf") {
+            for (i, schema) in schemas.iter().enumerate() {
+                let (e, found) = self.node(
+                    schema,
+                    instance,
+                    base,
+                    &at.keyword("allOf").keyword(&i.to_string()),
+                );
+                if e.is_empty() {
+                    evaluated.merge(found);
+                }
+                errors.extend(e);
+            }
+        }
+
+        for keyword in ["anyOf", "oneOf"] {
+            let Some(Value::Array(schemas)) = map.get(keyword) else {
+                continue;
+            };
+            let mut passing = Vec::new();
+            for (i, schema) in schemas.iter().enumerate() {
+                let (e, found) = self.node(
+                    schema,
+                    instance,
+                    base,
+                    &at.keyword(keyword).keyword(&i.to_string()),
+                );
+                if e.is_empty() {
+                    passing.push(i);
+                    evaluated.merge(found);
+                }
+            }
+            if passing.is_empty() {
+                errors.push(at.keyword(keyword).violation(format!(
+                    "Value does not match any of the {} {} schemas",
+                    schemas.len(),
+                    keyword
+                )));
+            } else if keyword == "oneOf" && passing.len() > 1 {
+                let indices: Vec<String> = passing.iter().map(usize::to_string).collect();
+                errors.push(at.keyword(keyword).violation(format!(
+                    "Value matches more than one oneOf schema (indexes {})",
+                    indices.join(", ")
+                )));
+            }
+        }
+
+        if let Some(schema) = map.get("not") {
+            if self
+                .node(schema, instance, base, &at.keyword("not"))
+                .0
+                .is_empty()
+            {
+                errors.push(
+                    at.keyword("not")
+                        .violation("Value must not match the schema in \"not\"".to_string()),
+                );
+            }
+        }
+
+        if let Some(condition) = map.get("if") {
+            let (e, found) = self.node(condition, instance, base, &at.keyword("if"));
+            let branch = if e.is_empty() {
+                evaluated.merge(found);
+                "then"
+            } else {
+                "else"
+            };
+            if let Some(schema) = map.get(branch) {
+                let (e, found) = self.node(schema, instance, base, &at.keyword(branch));
+                if e.is_empty() {
+                    evaluated.merge(found);
+                }
+                errors.extend(e);
+            }
+        }
+    }
+}
+
+fn is_type(value: &Value, name: &str) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        _ => false,
+    }
+}
+
+fn type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) if is_type(value, "integer") => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Whether `text` is valid for `format`; `None` for formats not checked
+fn format_matches(format: &str, text: &str) -> Option<bool> {
+    let digits = |s: &str, n: usize| s.len() == n && s.bytes().all(|b| b.is_ascii_digit());
+    let date = |s: &str| {
+        let parts: Vec<&str> = s.split('-').collect();
+        parts.len() == 3
+            && digits(parts[0], 4)
+            && digits(parts[1], 2)
+            && digits(parts[2], 2)
+            && chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok()
+    };
+    let time = |s: &str| {
+        let upper = s.to_ascii_uppercase();
+        let (clock, offset) = match upper.find(['Z', '+', '-']) {
+            Some(i) => upper.split_at(i),
+            None => return false,
+        };
+        let clock = clock.split('.').next().unwrap_or_default();
+        let parts: Vec<&str> = clock.split(':').collect();
+        let offset_ok = offset == "Z" || {
+            let rest = &offset[1..];
+            rest.len() == 5 && digits(&rest[..2], 2) && &rest[2..3] == ":" && digits(&rest[3..], 2)
+        };
+        parts.len() == 3
+            && parts.iter().all(|p| digits(p, 2))
+            && parts[0] < "24"
+            && parts[1] < "60"
+            && parts[2] <= "60"
+            && offset_ok
+    };
+
+    Some(match format {
+        "date-time" => text
+            .split_once(['T', 't'])
+            .is_some_and(|(d, t)| date(d) && time(t)),
+        "date" => date(text),
+        "time" => time(text),
+        "email" => text.split_once('@').is_some_and(|(local, domain)| {
+            !local.is_empty()
+                && !domain.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !text.contains(char::is_whitespace)
+        }),
+        "hostname" => {
+            !text.is_empty()
+                && text.len() <= 253
+                && text.trim_end_matches('.').split('.').all(|label| {
+                    (1..=63).contains(&label.len())
+                        && !label.starts_with('-')
+                        && !label.ends_with('-')
+                        && label
+                            .bytes()
+                            .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+                })
+        }
+        "ipv4" => text.parse::<std::net::Ipv4Addr>().is_ok(),
+        "ipv6" => text.parse::<std::net::Ipv6Addr>().is_ok(),
+        "uri" => text.split_once(':').is_some_and(|(scheme, rest)| {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+                && !rest.is_empty()
+                && !text.contains(char::is_whitespace)
+        }),
+        "uuid" => {
+            let groups: Vec<&str> = text.split('-').collect();
+            groups.len() == 5
+                && groups
+                    .iter()
+                    .zip([8, 4, 4, 4, 12])
+                    .all(|(g, n)| g.len() == n && g.bytes().all(|b| b.is_ascii_hexdigit()))
+        }
+        "regex" => Regex::new(text).is_ok(),
+        "json-pointer" => text.is_empty() || text.starts_with('/'),
+        _ => return None,
+    })
+}
+
+fn without_fragment(uri: &str) -> &str {
+    uri.split_once('#').map_or(uri, |(uri, _)| uri)
+}
+
+/// Resolves `reference` against `base`, enough for `$id`/`$ref` within one document
+fn resolve_uri(base: &str, reference: &str) -> String {
+    let base = without_fragment(base);
+    if reference.contains("://") || reference.starts_with("urn:") {
+        return reference.to_string();
+    }
+    if reference.starts_with('#') || reference.is_empty() {
+        return format!("{}{}", base, reference);
+    }
+    if reference.starts_with('/') {
+        let origin_end = base
+            .find("://")
+            .and_then(|i| base[i + 3..].find('/').map(|j| i + 3 + j))
+            .unwrap_or(base.len());
+        return format!("{}{}", &base[..origin_end], reference);
+    }
+    let directory = base.rfind('/').map_or("", |i| &base[..=i]);
+    format!("{}{}", directory, reference)
+}
+
+/// Decodes `%XX` escapes in a URI fragment
+fn percent_decode(text: &str) -> String {
+    urlencoding::decode(text).map_or(text.to_string(), |decoded| decoded.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Runs cases in the shape of the JSON-Schema-Test-Suite: a schema and
+    /// instances with whether each is valid
+    fn suite(
+        draft: Draft,
+        check_formats: bool,
+        description: &str,
+        schema: Value,
+        tests: &[(Value, bool)],
+    ) {
+        for (instance, valid) in tests {
+            let violations = validate(&schema, instance, draft, check_formats);
+            assert_eq!(
+                violations.is_empty(),
+                *valid,
+                "{} ({}): {} gave {:?}",
+                description,
+                draft.label(),
+                instance,
+                violations
+            );
+        }
+    }
+
+    #[test]
+    fn ref_draft7() {
+        let d = Draft::Draft7;
+        suite(
+            d,
+            false,
+            "root pointer ref",
+            json!({"properties": {"foo": {"$ref": "#"}}, "additionalProperties": false}),
+            &[
+                (json!({"foo": false}), true),
+                (json!({"foo": {"foo": false}}), true),
+                (json!({"bar": false}), false),
+                (json!({"foo": {"bar": false}}), false),
+            ],
+        );
+        suite(
+            d,
+            false,
+            "relative pointer ref to object",
+            json!({"properties": {"foo": {"type": "integer"}, "bar": {"$ref": "#/properties/foo"}}}),
+            &[(json!({"bar": 3}), true), (json!({"bar": true}), false)],
+        );
+        suite(
+            d,
+            false,
+            "escaped pointer ref",
+            json!({
+                "definitions": {
+                    "tilde~field": {"type": "integer"},
+                    "slash/field": {"type": "integer"},
+                    "percent%field": {"type": "integer"}
+                },
+                "properties": {
+                    "tilde": {"$ref": "#/definitions/tilde~0field"},
+                    "slash": {"$ref": "#/definitions/slash~1field"},
+                    "percent": {"$ref": "#/definitions/percent%25field"}
+                }
+            }),
+            &[
+                (json!({"slash": "aoeu"}), false),
+                (json!({"tilde": "aoeu"}), false),
+                (json!({"percent": "aoeu"}), false),
+                (json!({"slash": 123}), true),
+                (json!({"tilde": 123}), true),
+                (json!({"percent": 123}), true),
+            ],
+        );
+        suite(
+            d,
+            false,
+            "ref overrides any sibling keywords",
+            json!({
+                "definitions": {"reffed": {"type": "array"}},
+                "properties": {"foo": {"$ref": "#/definitions/reffed", "maxItems": 2}}
+            }),
+            &[
+                (json!({"foo": []}), true),
+                (json!({"foo": [1, 2, 3]}), true),
+                (json!({"foo": "string"}), false),
+            ],
+        );
+        suite(
+            d,
+            false,
+            "$ref to boolean schema false",
+            json!({"allOf": [{"$ref": "#/definitions/bool"}], "definitions": {"bool": false}}),
+            &[(json!("foo"), false)],
+        );
+        suite(
+            d,
+            false,
+            "Location-independent identifier",
+            json!({"allOf": [{"$ref": "#foo"}], "definitions": {"A": {"$id": "#foo", "type": "integer"}}}),
+            &[(json!(1), true), (json!("a"), false)],
+        );
+    }
+
+    #[test]
+    fn ref_2020() {
+        let d = Draft::Draft2020;
+        suite(
+            d,
+            false,
+            "ref applies alongside sibling keywords",
+            json!({
+                "$defs": {"reffed": {"type": "array"}},
+                "properties": {"foo": {"$ref": "#/$defs/reffed", "maxItems": 2}}
+            }),
+            &[
+                (json!({"foo": []}), true),
+                (json!({"foo": [1, 2, 3]}), false),
+                (json!({"foo": "string"}), false),
+            ],
+        );
+        suite(
+            d,
+            false,
+            "nested refs",
+            json!({
+                "$defs": {
+                    "a": {"type": "integer"},
+                    "b": {"$ref": "#/$defs/a"},
+                    "c": {"$ref": "#/$defs/b"}
+                },
+                "$ref": "#/$defs/c"
+            }),
+            &[(json!(5), true), (json!("a"), false)],
+        );
+        suite(
+            d,
+            false,
+            "Location-independent identifier",
+            json!({"$ref": "#foo", "$defs": {"A": {"$anchor": "foo", "type": "integer"}}}),
+            &[(json!(1), true), (json!("a"), false)],
+        );
+        suite(
+            d,
+            false,
+            "$id with a relative $ref",
+            json!({
+                "$id": "https://example.com/root.json",
+                "$ref": "item.json",
+                "$defs": {"item": {"$id": "item.json", "type": "string"}}
+            }),
+            &[(json!("a"), true), (json!(1), false)],
+        );
+    }
+
+    #[test]
+    fn any_of() {
+        for d in Draft::ALL {
+            suite(
+                d,
+                false,
+                "anyOf",
+                json!({"anyOf": [{"type": "integer"}, {"minimum": 2}]}),
+                &[
+                    (json!(1), true),
+                    (json!(2.5), true),
+                    (json!(3), true),
+                    (json!(1.5), false),
+                ],
+            );
+            suite(
+                d,
+                false,
+                "anyOf with base schema",
+                json!({"type": "string", "anyOf": [{"maxLength": 2}, {"minLength": 4}]}),
+                &[
+                    (json!(3), false),
+                    (json!("foobar"), true),
+                    (json!("foo"), false),
+                ],
+            );
+            suite(
+                d,
+                false,
+                "anyOf with boolean schemas, some true",
+                json!({"anyOf": [true, false]}),
+                &[(json!("foo"), true)],
+            );
+            suite(
+                d,
+                false,
+                "anyOf with boolean schemas, all false",
+                json!({"anyOf": [false, false]}),
+                &[(json!("foo"), false)],
+            );
+            suite(
+                d,
+                false,
+                "nested anyOf",
+                json!({"anyOf": [{"anyOf": [{"type": "null"}]}]}),
+                &[(json!(null), true), (json!(123), false)],
+            );
+        }
+    }
+
+    #[test]
+    fn one_of() {
+        for d in Draft::ALL {
+            suite(
+                d,
+                false,
+                "oneOf",
+                json!({"oneOf": [{"type": "integer"}, {"minimum": 2}]}),
+                &[
+                    (json!(1), true),
+                    (json!(2.5), true),
+                    (json!(3), false),
+                    (json!(1.5), false),
+                ],
+            );
+            suite(
+                d,
+                false,
+                "oneOf with base schema",
+                json!({"type": "string", "oneOf": [{"minLength": 2}, {"maxLength": 4}]}),
+                &[
+                    (json!(3), false),
+                    (json!("foobar"), true),
+                    (json!("foo"), false),
+                ],
+            );
+            suite(
+                d,
+                false,
+                "oneOf with boolean schemas, more than one true",
+                json!({"oneOf": [true, true, false]}),
+                &[(json!("foo"), false)],
+            );
+            suite(
+                d,
+                false,
+                "oneOf with required",
+                json!({
+                    "type": "object",
+                    "oneOf": [{"required": ["foo", "bar"]}, {"required": ["foo", "baz"]}]
+                }),
+                &[
+                    (json!({"bar": 2}), false),
+                    (json!({"foo": 1, "bar": 2}), true),
+                    (json!({"foo": 1, "baz": 3}), true),
+                    (json!({"foo": 1, "bar": 2, "baz": 3}), false),
+                ],
+            );
+        }
+    }
+
+    #[test]
+    fn additional_properties() {
+        for d in Draft::ALL {
+            suite(
+                d,
+                false,
+                "additionalProperties being false does not allow other properties",
+                json!({
+                    "properties": {"foo": {}, "bar": {}},
+                    "patternProperties": {"^v": {}},
+                    "additionalProperties": false
+                }),
+                &[
+                    (json!({"foo": 1}), true),
+                    (json!({"foo": 1, "bar": 2, "quux": "boom"}), false),
+                    (json!([1, 2, 3]), true),
+                    (json!("foobarbaz"), true),
+                    (json!({"foo": 1, "vroom": 2}), true),
+                ],
+            );
+            suite(
+                d,
+                false,
+                "additionalProperties allows a schema which should validate",
+                json!({
+                    "properties": {"foo": {}, "bar": {}},
+                    "additionalProperties": {"type": "boolean"}
+                }),
+                &[
+                    (json!({"foo": 1, "bar": 2, "quux": true}), true),
+                    (json!({"foo": 1, "bar": 2, "quux": 12}), false),
+                ],
+            );
+            suite(
+                d,
+                false,
+                "additionalProperties are allowed by default",
+                json!({"properties": {"foo": {}, "bar": {}}}),
+                &[(json!({"foo": 1, "bar": 2, "quux": true}), true)],
+            );
+            suite(
+                d,
+                false,
+                "additionalProperties does not look in applicators",
+                json!({
+                    "allOf": [{"properties": {"foo": {}}}],
+                    "additionalProperties": {"type": "boolean"}
+                }),
+                &[(json!({"foo": 1, "bar": true}), false)],
+            );
+        }
+    }
+
+    #[test]
+    fn format() {
+        for d in Draft::ALL {
+            let cases: [(&str, &[(Value, bool)]); 5] = [
+                (
+                    "email",
+                    &[
+                        (json!("joe.bloggs@example.com"), true),
+                        (json!("2962"), false),
+                        (json!(12), true),
+                    ],
+                ),
+                (
+                    "ipv4",
+                    &[
+                        (json!("192.168.0.1"), true),
+                        (json!("127.0.0.0.1"), false),
+                        (json!("256.256.256.256"), false),
+                    ],
+                ),
+                (
+                    "date-time",
+                    &[
+                        (json!("1963-06-19T08:30:06.283185Z"), true),
+                        (json!("1963-06-19t08:30:06.283185z"), true),
+                        (json!("06/19/1963 08:30:06 PST"), false),
+                        (json!("2013-350T01:01:01"), false),
+                    ],
+                ),
+                (
+                    "uuid",
+                    &[
+                        (json!("2EB8AA08-AA98-11EA-B4AA-73B441D16380"), true),
+                        (json!("2eb8aa08-aa98-11ea-b4aa-73b441d1638"), false),
+                        (json!("2eb8aa08aa9811eab4aa73b441d16380"), false),
+                    ],
+                ),
+                (
+                    "uri",
+                    &[
+                        (json!("http://foo.bar/?baz=qux#quux"), true),
+                        (json!("urn:isbn:0451450523"), true),
+                        (json!("//foo.bar/?baz=qux#quux"), false),
+                    ],
+                ),
+            ];
+            for (format, tests) in cases {
+                suite(d, true, format, json!({"format": format}), tests);
+            }
+
+            // Without assertion, format is only an annotation
+            suite(
+                d,
+                false,
+                "format as annotation",
+                json!({"format": "email"}),
+                &[(json!("2962"), true)],
+            );
+        }
+    }
+
+    #[test]
+    fn violation_paths() {
+        let schema = json!({
+            "$defs": {"age": {"type": "integer", "minimum": 0}},
+            "properties": {"people": {"items": {"properties": {"age": {"$ref": "#/$defs/age"}}}}}
+        });
+        let violations = validate(
+            &schema,
+            &json!({"people": [{"age": 3}, {"age": -1}]}),
+            Draft::Draft2020,
+            false,
+        );
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].instance_path, "/people/1/age");
+        assert!(
+            violations[0].schema_path.ends_with("/minimum"),
+            "{}",
+            violations[0].schema_path
+        );
+    }
+}
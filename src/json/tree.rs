@@ -0,0 +1,195 @@
+//! Flattening JSON values into the visible rows of a collapsible tree.
+//!
+//! Only expanded containers contribute children, so the cost of a render is
+//! proportional to what is on screen rather than to the document size.
+
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Value type shown as a badge next to each node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    Object,
+    Array,
+    String,
+    Number,
+    Bool,
+    Null,
+}
+
+impl ValueKind {
+    pub fn of(value: &Value) -> Self {
+        match value {
+            Value::Object(_) => ValueKind::Object,
+            Value::Array(_) => ValueKind::Array,
+            Value::String(_) => ValueKind::String,
+            Value::Number(_) => ValueKind::Number,
+            Value::Bool(_) => ValueKind::Bool,
+            Value::Null => ValueKind::Null,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ValueKind::Object => "object",
+            ValueKind::Array => "array",
+            ValueKind::String => "string",
+            ValueKind::Number => "number",
+            ValueKind::Bool => "bool",
+            ValueKind::Null => "null",
+        }
+    }
+}
+
+/// One visible line of the tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeRow {
+    /// JSONPath of the node, e.g. `$.items[3].id`
+    pub path: String,
+    /// JSON Pointer of the node, used to look the value up again
+    pub pointer: String,
+    pub depth: usize,
+    /// Object key or array index; `None` for the root
+    pub label: Option<String>,
+    pub kind: ValueKind,
+    /// Number of children for objects and arrays
+    pub child_count: Option<usize>,
+    pub expanded: bool,
+    /// Short rendering of scalar values
+    pub preview: String,
+}
+
+/// Longest scalar preview before it is truncated
+const PREVIEW_CHARS: usize = 120;
+
+/// Collects up to `limit` visible rows, returning them and whether more exist
+pub fn visible_rows(
+    value: &Value,
+    expanded: &HashSet<String>,
+    limit: usize,
+) -> (Vec<TreeRow>, bool) {
+    let mut rows = Vec::new();
+    let mut truncated = false;
+    // Depth-first walk with an explicit stack to cope with deep documents
+    let mut stack = vec![(value, "$".to_string(), String::new(), 0, None)];
+
+    while let Some((node, path, pointer, depth, label)) = stack.pop() {
+        if rows.len() >= limit {
+            truncated = true;
+            break;
+        }
+
+        let kind = ValueKind::of(node);
+        let is_expanded = expanded.contains(&path);
+        let child_count = match node {
+            Value::Object(map) => Some(map.len()),
+            Value::Array(items) => Some(items.len()),
+            _ => None,
+        };
+
+        if is_expanded {
+            // Push in reverse so children pop in document order
+            match node {
+                Value::Object(map) => {
+                    for (key, child) in map.iter().rev() {
+                        stack.push((
+                            child,
+                            format!("{}{}", path, path_segment(key)),
+                            format!("{}/{}", pointer, escape_pointer(key)),
+                            depth + 1,
+                            Some(key.clone()),
+                        ));
+                    }
+                }
+                Value::Array(items) => {
+                    for (i, child) in items.iter().enumerate().rev() {
+                        stack.push((
+                            child,
+                            format!("{}[{}]", path, i),
+                            format!("{}/{}", pointer, i),
+                            depth + 1,
+                            Some(format!("[{}]", i)),
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        rows.push(TreeRow {
+            preview: scalar_preview(node),
+            path,
+            pointer,
+            depth,
+            label,
+            kind,
+            child_count,
+            expanded: is_expanded,
+        });
+    }
+
+    (rows, truncated)
+}
+
+/// JSONPath of every object and array in `value`, for "expand all"
+pub fn container_paths(value: &Value) -> HashSet<String> {
+    let mut paths = HashSet::new();
+    let mut stack = vec![(value, "$".to_string())];
+    while let Some((node, path)) = stack.pop() {
+        match node {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    if child.is_object() || child.is_array() {
+                        stack.push((child, format!("{}{}", path, path_segment(key))));
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for (i, child) in items.iter().enumerate() {
+                    if child.is_object() || child.is_array() {
+                        stack.push((child, format!("{}[{}]", path, i)));
+                    }
+                }
+            }
+            _ => continue,
+        }
+        paths.insert(path);
+    }
+    paths
+}
+
+/// `.key` for identifier-like keys, `['key']` otherwise
+pub fn path_segment(key: &str) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        format!(".{}", key)
+    } else {
+        format!("['{}']", key.replace('\\', "\\\\").replace('\'', "\\'"))
+    }
+}
+
+/// Escapes a key for use in a JSON Pointer (RFC 6901)
+pub fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn scalar_preview(value: &Value) -> String {
+    match value {
+        Value::Object(_) | Value::Array(_) => String::new(),
+        scalar => {
+            let text = scalar.to_string();
+            if text.chars().count() > PREVIEW_CHARS {
+                let cut: String = text.chars().take(PREVIEW_CHARS).collect();
+                format!("{}…", cut)
+            } else {
+                text
+            }
+        }
+    }
+}
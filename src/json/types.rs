@@ -0,0 +1,565 @@
+//! Inferring Rust structs and TypeScript interfaces from sample JSON.
+//!
+//! Array elements are merged into one shape, so a field counts as optional
+//! when some samples lack it and as nullable when any sample holds `null`.
+
+use serde_json::Value;
+use std::fmt::Write;
+
+/// Language to generate types for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Rust,
+    TypeScript,
+}
+
+impl Target {
+    pub const ALL: [Target; 2] = [Target::Rust, Target::TypeScript];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Target::Rust => "rust",
+            Target::TypeScript => "typescript",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Target::Rust => "Rust",
+            Target::TypeScript => "TypeScript",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|target| target.id() == id)
+    }
+}
+
+/// Source code for types describing `value`, with the top-level type named
+/// after `root_name`
+pub fn generate(value: &Value, root_name: &str, target: Target) -> String {
+    let mut generator = Generator {
+        target,
+        types: Vec::new(),
+    };
+    let shape = infer(value);
+    let root = pascal_case(root_name, "Root");
+
+    let mut out = String::new();
+    if target == Target::Rust {
+        out.push_str("use serde::{Deserialize, Serialize};\n\n");
+    }
+    match &shape.kind {
+        Kind::Object(fields) if !fields.is_empty() => {
+            generator.register(&root, fields);
+        }
+        _ => {
+            // Arrays and scalars at the top level become an alias
+            let expression = generator.expression(&shape, &root);
+            let _ = match target {
+                Target::Rust => writeln!(out, "pub type {} = {};\n", root, expression),
+                Target::TypeScript => writeln!(out, "export type {} = {};\n", root, expression),
+            };
+        }
+    }
+
+    // Rendering a type may register the nested types it refers to
+    let mut i = 0;
+    while i < generator.types.len() {
+        let (name, fields) = generator.types[i].clone();
+        out.push_str(&generator.render(&name, &fields));
+        out.push('\n');
+        i += 1;
+    }
+    out.trim_end().to_string() + "\n"
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    /// No samples, as in the elements of an empty array
+    Unknown,
+    Null,
+    Bool,
+    /// `unsigned` once a sample is too large for `i64`
+    Integer {
+        unsigned: bool,
+    },
+    Float,
+    String,
+    Array(Box<Shape>),
+    Object(Vec<Field>),
+    /// Samples of incompatible types
+    Any,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Shape {
+    kind: Kind,
+    nullable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Field {
+    key: String,
+    shape: Shape,
+    /// Missing from at least one sample
+    optional: bool,
+}
+
+fn infer(value: &Value) -> Shape {
+    let kind = match value {
+        Value::Null => Kind::Null,
+        Value::Bool(_) => Kind::Bool,
+        Value::Number(n) if n.is_i64() => Kind::Integer { unsigned: false },
+        Value::Number(n) if n.is_u64() => Kind::Integer { unsigned: true },
+        Value::Number(_) => Kind::Float,
+        Value::String(_) => Kind::String,
+        Value::Array(items) => {
+            let element = items.iter().map(infer).fold(
+                Shape {
+                    kind: Kind::Unknown,
+                    nullable: false,
+                },
+                merge,
+            );
+            Kind::Array(Box::new(element))
+        }
+        Value::Object(map) => Kind::Object(
+            map.iter()
+                .map(|(key, value)| Field {
+                    key: key.clone(),
+                    shape: infer(value),
+                    optional: false,
+                })
+                .collect(),
+        ),
+    };
+    Shape {
+        nullable: kind == Kind::Null,
+        kind,
+    }
+}
+
+/// The narrowest shape that covers both samples
+fn merge(left: Shape, right: Shape) -> Shape {
+    let nullable = left.nullable || right.nullable;
+    let kind = match (left.kind, right.kind) {
+        (Kind::Unknown, kind) | (kind, Kind::Unknown) => kind,
+        (Kind::Null, kind) | (kind, Kind::Null) => kind,
+        (Kind::Integer { unsigned: a }, Kind::Integer { unsigned: b }) => {
+            Kind::Integer { unsigned: a || b }
+        }
+        (Kind::Integer { .. } | Kind::Float, Kind::Integer { .. } | Kind::Float) => Kind::Float,
+        (Kind::Array(a), Kind::Array(b)) => Kind::Array(Box::new(merge(*a, *b))),
+        (Kind::Object(a), Kind::Object(b)) => Kind::Object(merge_fields(a, b)),
+        (a, b) if a == b => a,
+        _ => Kind::Any,
+    };
+    Shape { kind, nullable }
+}
+
+fn merge_fields(left: Vec<Field>, right: Vec<Field>) -> Vec<Field> {
+    let mut right: Vec<Option<Field>> = right.into_iter().map(Some).collect();
+    let mut fields: Vec<Field> = left
+        .into_iter()
+        .map(|field| {
+            let other = right
+                .iter_mut()
+                .find(|other| other.as_ref().is_some_and(|o| o.key == field.key))
+                .and_then(Option::take);
+            match other {
+                Some(other) => Field {
+                    key: field.key,
+                    shape: merge(field.shape, other.shape),
+                    optional: field.optional || other.optional,
+                },
+                None => Field {
+                    optional: true,
+                    ..field
+                },
+            }
+        })
+        .collect();
+    fields.extend(right.into_iter().flatten().map(|field| Field {
+        optional: true,
+        ..field
+    }));
+    fields
+}
+
+struct Generator {
+    target: Target,
+    /// Named object types in the order they were first referenced
+    types: Vec<(String, Vec<Field>)>,
+}
+
+impl Generator {
+    /// Name for an object type, reusing an identical type with the same base name
+    fn register(&mut self, base: &str, fields: &[Field]) -> String {
+        let mut name = base.to_string();
+        let mut suffix = 2;
+        while let Some((_, existing)) = self.types.iter().find(|(n, _)| *n == name) {
+            if existing == fields {
+                return name;
+            }
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+        self.types.push((name.clone(), fields.to_vec()));
+        name
+    }
+
+    /// Type expression for `shape`; `hint` names any object type it needs
+    fn expression(&mut self, shape: &Shape, hint: &str) -> String {
+        let rust = self.target == Target::Rust;
+        let inner = match &shape.kind {
+            Kind::Unknown | Kind::Any | Kind::Null if rust => "serde_json::Value".to_string(),
+            Kind::Unknown | Kind::Any => "unknown".to_string(),
+            Kind::Null => return "null".to_string(),
+            Kind::Bool if rust => "bool".to_string(),
+            Kind::Bool => "boolean".to_string(),
+            Kind::Integer { unsigned } if rust => if *unsigned { "u64" } else { "i64" }.to_string(),
+            Kind::Float if rust => "f64".to_string(),
+            Kind::Integer { .. } | Kind::Float => "number".to_string(),
+            Kind::String if rust => "String".to_string(),
+            Kind::String => "string".to_string(),
+            Kind::Array(element) => {
+                let element_type = self.expression(element, &singular(hint));
+                if rust {
+                    format!("Vec<{}>", element_type)
+                } else if element_type.contains(' ') {
+                    format!("({})[]", element_type)
+                } else {
+                    format!("{}[]", element_type)
+                }
+            }
+            Kind::Object(fields) if fields.is_empty() => if rust {
+                "serde_json::Map<String, serde_json::Value>"
+            } else {
+                "Record<string, unknown>"
+            }
+            .to_string(),
+            Kind::Object(fields) => self.register(hint, fields),
+        };
+        match (shape.nullable, rust) {
+            (true, true) => format!("Option<{}>", inner),
+            (true, false) => format!("{} | null", inner),
+            (false, _) => inner,
+        }
+    }
+
+    fn render(&mut self, name: &str, fields: &[Field]) -> String {
+        match self.target {
+            Target::Rust => self.render_rust(name, fields),
+            Target::TypeScript => self.render_typescript(name, fields),
+        }
+    }
+
+    fn render_rust(&mut self, name: &str, fields: &[Field]) -> String {
+        let mut out = String::new();
+        let mut used: Vec<String> = Vec::new();
+        let _ = writeln!(out, "#[derive(Debug, Clone, Serialize, Deserialize)]");
+        let _ = writeln!(out, "pub struct {} {{", name);
+        for field in fields {
+            let mut ident = rust_field_name(&field.key);
+            let base = ident.clone();
+            let mut suffix = 2;
+            while used.contains(&ident) {
+                ident = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+            used.push(ident.clone());
+
+            let mut shape = field.shape.clone();
+            shape.nullable |= field.optional;
+            let ty = self.expression(&shape, &pascal_case(&field.key, "Field"));
+
+            let mut attributes = Vec::new();
+            if ident.trim_start_matches("r#") != field.key {
+                attributes.push(format!("rename = {}", Value::String(field.key.clone())));
+            }
+            if field.optional {
+                attributes.push("skip_serializing_if = \"Option::is_none\"".to_string());
+            }
+            if !attributes.is_empty() {
+                let _ = writeln!(out, "    #[serde({})]", attributes.join(", "));
+            }
+            let _ = writeln!(out, "    pub {}: {},", ident, ty);
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn render_typescript(&mut self, name: &str, fields: &[Field]) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "export interface {} {{", name);
+        for field in fields {
+            let ty = self.expression(&field.shape, &pascal_case(&field.key, "Field"));
+            let key = if is_ts_identifier(&field.key) {
+                field.key.clone()
+            } else {
+                Value::String(field.key.clone()).to_string()
+            };
+            let optional = if field.optional { "?" } else { "" };
+            let _ = writeln!(out, "  {}{}: {};", key, optional, ty);
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Splits a key into lowercase words at separators and case changes
+fn words(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let previous = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1);
+        // Break before an uppercase letter that follows a lowercase one or
+        // digit, and before the last capital of an acronym ("HTTPServer")
+        let boundary = c.is_uppercase()
+            && previous.is_some_and(|p| {
+                p.is_lowercase()
+                    || p.is_ascii_digit()
+                    || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase()))
+            });
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// `PascalCase` form of `text`, or `fallback` when it has no usable characters
+fn pascal_case(text: &str, fallback: &str) -> String {
+    let name: String = words(text)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect();
+    match name.chars().next() {
+        None => fallback.to_string(),
+        Some(first) if first.is_ascii_digit() => format!("{}{}", fallback, name),
+        Some(_) => name,
+    }
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "try", "type", "unsafe", "use", "where",
+    "while", "abstract", "become", "box", "do", "final", "macro", "override", "priv", "typeof",
+    "unsized", "virtual", "yield",
+];
+
+/// `snake_case` field name for `key`, escaped if it is a keyword
+fn rust_field_name(key: &str) -> String {
+    let name = words(key).join("_");
+    let name = match name.chars().next() {
+        None => "field".to_string(),
+        Some(first) if first.is_ascii_digit() => format!("field_{}", name),
+        Some(_) => name,
+    };
+    if RUST_KEYWORDS.contains(&name.as_str()) {
+        format!("r#{}", name)
+    } else if matches!(name.as_str(), "self" | "super" | "crate") {
+        // These cannot be raw identifiers
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+fn is_ts_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Rough singular of a `PascalCase` name, for array element types
+fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if name.ends_with("ss") || name.ends_with("us") || name.ends_with("is") {
+        format!("{}Item", name)
+    } else if let Some(stem) = name
+        .strip_suffix("es")
+        .filter(|s| s.ends_with("ch") || s.ends_with("sh") || s.ends_with('x') || s.ends_with("ss"))
+    {
+        stem.to_string()
+    } else if let Some(stem) = name.strip_suffix('s').filter(|s| !s.is_empty()) {
+        stem.to_string()
+    } else {
+        format!("{}Item", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rust(json: &str) -> String {
+        generate(&serde_json::from_str(json).unwrap(), "root", Target::Rust)
+    }
+
+    fn typescript(json: &str) -> String {
+        generate(
+            &serde_json::from_str(json).unwrap(),
+            "root",
+            Target::TypeScript,
+        )
+    }
+
+    const SAMPLES: &str =
+        r#"[{"id": 1, "name": "a", "tag": null}, {"id": 2, "tag": "x", "score": 1.5}]"#;
+
+    #[test]
+    fn optional_fields() {
+        assert_eq!(
+            rust(SAMPLES),
+            "use serde::{Deserialize, Serialize};
+
+pub type Root = Vec<RootItem>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RootItem {
+    pub id: i64,
+    #[serde(skip_serializing_if = \"Option::is_none\")]
+    pub name: Option<String>,
+    pub tag: Option<String>,
+    #[serde(skip_serializing_if = \"Option::is_none\")]
+    pub score: Option<f64>,
+}
+"
+        );
+    }
+
+    #[test]
+    fn field_names() {
+        let out = rust(
+            r#"{"userId": 1, "HTTPServer": "h", "first-name": "x", "user_id": 3, "123": 2, "": 1}"#,
+        );
+        assert!(out.contains("    #[serde(rename = \"userId\")]\n    pub user_id: i64,\n"));
+        assert!(
+            out.contains("    #[serde(rename = \"HTTPServer\")]\n    pub http_server: String,\n")
+        );
+        assert!(
+            out.contains("    #[serde(rename = \"first-name\")]\n    pub first_name: String,\n")
+        );
+        // Clashes with `userId` once converted
+        assert!(out.contains("    #[serde(rename = \"user_id\")]\n    pub user_id_2: i64,\n"));
+        assert!(out.contains("    #[serde(rename = \"123\")]\n    pub field_123: i64,\n"));
+        assert!(out.contains("    #[serde(rename = \"\")]\n    pub field: i64,\n"));
+    }
+
+    #[test]
+    fn keywords() {
+        let out = rust(r#"{"type": "t", "match": 1, "self": 1, "crate": true}"#);
+        assert!(out.contains("\n    pub r#type: String,\n"));
+        assert!(out.contains("\n    pub r#match: i64,\n"));
+        assert!(out.contains("    #[serde(rename = \"self\")]\n    pub self_: i64,\n"));
+        assert!(out.contains("    #[serde(rename = \"crate\")]\n    pub crate_: bool,\n"));
+        assert!(!out.contains("rename = \"type\""));
+    }
+
+    #[test]
+    fn nested_types() {
+        let out = rust(
+            r#"{"owner": {"name": "a"}, "categories": [{"id": 1}],
+                "other": {"owner": {"age": 1}, "copy": {"owner": {"name": "b"}}}}"#,
+        );
+        let structs: Vec<&str> = out
+            .lines()
+            .filter_map(|line| line.strip_prefix("pub struct "))
+            .collect();
+        assert_eq!(
+            structs,
+            [
+                "Root {",
+                "Owner {",
+                "Category {",
+                "Other {",
+                "Owner2 {",
+                "Copy {"
+            ]
+        );
+        assert!(out.contains("    pub categories: Vec<Category>,\n"));
+        assert!(out.contains("    pub owner: Owner2,\n"));
+        // An identical shape reuses the existing type
+        assert!(out.contains("pub struct Copy {\n    pub owner: Owner,\n}"));
+    }
+
+    #[test]
+    fn arrays() {
+        let json = r#"{"mixed": [1, "a"], "nullable": [1, null], "nums": [1, 2.5],
+            "big": [1, 18446744073709551615], "empty": [], "obj": {}, "nested": [[1]]}"#;
+        let out = rust(json);
+        assert!(out.contains("    pub mixed: Vec<serde_json::Value>,\n"));
+        assert!(out.contains("    pub nullable: Vec<Option<i64>>,\n"));
+        assert!(out.contains("    pub nums: Vec<f64>,\n"));
+        assert!(out.contains("    pub big: Vec<u64>,\n"));
+        assert!(out.contains("    pub empty: Vec<serde_json::Value>,\n"));
+        assert!(out.contains("    pub obj: serde_json::Map<String, serde_json::Value>,\n"));
+        assert!(out.contains("    pub nested: Vec<Vec<i64>>,\n"));
+
+        let out = typescript(json);
+        assert!(out.contains("  mixed: unknown[];\n"));
+        assert!(out.contains("  nullable: (number | null)[];\n"));
+        assert!(out.contains("  empty: unknown[];\n"));
+        assert!(out.contains("  obj: Record<string, unknown>;\n"));
+        assert!(out.contains("  nested: number[][];\n"));
+        assert_eq!(
+            rust("[1, 2]"),
+            "use serde::{Deserialize, Serialize};\n\npub type Root = Vec<i64>;\n"
+        );
+    }
+
+    #[test]
+    fn typescript_interfaces() {
+        assert_eq!(
+            typescript(SAMPLES),
+            "export type Root = RootItem[];
+
+export interface RootItem {
+  id: number;
+  name?: string;
+  tag: string | null;
+  score?: number;
+}
+"
+        );
+        let out = typescript(r#"{"first-name": "x", "$ref": 1, "type": {"a": true}, "": null}"#);
+        assert_eq!(
+            out,
+            "export interface Root {
+  \"first-name\": string;
+  $ref: number;
+  type: Type;
+  \"\": null;
+}
+
+export interface Type {
+  a: boolean;
+}
+"
+        );
+    }
+}
@@ -1,9 +1,16 @@
+pub mod binary;
 pub mod components;
+pub mod convert;
+pub mod encoding;
+pub mod hash;
+pub mod json;
+pub mod jwt;
 pub mod pages;
+pub mod qr;
 pub mod server;
 pub mod types;
 
 pub use components::*;
 pub use pages::*;
 pub use server::*;
-pub use types::*;
\ No newline at end of file
+pub use types::*;
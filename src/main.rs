@@ -1,13 +1,18 @@
 use dioxus::document;
 use dioxus::prelude::*;
 
+mod binary;
 mod components;
+mod convert;
+mod encoding;
+mod hash;
+mod json;
+mod jwt;
 mod pages;
+mod qr;
 mod server;
 mod types;
 
-use components::Navbar;
-use pages::{Blog, Home, NotFound, Tools};
 use types::*;
 
 const FAVICON: Asset = asset!("/assets/favicon.ico");
@@ -48,7 +53,8 @@ fn main() {
     println!("🎯 Routes configured:");
     println!("  - GET / -> Home");
     println!("  - GET /blog/:id -> Blog");
-    println!("  - GET /tools -> Tools");
+    println!("  - GET /tools -> Tools (redirects to first tool)");
+    println!("  - GET /tools/:id?query -> Tool");
     println!("  - GET /* -> NotFound (catch-all)");
 
     println!("📁 Assets configured:");
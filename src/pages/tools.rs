@@ -1,4 +1,5 @@
-use crate::components::tools::{Base64Tool, JsonFormatter, QRGenerator, TextUtilities};
+use crate::components::tools::{find_tool, DynTool, ToolState, TOOLS};
+use crate::types::{Route, ToolQuery};
 use dioxus::prelude::*;
 
 // ============================================================================
@@ -13,68 +14,33 @@ struct TabInfo {
     subtitle: &'static str,
 }
 
-const TABS: [TabInfo; 4] = [
-    TabInfo {
-        id: "json",
-        icon: "📄",
-        title: "JSON",
-        subtitle: "Format & Validate",
-    },
-    TabInfo {
-        id: "text",
-        icon: "Aa",
-        title: "Text",
-        subtitle: "Transform & Count",
-    },
-    TabInfo {
-        id: "qr",
-        icon: "⚡",
-        title: "QR Code",
-        subtitle: "Generate Codes",
-    },
-    TabInfo {
-        id: "base64",
-        icon: "🔒",
-        title: "Base64",
-        subtitle: "Encode & Decode",
-    },
-];
+impl TabInfo {
+    fn from_tool(tool: &dyn DynTool) -> Self {
+        Self {
+            id: tool.id(),
+            icon: tool.icon(),
+            title: tool.title(),
+            subtitle: tool.subtitle(),
+        }
+    }
+}
 
 // ============================================================================
 // BUSINESS LOGIC
 // ============================================================================
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Copy, PartialEq)]
 struct ToolsState {
-    active_tab: Signal<String>,
     is_initialized: Signal<bool>,
-    json_input: Signal<String>,
-    json_output: Signal<String>,
-    json_error: Signal<String>,
-    text_input: Signal<String>,
-    word_count: Signal<i32>,
-    char_count: Signal<i32>,
-    qr_text: Signal<String>,
-    qr_url: Signal<String>,
-    base64_input: Signal<String>,
-    base64_output: Signal<String>,
+    /// One state per entry in [`TOOLS`], in registry order
+    tool_states: Signal<Vec<ToolState>>,
 }
 
 impl ToolsState {
     fn new() -> Self {
         Self {
-            active_tab: use_signal(|| "json".to_string()),
             is_initialized: use_signal(|| false),
-            json_input: use_signal(|| String::new()),
-            json_output: use_signal(|| String::new()),
-            json_error: use_signal(|| String::new()),
-            text_input: use_signal(|| String::new()),
-            word_count: use_signal(|| 0),
-            char_count: use_signal(|| 0),
-            qr_text: use_signal(|| String::new()),
-            qr_url: use_signal(|| String::new()),
-            base64_input: use_signal(|| String::new()),
-            base64_output: use_signal(|| String::new()),
+            tool_states: use_signal(|| TOOLS.iter().map(|tool| tool.create_state()).collect()),
         }
     }
 
@@ -88,8 +54,34 @@ impl ToolsState {
         });
     }
 
-    fn get_active_tab(&self) -> String {
-        (self.active_tab)()
+    /// Loads a deep link's query into the matching tool's state. This runs
+    /// in the route effect, so nothing here may subscribe to tool signals.
+    fn apply_route(&self, id: &str, query: &ToolQuery) {
+        if let Some((index, tool)) = find_tool(id) {
+            tool.apply_query(&self.tool_states.peek()[index], query);
+        }
+    }
+
+    /// Route for `id` carrying that tool's current state
+    fn route_for(&self, id: &str) -> Route {
+        let query = find_tool(id)
+            .map(|(index, tool)| tool.to_query(&self.tool_states.read()[index]))
+            .unwrap_or_default();
+        Route::Tool {
+            id: id.to_string(),
+            query,
+        }
+    }
+
+    /// Switches tabs through the router so the back button works. The
+    /// current entry is refreshed first so going back restores edits.
+    fn switch_tab(&self, from: &str, to: &str) {
+        if from == to {
+            return;
+        }
+        let nav = navigator();
+        nav.replace(self.route_for(from));
+        nav.push(self.route_for(to));
     }
 
     fn is_initialized(&self) -> bool {
@@ -101,11 +93,32 @@ impl ToolsState {
 // VIEW COMPONENTS
 // ============================================================================
 
+/// `/tools` forwards to the first registered tool
 #[component]
 pub fn Tools() -> Element {
+    let nav = navigator();
+    use_effect(move || {
+        nav.replace(Route::Tool {
+            id: TOOLS[0].id().to_string(),
+            query: ToolQuery::default(),
+        });
+    });
+
+    rsx! {
+        LoadingView {}
+    }
+}
+
+#[component]
+pub fn Tool(id: String, query: ToolQuery) -> Element {
     let mut state = ToolsState::new();
     state.initialize();
 
+    // Re-applied on every route change, including back/forward navigation
+    use_effect(use_reactive((&id, &query), move |(id, query)| {
+        state.apply_route(&id, &query)
+    }));
+
     if !state.is_initialized() {
         return rsx! {
             LoadingView {}
@@ -115,8 +128,8 @@ pub fn Tools() -> Element {
     rsx! {
         div { class: "min-h-screen pt-20 pb-16",
             PageHeader {}
-            TabSelector { state: state.clone() }
-            ToolContent { state }
+            TabSelector { state, active_tab: id.clone() }
+            ToolContent { state, active_tab: id }
         }
     }
 }
@@ -146,18 +159,18 @@ fn PageHeader() -> Element {
 }
 
 #[component]
-fn TabSelector(state: ToolsState) -> Element {
-    let active_tab = state.get_active_tab();
-    let mut active_tab_signal = state.active_tab;
-
+fn TabSelector(state: ToolsState, active_tab: String) -> Element {
     rsx! {
         div { class: "max-w-4xl mx-auto px-6 mb-8",
             div { class: "grid grid-cols-2 lg:grid-cols-4 gap-3",
-                for tab in TABS {
+                for tab in TOOLS.iter().map(|tool| TabInfo::from_tool(*tool)) {
                     TabButton {
                         tab: tab.clone(),
                         is_active: active_tab == tab.id,
-                        on_click: move |tab_id| active_tab_signal.set(tab_id),
+                        on_click: {
+                            let active_tab = active_tab.clone();
+                            move |tab_id: String| state.switch_tab(&active_tab, &tab_id)
+                        },
                     }
                 }
             }
@@ -188,9 +201,7 @@ fn TabButton(tab: TabInfo, is_active: bool, on_click: EventHandler<String>) -> E
 }
 
 #[component]
-fn ToolContent(state: ToolsState) -> Element {
-    let active_tab = state.get_active_tab();
-
+fn ToolContent(state: ToolsState, active_tab: String) -> Element {
     rsx! {
         div { class: "max-w-4xl mx-auto px-6",
             div { class: "bg-ctp-surface0 border border-ctp-surface1 rounded-xl p-6",
@@ -202,41 +213,17 @@ fn ToolContent(state: ToolsState) -> Element {
 
 #[component]
 fn ToolRenderer(state: ToolsState, active_tab: String) -> Element {
-    match active_tab.as_str() {
-        "json" => rsx! {
-            div { class: "animate-fade-in",
-                JsonFormatter {
-                    input: state.json_input,
-                    output: state.json_output,
-                    error: state.json_error,
-                }
-            }
-        },
-        "text" => rsx! {
-            div { class: "animate-fade-in",
-                TextUtilities {
-                    input: state.text_input,
-                    word_count: state.word_count,
-                    char_count: state.char_count,
-                }
-            }
-        },
-        "qr" => rsx! {
-            div { class: "animate-fade-in",
-                QRGenerator { input: state.qr_text, qr_url: state.qr_url }
-            }
-        },
-        "base64" => rsx! {
-            div { class: "animate-fade-in",
-                Base64Tool { input: state.base64_input, output: state.base64_output }
-            }
-        },
-        _ => rsx! {
+    let Some((index, tool)) = find_tool(&active_tab) else {
+        return rsx! {
             div { class: "text-center text-ctp-subtext0 py-16 animate-fade-in",
                 div { class: "text-6xl mb-4 opacity-50", "🔧" }
                 p { class: "text-xl font-light", "Tool not found" }
             }
-        },
+        };
+    };
+    let tool_state = state.tool_states.read()[index].clone();
+
+    rsx! {
+        div { key: "{active_tab}", class: "animate-fade-in", {tool.render(&tool_state)} }
     }
 }
-
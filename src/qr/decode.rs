@@ -0,0 +1,58 @@
+//! Reading QR codes back out of PNG/JPEG images, entirely in-process.
+
+use super::EcLevel;
+
+/// Contents and metadata of a decoded QR code
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedQr {
+    pub text: String,
+    /// Symbol version (1-40)
+    pub version: usize,
+    pub ecc: EcLevel,
+}
+
+/// Decodes every QR code found in an encoded PNG or JPEG image
+pub fn decode_qr_image(bytes: &[u8]) -> Result<Vec<DecodedQr>, String> {
+    let image = image::load_from_memory(bytes)
+        .map_err(|e| format!("Could not read image: {}", e))?
+        .to_luma8();
+
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        image.width() as usize,
+        image.height() as usize,
+        |x, y| image.get_pixel(x as u32, y as u32).0[0],
+    );
+
+    let grids = prepared.detect_grids();
+    if grids.is_empty() {
+        return Err("No QR code found in image".to_string());
+    }
+
+    let mut errors = Vec::new();
+    let mut decoded = Vec::new();
+    for grid in grids {
+        match grid.decode() {
+            Ok((meta, text)) => decoded.push(DecodedQr {
+                text,
+                version: meta.version.0,
+                // Raw format bits: 00 = M, 01 = L, 10 = H, 11 = Q
+                ecc: match meta.ecc_level {
+                    0 => EcLevel::M,
+                    1 => EcLevel::L,
+                    2 => EcLevel::H,
+                    _ => EcLevel::Q,
+                },
+            }),
+            Err(e) => errors.push(e.to_string()),
+        }
+    }
+
+    if decoded.is_empty() {
+        Err(format!(
+            "Found a QR code but could not decode it: {}",
+            errors.join(", ")
+        ))
+    } else {
+        Ok(decoded)
+    }
+}
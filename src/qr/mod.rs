@@ -0,0 +1,130 @@
+//! QR code payloads, rendering and decoding.
+//!
+//! Payloads are built and uploads decoded in the browser. Rendering needs the
+//! `server` feature and runs in the app's own server function, so payloads
+//! are never sent to a third-party service.
+
+pub mod decode;
+pub mod payload;
+#[cfg(feature = "server")]
+pub mod render;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// QR error correction level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EcLevel {
+    /// Recovers ~7% of damaged codewords
+    L,
+    /// Recovers ~15% of damaged codewords
+    #[default]
+    M,
+    /// Recovers ~25% of damaged codewords
+    Q,
+    /// Recovers ~30% of damaged codewords
+    H,
+}
+
+impl EcLevel {
+    pub const ALL: [EcLevel; 4] = [EcLevel::L, EcLevel::M, EcLevel::Q, EcLevel::H];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EcLevel::L => "L",
+            EcLevel::M => "M",
+            EcLevel::Q => "Q",
+            EcLevel::H => "H",
+        }
+    }
+}
+
+impl fmt::Display for EcLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for EcLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "L" => Ok(EcLevel::L),
+            "M" => Ok(EcLevel::M),
+            "Q" => Ok(EcLevel::Q),
+            "H" => Ok(EcLevel::H),
+            _ => Err(format!("Unknown error correction level: {}", s)),
+        }
+    }
+}
+
+/// Visual options for rendered codes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QrStyle {
+    /// Pixels per module in the PNG (and nominal SVG size)
+    pub module_size: u32,
+    /// Quiet zone around the symbol, in modules
+    pub margin: u32,
+    /// Hex color of dark modules, `#rgb` or `#rrggbb`
+    pub dark: String,
+    /// Hex color of light modules and the quiet zone
+    pub light: String,
+}
+
+impl Default for QrStyle {
+    fn default() -> Self {
+        Self {
+            module_size: 8,
+            margin: 4,
+            dark: "#000000".to_string(),
+            light: "#ffffff".to_string(),
+        }
+    }
+}
+
+/// Parses `#rgb` or `#rrggbb` (the `#` is optional)
+pub fn parse_hex_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.trim().trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    match hex.len() {
+        3 => {
+            let mut rgb = [0; 3];
+            for (i, c) in hex.chars().enumerate() {
+                rgb[i] = channel(&c.to_string().repeat(2))?;
+            }
+            Some(rgb)
+        }
+        6 => Some([
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        ]),
+        _ => None,
+    }
+}
+
+/// A rendered QR code in both vector and raster form
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QrImage {
+    pub svg: String,
+    pub png: Vec<u8>,
+    /// Symbol version (1-40)
+    pub version: i16,
+    pub ecc: EcLevel,
+}
+
+impl QrImage {
+    /// `data:` URI suitable for an `img` `src`
+    pub fn svg_data_uri(&self) -> String {
+        use base64::{engine::general_purpose, Engine as _};
+        format!(
+            "data:image/svg+xml;base64,{}",
+            general_purpose::STANDARD.encode(self.svg.as_bytes())
+        )
+    }
+}
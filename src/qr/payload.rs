@@ -0,0 +1,969 @@
+//! Structured QR payloads (Wi-Fi, contacts, messages, locations, events).
+//!
+//! Each [`Template`] declares its form fields and builds a correctly escaped
+//! payload from the submitted values. [`PayloadKind::detect`] goes the other
+//! way and classifies an arbitrary payload string.
+
+use chrono::NaiveDateTime;
+use std::collections::BTreeMap;
+
+/// Values submitted for a template's fields, keyed by [`FieldSpec::key`]
+pub type FieldValues = BTreeMap<String, String>;
+
+/// Input control used for a field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Text,
+    Multiline,
+    Email,
+    Tel,
+    Url,
+    Number,
+    DateTime,
+    Password,
+    /// One of a fixed set of `(value, label)` choices
+    Choice(&'static [(&'static str, &'static str)]),
+}
+
+impl FieldKind {
+    /// HTML `input` type for single-line controls
+    pub fn input_type(self) -> &'static str {
+        match self {
+            FieldKind::Email => "email",
+            FieldKind::Tel => "tel",
+            FieldKind::Url => "url",
+            FieldKind::Number => "number",
+            FieldKind::DateTime => "datetime-local",
+            FieldKind::Password => "password",
+            FieldKind::Text | FieldKind::Multiline | FieldKind::Choice(_) => "text",
+        }
+    }
+}
+
+/// Describes one form field of a template
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldSpec {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub placeholder: &'static str,
+    pub kind: FieldKind,
+    pub required: bool,
+}
+
+const fn field(
+    key: &'static str,
+    label: &'static str,
+    placeholder: &'static str,
+    kind: FieldKind,
+    required: bool,
+) -> FieldSpec {
+    FieldSpec {
+        key,
+        label,
+        placeholder,
+        kind,
+        required,
+    }
+}
+
+const WIFI_SECURITY: &[(&str, &str)] = &[("WPA", "WPA/WPA2"), ("WEP", "WEP"), ("nopass", "None")];
+const YES_NO: &[(&str, &str)] = &[("false", "No"), ("true", "Yes")];
+
+const WIFI_FIELDS: &[FieldSpec] = &[
+    field(
+        "ssid",
+        "Network name (SSID)",
+        "OfficeNet",
+        FieldKind::Text,
+        true,
+    ),
+    field(
+        "security",
+        "Security",
+        "",
+        FieldKind::Choice(WIFI_SECURITY),
+        false,
+    ),
+    field(
+        "password",
+        "Password",
+        "••••••••",
+        FieldKind::Password,
+        false,
+    ),
+    field(
+        "hidden",
+        "Hidden network",
+        "",
+        FieldKind::Choice(YES_NO),
+        false,
+    ),
+];
+
+const CONTACT_FIELDS: &[FieldSpec] = &[
+    field("first", "First name", "Ada", FieldKind::Text, false),
+    field("last", "Last name", "Lovelace", FieldKind::Text, true),
+    field(
+        "org",
+        "Organization",
+        "Analytical Engines Ltd",
+        FieldKind::Text,
+        false,
+    ),
+    field("phone", "Phone", "+44 20 7946 0000", FieldKind::Tel, false),
+    field("email", "Email", "ada@example.com", FieldKind::Email, false),
+    field(
+        "url",
+        "Website",
+        "https://example.com",
+        FieldKind::Url,
+        false,
+    ),
+    field(
+        "address",
+        "Address",
+        "12 St James's Square, London",
+        FieldKind::Text,
+        false,
+    ),
+    field("note", "Note", "", FieldKind::Multiline, false),
+];
+
+const EMAIL_FIELDS: &[FieldSpec] = &[
+    field("to", "To", "team@example.com", FieldKind::Email, true),
+    field("subject", "Subject", "Hello", FieldKind::Text, false),
+    field("body", "Body", "", FieldKind::Multiline, false),
+];
+
+const SMS_FIELDS: &[FieldSpec] = &[
+    field(
+        "number",
+        "Phone number",
+        "+15555550123",
+        FieldKind::Tel,
+        true,
+    ),
+    field("message", "Message", "", FieldKind::Multiline, false),
+];
+
+const GEO_FIELDS: &[FieldSpec] = &[
+    field("lat", "Latitude", "51.5074", FieldKind::Number, true),
+    field("lon", "Longitude", "-0.1278", FieldKind::Number, true),
+    field("label", "Label", "Conference venue", FieldKind::Text, false),
+];
+
+const EVENT_FIELDS: &[FieldSpec] = &[
+    field(
+        "summary",
+        "Title",
+        "RustConf keynote",
+        FieldKind::Text,
+        true,
+    ),
+    field("start", "Starts", "", FieldKind::DateTime, true),
+    field("end", "Ends", "", FieldKind::DateTime, false),
+    field("location", "Location", "Main hall", FieldKind::Text, false),
+    field(
+        "description",
+        "Description",
+        "",
+        FieldKind::Multiline,
+        false,
+    ),
+];
+
+/// Payload builders offered by the QR tool
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Template {
+    #[default]
+    Text,
+    Wifi,
+    VCard,
+    MeCard,
+    Email,
+    Sms,
+    Geo,
+    Event,
+}
+
+impl Template {
+    pub const ALL: [Template; 8] = [
+        Template::Text,
+        Template::Wifi,
+        Template::VCard,
+        Template::MeCard,
+        Template::Email,
+        Template::Sms,
+        Template::Geo,
+        Template::Event,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Template::Text => "text",
+            Template::Wifi => "wifi",
+            Template::VCard => "vcard",
+            Template::MeCard => "mecard",
+            Template::Email => "email",
+            Template::Sms => "sms",
+            Template::Geo => "geo",
+            Template::Event => "event",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Template::Text => "Text/URL",
+            Template::Wifi => "Wi-Fi",
+            Template::VCard => "vCard",
+            Template::MeCard => "MECARD",
+            Template::Email => "Email",
+            Template::Sms => "SMS",
+            Template::Geo => "Location",
+            Template::Event => "Event",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.id() == id)
+    }
+
+    /// Form fields, empty for free text
+    pub fn fields(self) -> &'static [FieldSpec] {
+        match self {
+            Template::Text => &[],
+            Template::Wifi => WIFI_FIELDS,
+            Template::VCard | Template::MeCard => CONTACT_FIELDS,
+            Template::Email => EMAIL_FIELDS,
+            Template::Sms => SMS_FIELDS,
+            Template::Geo => GEO_FIELDS,
+            Template::Event => EVENT_FIELDS,
+        }
+    }
+
+    /// Validates `values` and builds the escaped payload. Free text has no
+    /// form and is edited directly, so it builds an empty payload.
+    pub fn build(self, values: &FieldValues) -> Result<String, Vec<String>> {
+        let values = Values(values);
+
+        let mut errors: Vec<String> = self
+            .fields()
+            .iter()
+            .filter(|f| f.required && values.get(f.key).is_empty())
+            .map(|f| format!("{} is required", f.label))
+            .collect();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let payload = match self {
+            Template::Text => String::new(),
+            Template::Wifi => {
+                let security = match values.get("security") {
+                    "" => "WPA",
+                    s => s,
+                };
+                // Spaces around an SSID or password are part of it
+                let password = values.raw("password");
+                if security != "nopass" && values.get("password").is_empty() {
+                    errors.push("Password is required for secured networks".to_string());
+                }
+                let length = password.chars().count();
+                if security == "WPA"
+                    && !values.get("password").is_empty()
+                    && !(8..=63).contains(&length)
+                {
+                    errors.push("WPA passwords must be 8 to 63 characters".to_string());
+                }
+                let mut out = format!("WIFI:T:{};S:{};", security, escape_wifi(values.raw("ssid")));
+                if security != "nopass" {
+                    out.push_str(&format!("P:{};", escape_wifi(password)));
+                }
+                if values.get("hidden") == "true" {
+                    out.push_str("H:true;");
+                }
+                out.push(';');
+                out
+            }
+            Template::VCard => {
+                validate_contact(&values, &mut errors);
+                let (first, last) = (values.get("first"), values.get("last"));
+                let full_name = [first, last]
+                    .iter()
+                    .filter(|s| !s.is_empty())
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let mut lines = vec![
+                    "BEGIN:VCARD".to_string(),
+                    "VERSION:3.0".to_string(),
+                    format!("N:{};{};;;", escape_ical(last), escape_ical(first)),
+                    format!("FN:{}", escape_ical(&full_name)),
+                ];
+                let optional = [
+                    ("ORG", "org"),
+                    ("TEL;TYPE=CELL", "phone"),
+                    ("EMAIL", "email"),
+                    ("URL", "url"),
+                    ("NOTE", "note"),
+                ];
+                for (name, key) in optional {
+                    if !values.get(key).is_empty() {
+                        lines.push(format!("{}:{}", name, escape_ical(values.get(key))));
+                    }
+                }
+                if !values.get("address").is_empty() {
+                    lines.push(format!("ADR:;;{};;;;", escape_ical(values.get("address"))));
+                }
+                lines.push("END:VCARD".to_string());
+                lines.join("\r\n")
+            }
+            Template::MeCard => {
+                validate_contact(&values, &mut errors);
+                let name = match values.get("first") {
+                    "" => escape_mecard(values.get("last")),
+                    first => format!(
+                        "{},{}",
+                        escape_mecard(values.get("last")),
+                        escape_mecard(first)
+                    ),
+                };
+                let mut out = format!("MECARD:N:{};", name);
+                let optional = [
+                    ("ORG", "org"),
+                    ("TEL", "phone"),
+                    ("EMAIL", "email"),
+                    ("URL", "url"),
+                    ("ADR", "address"),
+                    ("NOTE", "note"),
+                ];
+                for (name, key) in optional {
+                    if !values.get(key).is_empty() {
+                        out.push_str(&format!("{}:{};", name, escape_mecard(values.get(key))));
+                    }
+                }
+                out.push(';');
+                out
+            }
+            Template::Email => {
+                let to = values.get("to");
+                if !is_email(to) {
+                    errors.push(format!("'{}' is not a valid email address", to));
+                }
+                let params: Vec<String> = [
+                    ("subject", values.get("subject")),
+                    ("body", values.get("body")),
+                ]
+                .into_iter()
+                .filter(|(_, v)| !v.is_empty())
+                .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
+                .collect();
+                let recipient: Vec<String> = to
+                    .split('@')
+                    .map(|part| urlencoding::encode(part).into_owned())
+                    .collect();
+                let recipient = recipient.join("@");
+                if params.is_empty() {
+                    format!("mailto:{}", recipient)
+                } else {
+                    format!("mailto:{}?{}", recipient, params.join("&"))
+                }
+            }
+            Template::Sms => {
+                let number = values.get("number");
+                if !is_phone(number) {
+                    errors.push(format!("'{}' is not a valid phone number", number));
+                }
+                format!("SMSTO:{}:{}", strip_phone(number), values.get("message"))
+            }
+            Template::Geo => {
+                let lat = parse_coordinate(values.get("lat"), 90.0, "Latitude", &mut errors);
+                let lon = parse_coordinate(values.get("lon"), 180.0, "Longitude", &mut errors);
+                match values.get("label") {
+                    "" => format!("geo:{},{}", lat, lon),
+                    label => format!("geo:{},{}?q={}", lat, lon, urlencoding::encode(label)),
+                }
+            }
+            Template::Event => {
+                let start = parse_datetime(values.get("start"), "Start", &mut errors);
+                let end = match values.get("end") {
+                    "" => None,
+                    end => parse_datetime(end, "End", &mut errors),
+                };
+                if let (Some(start), Some(end)) = (start, end) {
+                    if end < start {
+                        errors.push("Event cannot end before it starts".to_string());
+                    }
+                }
+                let format_dt = |dt: NaiveDateTime| dt.format("%Y%m%dT%H%M%S").to_string();
+                // Scanners only accept events inside a calendar object
+                let mut lines = vec![
+                    "BEGIN:VCALENDAR".to_string(),
+                    "VERSION:2.0".to_string(),
+                    "BEGIN:VEVENT".to_string(),
+                    format!("SUMMARY:{}", escape_ical(values.get("summary"))),
+                ];
+                if let Some(start) = start {
+                    lines.push(format!("DTSTART:{}", format_dt(start)));
+                }
+                if let Some(end) = end {
+                    lines.push(format!("DTEND:{}", format_dt(end)));
+                }
+                for (name, key) in [("LOCATION", "location"), ("DESCRIPTION", "description")] {
+                    if !values.get(key).is_empty() {
+                        lines.push(format!("{}:{}", name, escape_ical(values.get(key))));
+                    }
+                }
+                lines.push("END:VEVENT".to_string());
+                lines.push("END:VCALENDAR".to_string());
+                lines.join("\r\n")
+            }
+        };
+
+        if errors.is_empty() {
+            Ok(payload)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Access to submitted field values
+struct Values<'a>(&'a FieldValues);
+
+impl<'a> Values<'a> {
+    /// The value with surrounding whitespace trimmed
+    fn get(&self, key: &str) -> &'a str {
+        self.raw(key).trim()
+    }
+
+    /// The value exactly as entered
+    fn raw(&self, key: &str) -> &'a str {
+        self.0.get(key).map(String::as_str).unwrap_or_default()
+    }
+}
+
+/// What a payload string encodes, as recognized by scanners
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadKind {
+    Text,
+    Url,
+    Wifi,
+    VCard,
+    MeCard,
+    Email,
+    Sms,
+    Phone,
+    Geo,
+    Event,
+}
+
+impl PayloadKind {
+    pub fn detect(payload: &str) -> Self {
+        let trimmed = payload.trim_start();
+        let upper: String = trimmed.chars().take(16).collect::<String>().to_uppercase();
+        let starts = |prefix: &str| upper.starts_with(prefix);
+
+        if starts("WIFI:") {
+            PayloadKind::Wifi
+        } else if starts("BEGIN:VCARD") {
+            PayloadKind::VCard
+        } else if starts("MECARD:") {
+            PayloadKind::MeCard
+        } else if starts("MAILTO:") || starts("MATMSG:") {
+            PayloadKind::Email
+        } else if starts("SMSTO:") || starts("SMS:") {
+            PayloadKind::Sms
+        } else if starts("TEL:") {
+            PayloadKind::Phone
+        } else if starts("GEO:") {
+            PayloadKind::Geo
+        } else if starts("BEGIN:VEVENT") || starts("BEGIN:VCALENDAR") {
+            PayloadKind::Event
+        } else if starts("HTTP://") || starts("HTTPS://") {
+            PayloadKind::Url
+        } else {
+            PayloadKind::Text
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PayloadKind::Text => "Text",
+            PayloadKind::Url => "URL",
+            PayloadKind::Wifi => "Wi-Fi",
+            PayloadKind::VCard => "vCard",
+            PayloadKind::MeCard => "MECARD",
+            PayloadKind::Email => "Email",
+            PayloadKind::Sms => "SMS",
+            PayloadKind::Phone => "Phone",
+            PayloadKind::Geo => "Location",
+            PayloadKind::Event => "Calendar event",
+        }
+    }
+}
+
+/// Suggests a download filename such as `qr-wifi-OfficeNet.svg`
+pub fn suggested_filename(payload: &str, extension: &str) -> String {
+    let kind = PayloadKind::detect(payload);
+    let slug = payload_title(kind, payload.trim())
+        .map(|title| slugify(&title))
+        .unwrap_or_default();
+    if slug.is_empty() {
+        format!("qr-{}.{}", kind.slug(), extension)
+    } else {
+        format!("qr-{}-{}.{}", kind.slug(), slug, extension)
+    }
+}
+
+impl PayloadKind {
+    /// Short lowercase name used in filenames
+    pub fn slug(self) -> &'static str {
+        match self {
+            PayloadKind::Text => "text",
+            PayloadKind::Url => "url",
+            PayloadKind::Wifi => "wifi",
+            PayloadKind::VCard => "vcard",
+            PayloadKind::MeCard => "mecard",
+            PayloadKind::Email => "email",
+            PayloadKind::Sms => "sms",
+            PayloadKind::Phone => "tel",
+            PayloadKind::Geo => "geo",
+            PayloadKind::Event => "event",
+        }
+    }
+}
+
+/// The most recognizable part of a payload (SSID, contact name, host...)
+fn payload_title(kind: PayloadKind, payload: &str) -> Option<String> {
+    // Everything after the scheme prefix, e.g. `WIFI:` or `mailto:`
+    let body = || payload.split_once(':').map(|(_, rest)| rest).unwrap_or("");
+    match kind {
+        PayloadKind::Wifi => scheme_field(body(), "S"),
+        PayloadKind::MeCard => scheme_field(body(), "N").map(|name| {
+            let mut parts: Vec<&str> = name.split(',').map(str::trim).collect();
+            parts.reverse();
+            parts.join(" ")
+        }),
+        PayloadKind::VCard => content_line(payload, "FN"),
+        PayloadKind::Event => content_line(payload, "SUMMARY"),
+        PayloadKind::Email | PayloadKind::Phone | PayloadKind::Geo => {
+            body().split('?').next().map(str::to_string)
+        }
+        PayloadKind::Sms => body().split(':').next().map(str::to_string),
+        PayloadKind::Url => body()
+            .trim_start_matches('/')
+            .split(['/', '?', '#'])
+            .next()
+            .map(str::to_string),
+        PayloadKind::Text => Some(
+            payload
+                .split_whitespace()
+                .take(4)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+    }
+}
+
+/// Reads `KEY:value;` from a `WIFI:`/`MECARD:` body, honoring `\` escapes
+fn scheme_field(body: &str, key: &str) -> Option<String> {
+    let mut field = String::new();
+    let mut escaped = false;
+    for c in body.chars().chain(std::iter::once(';')) {
+        if escaped {
+            field.push(c);
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == ';' {
+            if let Some(value) = field.strip_prefix(key).and_then(|f| f.strip_prefix(':')) {
+                return Some(value.to_string());
+            }
+            field.clear();
+        } else {
+            field.push(c);
+        }
+    }
+    None
+}
+
+/// Reads an unescaped `NAME:value` line from vCard/iCalendar text
+fn content_line(payload: &str, name: &str) -> Option<String> {
+    payload.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        let key = key.split(';').next()?;
+        key.eq_ignore_ascii_case(name).then(|| {
+            value
+                .replace("\\n", " ")
+                .replace("\\,", ",")
+                .replace("\\;", ";")
+                .replace("\\\\", "\\")
+        })
+    })
+}
+
+/// Keeps filename-safe characters and collapses the rest into `-`
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() || c == '_' || c == '.' || c == '@' {
+            slug.push(c);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug.chars().take(40).collect();
+    slug.trim_matches(['-', '.']).to_string()
+}
+
+/// Escapes `\ ; , : "` as required by the `WIFI:` scheme
+fn escape_wifi(value: &str) -> String {
+    escape_with(value, &['\\', ';', ',', ':', '"'])
+}
+
+/// Escapes `\ ; , :` as required by `MECARD:`
+fn escape_mecard(value: &str) -> String {
+    escape_with(value, &['\\', ';', ',', ':'])
+}
+
+fn escape_with(value: &str, special: &[char]) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if special.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Escapes text values for vCard 3.0 and iCalendar (RFC 6350 / RFC 5545)
+fn escape_ical(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn validate_contact(values: &Values, errors: &mut Vec<String>) {
+    let (email, phone) = (values.get("email"), values.get("phone"));
+    if !email.is_empty() && !is_email(email) {
+        errors.push(format!("'{}' is not a valid email address", email));
+    }
+    if !phone.is_empty() && !is_phone(phone) {
+        errors.push(format!("'{}' is not a valid phone number", phone));
+    }
+}
+
+fn is_email(value: &str) -> bool {
+    match value.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !value.contains(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+fn is_phone(value: &str) -> bool {
+    let digits = strip_phone(value);
+    let digits = digits.strip_prefix('+').unwrap_or(&digits);
+    (3..=15).contains(&digits.len()) && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Drops the separators people type into phone numbers
+fn strip_phone(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '(' | ')' | '.'))
+        .collect()
+}
+
+fn parse_coordinate(value: &str, limit: f64, label: &str, errors: &mut Vec<String>) -> f64 {
+    match value.parse::<f64>() {
+        Ok(v) if v.is_finite() && v.abs() <= limit => v,
+        Ok(_) => {
+            errors.push(format!(
+                "{} must be between -{} and {}",
+                label, limit, limit
+            ));
+            0.0
+        }
+        Err(_) => {
+            errors.push(format!("{} must be a number", label));
+            0.0
+        }
+    }
+}
+
+/// Parses the `datetime-local` input format (`2024-05-01T09:30`)
+fn parse_datetime(value: &str, label: &str, errors: &mut Vec<String>) -> Option<NaiveDateTime> {
+    let parsed = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S"));
+    match parsed {
+        Ok(dt) => Some(dt),
+        Err(_) => {
+            errors.push(format!(
+                "{} time '{}' is not a valid date and time",
+                label, value
+            ));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(template: Template, values: &[(&str, &str)]) -> Result<String, Vec<String>> {
+        let values: FieldValues = values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        template.build(&values)
+    }
+
+    #[test]
+    fn wifi_escaping() {
+        assert_eq!(
+            build(
+                Template::Wifi,
+                &[
+                    ("ssid", r#"Cafe;Bar,"1:2"\"#),
+                    ("password", r"p@ss;w,o:r\d")
+                ]
+            ),
+            Ok(r#"WIFI:T:WPA;S:Cafe\;Bar\,\"1\:2\"\\;P:p@ss\;w\,o\:r\\d;;"#.to_string())
+        );
+        assert_eq!(
+            build(
+                Template::Wifi,
+                &[
+                    ("ssid", " Guest "),
+                    ("security", "nopass"),
+                    ("hidden", "true")
+                ]
+            ),
+            Ok("WIFI:T:nopass;S: Guest ;H:true;;".to_string())
+        );
+    }
+
+    #[test]
+    fn wifi_passwords() {
+        let wpa =
+            |password: &str| build(Template::Wifi, &[("ssid", "Net"), ("password", password)]);
+        let too_short = Err(vec!["WPA passwords must be 8 to 63 characters".to_string()]);
+        assert_eq!(wpa("1234567"), too_short);
+        // Characters, not bytes: seven of them take 14 bytes
+        assert_eq!(wpa("ééééééé"), too_short);
+        assert!(wpa("12345678").is_ok());
+        assert!(wpa(&"x".repeat(63)).is_ok());
+        assert_eq!(wpa(&"x".repeat(64)), too_short);
+        assert_eq!(
+            wpa("   "),
+            Err(vec!["Password is required for secured networks".to_string()])
+        );
+        assert!(build(
+            Template::Wifi,
+            &[("ssid", "Net"), ("security", "WEP"), ("password", "abc")]
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn mecard_escaping() {
+        assert_eq!(
+            build(
+                Template::MeCard,
+                &[
+                    ("first", "Ada"),
+                    ("last", "King, Countess"),
+                    ("org", r"A;B:C\D"),
+                    ("phone", "+44 20 7946 0000"),
+                ]
+            ),
+            Ok(r"MECARD:N:King\, Countess,Ada;ORG:A\;B\:C\\D;TEL:+44 20 7946 0000;;".to_string())
+        );
+    }
+
+    #[test]
+    fn vcard_escaping() {
+        assert_eq!(
+            build(
+                Template::VCard,
+                &[
+                    ("first", "Ada"),
+                    ("last", "Lovelace"),
+                    ("org", r"Engines, Ltd; R\D"),
+                    ("note", "line one\r\nline two"),
+                ]
+            ),
+            Ok([
+                "BEGIN:VCARD",
+                "VERSION:3.0",
+                "N:Lovelace;Ada;;;",
+                "FN:Ada Lovelace",
+                r"ORG:Engines\, Ltd\; R\\D",
+                r"NOTE:line one\nline two",
+                "END:VCARD",
+            ]
+            .join("\r\n"))
+        );
+        assert_eq!(
+            build(Template::VCard, &[("last", "X"), ("email", "not-an-email")]),
+            Err(vec![
+                "'not-an-email' is not a valid email address".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn event_escaping() {
+        assert_eq!(
+            build(
+                Template::Event,
+                &[
+                    ("summary", "Talks; Q&A, drinks"),
+                    ("start", "2024-05-01T09:30"),
+                    ("end", "2024-05-01T11:00"),
+                    ("description", "Bring\na laptop"),
+                ]
+            ),
+            Ok([
+                "BEGIN:VCALENDAR",
+                "VERSION:2.0",
+                "BEGIN:VEVENT",
+                r"SUMMARY:Talks\; Q&A\, drinks",
+                "DTSTART:20240501T093000",
+                "DTEND:20240501T110000",
+                r"DESCRIPTION:Bring\na laptop",
+                "END:VEVENT",
+                "END:VCALENDAR",
+            ]
+            .join("\r\n"))
+        );
+        assert_eq!(
+            build(
+                Template::Event,
+                &[
+                    ("summary", "Late"),
+                    ("start", "2024-05-01T09:30"),
+                    ("end", "2024-05-01T09:00")
+                ]
+            ),
+            Err(vec!["Event cannot end before it starts".to_string()])
+        );
+    }
+
+    #[test]
+    fn uri_payloads() {
+        assert_eq!(
+            build(
+                Template::Email,
+                &[("to", "a&b?c@example.com"), ("subject", "Hi there")]
+            ),
+            Ok("mailto:a%26b%3Fc@example.com?subject=Hi%20there".to_string())
+        );
+        assert_eq!(
+            build(
+                Template::Sms,
+                &[("number", "+1 (555) 555-0123"), ("message", "On my way")]
+            ),
+            Ok("SMSTO:+15555550123:On my way".to_string())
+        );
+        assert_eq!(
+            build(
+                Template::Geo,
+                &[("lat", "51.5"), ("lon", "-0.12"), ("label", "Big Ben")]
+            ),
+            Ok("geo:51.5,-0.12?q=Big%20Ben".to_string())
+        );
+        assert_eq!(
+            build(Template::Geo, &[("lat", "91"), ("lon", "x")]),
+            Err(vec![
+                "Latitude must be between -90 and 90".to_string(),
+                "Longitude must be a number".to_string(),
+            ])
+        );
+        assert_eq!(
+            build(Template::Email, &[]),
+            Err(vec!["To is required".to_string()])
+        );
+    }
+
+    #[test]
+    fn detect_round_trips() {
+        let detect = |template: Template, values: &[(&str, &str)]| {
+            PayloadKind::detect(&build(template, values).unwrap())
+        };
+        assert_eq!(
+            detect(Template::Wifi, &[("ssid", "Net"), ("security", "nopass")]),
+            PayloadKind::Wifi
+        );
+        assert_eq!(
+            detect(Template::VCard, &[("last", "Lovelace")]),
+            PayloadKind::VCard
+        );
+        assert_eq!(
+            detect(Template::MeCard, &[("last", "Lovelace")]),
+            PayloadKind::MeCard
+        );
+        assert_eq!(
+            detect(Template::Email, &[("to", "ada@example.com")]),
+            PayloadKind::Email
+        );
+        assert_eq!(
+            detect(Template::Sms, &[("number", "5550123")]),
+            PayloadKind::Sms
+        );
+        assert_eq!(
+            detect(Template::Geo, &[("lat", "0"), ("lon", "0")]),
+            PayloadKind::Geo
+        );
+        assert_eq!(
+            detect(
+                Template::Event,
+                &[("summary", "Launch"), ("start", "2024-05-01T09:30")]
+            ),
+            PayloadKind::Event
+        );
+        assert_eq!(
+            PayloadKind::detect("  https://example.com"),
+            PayloadKind::Url
+        );
+        assert_eq!(PayloadKind::detect("tel:+15555550123"), PayloadKind::Phone);
+        assert_eq!(PayloadKind::detect("hello"), PayloadKind::Text);
+    }
+
+    #[test]
+    fn filenames() {
+        let wifi = build(
+            Template::Wifi,
+            &[("ssid", r"Cafe;Bar"), ("security", "nopass")],
+        )
+        .unwrap();
+        assert_eq!(suggested_filename(&wifi, "png"), "qr-wifi-Cafe-Bar.png");
+        let event = build(
+            Template::Event,
+            &[("summary", "Launch, day 1"), ("start", "2024-05-01T09:30")],
+        )
+        .unwrap();
+        assert_eq!(
+            suggested_filename(&event, "svg"),
+            "qr-event-Launch-day-1.svg"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Rendering codes to SVG and PNG, done by the server function.
+
+use super::{parse_hex_color, EcLevel, QrImage, QrStyle};
+use qrcode::{Color, QrCode, Version};
+
+/// Largest rendered image edge, in pixels
+const MAX_IMAGE_SIZE: usize = 4096;
+
+impl EcLevel {
+    fn to_qrcode(self) -> qrcode::EcLevel {
+        match self {
+            EcLevel::L => qrcode::EcLevel::L,
+            EcLevel::M => qrcode::EcLevel::M,
+            EcLevel::Q => qrcode::EcLevel::Q,
+            EcLevel::H => qrcode::EcLevel::H,
+        }
+    }
+}
+
+impl QrStyle {
+    /// Checks ranges and colors, returning the parsed dark and light RGB values
+    fn validate(&self) -> Result<([u8; 3], [u8; 3]), String> {
+        if !(1..=32).contains(&self.module_size) {
+            return Err("Module size must be between 1 and 32 pixels".to_string());
+        }
+        if self.margin > 16 {
+            return Err("Margin must be between 0 and 16 modules".to_string());
+        }
+        let dark = parse_hex_color(&self.dark)
+            .ok_or_else(|| format!("Invalid dark color '{}' (use #rgb or #rrggbb)", self.dark))?;
+        let light = parse_hex_color(&self.light)
+            .ok_or_else(|| format!("Invalid light color '{}' (use #rgb or #rrggbb)", self.light))?;
+        Ok((dark, light))
+    }
+}
+
+/// Encodes `text` and renders it as SVG and PNG
+pub fn render_qr(text: &str, ecc: EcLevel, style: &QrStyle) -> Result<QrImage, String> {
+    let (dark, light) = style.validate()?;
+    let code = QrCode::with_error_correction_level(text.as_bytes(), ecc.to_qrcode()).map_err(
+        |e| match e {
+            qrcode::types::QrError::DataTooLong => format!(
+                "Text too long for error correction level {} ({} bytes)",
+                ecc,
+                text.len()
+            ),
+            e => format!("QR encoding error: {}", e),
+        },
+    )?;
+
+    let version = match code.version() {
+        Version::Normal(v) | Version::Micro(v) => v,
+    };
+    let modules = Modules::from_code(&code, style.margin as usize);
+    let module_size = style.module_size as usize;
+    if modules.width * module_size > MAX_IMAGE_SIZE {
+        return Err(format!(
+            "Image would be {0}x{0} px (max {1}); reduce module size or margin",
+            modules.width * module_size,
+            MAX_IMAGE_SIZE
+        ));
+    }
+
+    Ok(QrImage {
+        svg: modules.to_svg(module_size, &dark, &light),
+        png: modules.to_png(module_size, &dark, &light)?,
+        version,
+        ecc,
+    })
+}
+
+/// Dark/light module grid including the quiet zone
+struct Modules {
+    width: usize,
+    dark: Vec<bool>,
+}
+
+impl Modules {
+    fn from_code(code: &QrCode, margin: usize) -> Self {
+        let inner = code.width();
+        let width = inner + margin * 2;
+        let mut dark = vec![false; width * width];
+        for (i, color) in code.to_colors().into_iter().enumerate() {
+            let (x, y) = (i % inner + margin, i / inner + margin);
+            dark[y * width + x] = color == Color::Dark;
+        }
+        Self { width, dark }
+    }
+
+    fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.dark[y * self.width + x]
+    }
+
+    fn to_svg(&self, module_size: usize, dark: &[u8; 3], light: &[u8; 3]) -> String {
+        let size = self.width * module_size;
+        let mut path = String::new();
+        for y in 0..self.width {
+            let mut x = 0;
+            while x < self.width {
+                if !self.is_dark(x, y) {
+                    x += 1;
+                    continue;
+                }
+                // Merge horizontal runs to keep the path short
+                let start = x;
+                while x < self.width && self.is_dark(x, y) {
+                    x += 1;
+                }
+                path.push_str(&format!("M{start} {y}h{}v1h-{}z", x - start, x - start));
+            }
+        }
+
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {w} {w}" shape-rendering="crispEdges"><rect width="{w}" height="{w}" fill="{light}"/><path fill="{dark}" d="{path}"/></svg>"##,
+            w = self.width,
+            light = to_hex(light),
+            dark = to_hex(dark),
+        )
+    }
+
+    fn to_png(
+        &self,
+        module_size: usize,
+        dark: &[u8; 3],
+        light: &[u8; 3],
+    ) -> Result<Vec<u8>, String> {
+        let size = self.width * module_size;
+        let mut pixels = Vec::with_capacity(size * size * 3);
+        for py in 0..size {
+            for px in 0..size {
+                let is_dark = self.is_dark(px / module_size, py / module_size);
+                pixels.extend_from_slice(if is_dark { dark } else { light });
+            }
+        }
+
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, size as u32, size as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .map_err(|e| format!("PNG encoding error: {}", e))?;
+        Ok(bytes)
+    }
+}
+
+fn to_hex(rgb: &[u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])
+}
@@ -1,8 +1,10 @@
 #[cfg(feature = "server")]
 use dioxus::fullstack::prelude::*;
 
-use dioxus::prelude::*;
+use crate::qr::{EcLevel, QrImage, QrStyle};
+#[cfg(feature = "server")]
 use crate::types::NoCustomError;
+use dioxus::prelude::*;
 
 #[server(EchoServer)]
 pub async fn echo_server(input: String) -> Result<String, ServerFnError> {
@@ -118,12 +120,17 @@ pub async fn generate_blog_content(blog_id: i32) -> Result<(String, String), Ser
 }
 
 #[server(GenerateQRCode)]
-pub async fn generate_qr_code(text: String) -> Result<String, ServerFnError> {
+pub async fn generate_qr_code(
+    text: String,
+    ecc: EcLevel,
+    style: QrStyle,
+) -> Result<QrImage, ServerFnError> {
     let start = std::time::Instant::now();
     println!(
-        "📡 [{}] Server function called: generate_qr_code with text: '{}'",
+        "📡 [{}] Server function called: generate_qr_code with text: '{}' (ecc {})",
         chrono::Utc::now().format("%H:%M:%S%.3f"),
-        text
+        text,
+        ecc
     );
 
     if text.trim().is_empty() {
@@ -138,19 +145,17 @@ pub async fn generate_qr_code(text: String) -> Result<String, ServerFnError> {
         ));
     }
 
-    // Use a QR code generation service API (like qr-server.com)
-    let encoded_text = urlencoding::encode(&text);
-    let qr_url = format!(
-        "https://api.qrserver.com/v1/create-qr-code/?size=200x200&data={}",
-        encoded_text
-    );
+    // Encode in-process so the payload never reaches a third-party service
+    let image = crate::qr::render::render_qr(&text, ecc, &style)
+        .map_err(ServerFnError::<NoCustomError>::ServerError)?;
 
     let elapsed = start.elapsed();
     println!(
-        "📡 [{}] generate_qr_code responding after {:?} with URL: '{}'",
+        "📡 [{}] generate_qr_code responding after {:?} with version {} ({} byte PNG)",
         chrono::Utc::now().format("%H:%M:%S%.3f"),
         elapsed,
-        qr_url
+        image.version,
+        image.png.len()
     );
-    Ok(qr_url)
-}
\ No newline at end of file
+    Ok(image)
+}
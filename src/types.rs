@@ -1,5 +1,5 @@
 use crate::components::Navbar;
-use crate::pages::{Blog, Home, NotFound, Tools};
+use crate::pages::{Blog, Home, NotFound, Tool, Tools};
 use dioxus::prelude::*;
 use std::fmt;
 
@@ -14,6 +14,65 @@ impl fmt::Display for NoCustomError {
 
 impl std::error::Error for NoCustomError {}
 
+/// Query parameters carried by `/tools/:id` so tool state can be deep-linked,
+/// e.g. `/tools/base64?mode=decode&input=aGVsbG8=`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolQuery(Vec<(String, String)>);
+
+impl ToolQuery {
+    /// Returns the value for `key`, if present
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Adds a parameter, skipping empty values to keep links short
+    pub fn with(mut self, key: &str, value: impl Into<String>) -> Self {
+        let value = value.into();
+        if !value.is_empty() {
+            self.0.push((key.to_string(), value));
+        }
+        self
+    }
+}
+
+impl From<&str> for ToolQuery {
+    fn from(query: &str) -> Self {
+        let decode = |s: &str| {
+            urlencoding::decode(s)
+                .map(|s| s.into_owned())
+                .unwrap_or_else(|_| s.to_string())
+        };
+        ToolQuery(
+            query
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .map(|(k, v)| (decode(k), decode(v)))
+                .filter(|(k, _)| !k.is_empty())
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for ToolQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (key, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "&")?;
+            }
+            write!(
+                f,
+                "{}={}",
+                urlencoding::encode(key),
+                urlencoding::encode(value)
+            )?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Routable, PartialEq)]
 #[rustfmt::skip]
 pub enum Route {
@@ -24,6 +83,8 @@ pub enum Route {
     Blog { id: i32 },
     #[route("/tools")]
     Tools {},
+    #[route("/tools/:id?:..query")]
+    Tool { id: String, query: ToolQuery },
     #[route("/:..segments")]
     NotFound { segments: Vec<String> },
 }